@@ -1,5 +1,10 @@
+use bytes::Bytes;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use desert_rust::{deserialize, serialize_to_byte_vec, BinaryCodec};
+use desert_rust::{
+    deserialize, deserialize_borrowed, deserialize_from_bytes, serialize_to_byte_vec,
+    serialize_to_bytes, BinaryCodec,
+};
+use std::collections::{BTreeMap, HashMap};
 use std::hint::black_box;
 
 fn bench_deserialize<T: BinaryCodec>(name: &str, data: T, c: &mut Criterion) {
@@ -14,6 +19,36 @@ fn bench_deserialize<T: BinaryCodec>(name: &str, data: T, c: &mut Criterion) {
     group.finish()
 }
 
+fn bench_deserialize_borrowed<T: BinaryCodec>(
+    name: &str,
+    data: T,
+    deserialize_borrowed: impl Fn(&[u8]),
+    c: &mut Criterion,
+) {
+    let bytes = serialize_to_byte_vec(&data).unwrap();
+
+    let mut group = c.benchmark_group("deserialize");
+    group.bench_with_input(
+        BenchmarkId::from_parameter(format!("{name} (owned)")),
+        &bytes,
+        |b, bytes| {
+            b.iter(|| {
+                black_box(deserialize::<T>(black_box(bytes)).unwrap());
+            });
+        },
+    );
+    group.bench_with_input(
+        BenchmarkId::from_parameter(format!("{name} (borrowed)")),
+        &bytes,
+        |b, bytes| {
+            b.iter(|| {
+                deserialize_borrowed(black_box(bytes));
+            });
+        },
+    );
+    group.finish()
+}
+
 #[derive(BinaryCodec)]
 struct WrappedU64 {
     value: u64,
@@ -37,10 +72,94 @@ fn bench_deserialize_evolved_u64(c: &mut Criterion) {
     bench_deserialize("evolved u64", EvolvedU64 { value: u64::MAX }, c);
 }
 
+fn bench_deserialize_str(c: &mut Criterion) {
+    bench_deserialize_borrowed(
+        "str",
+        "x".repeat(1024),
+        |bytes| {
+            black_box(deserialize_borrowed::<&str>(bytes).unwrap());
+        },
+        c,
+    );
+}
+
+fn bench_deserialize_byte_slice(c: &mut Criterion) {
+    bench_deserialize_borrowed(
+        "byte slice",
+        vec![42u8; 1024],
+        |bytes| {
+            black_box(deserialize_borrowed::<&[u8]>(bytes).unwrap());
+        },
+        c,
+    );
+}
+
+fn bench_deserialize_u32_vec(c: &mut Criterion) {
+    bench_deserialize("u32 vec", (0..1024u32).collect::<Vec<_>>(), c);
+}
+
+fn bench_deserialize_f32_vec(c: &mut Criterion) {
+    bench_deserialize(
+        "f32 vec",
+        (0..1024).map(|i| i as f32 * 0.5).collect::<Vec<_>>(),
+        c,
+    );
+}
+
+fn bench_deserialize_string_vec(c: &mut Criterion) {
+    bench_deserialize(
+        "100k string vec",
+        (0..100_000).map(|i| format!("item{i}")).collect::<Vec<_>>(),
+        c,
+    );
+}
+
+fn bench_deserialize_maps(c: &mut Criterion) {
+    let entries = (0..10_000).map(|i| (format!("key{i:05}"), i as u64));
+    bench_deserialize(
+        "10k btree map",
+        entries.clone().collect::<BTreeMap<_, _>>(),
+        c,
+    );
+    bench_deserialize("10k hash map", entries.collect::<HashMap<_, _>>(), c);
+}
+
+fn bench_deserialize_bytes(c: &mut Criterion) {
+    let data = serialize_to_bytes(&Bytes::from(vec![42u8; 1024 * 1024])).unwrap();
+
+    let mut group = c.benchmark_group("deserialize");
+    group.bench_with_input(
+        BenchmarkId::from_parameter("1MiB bytes (copied)"),
+        &data,
+        |b, data| {
+            b.iter(|| {
+                black_box(deserialize::<Bytes>(black_box(data)).unwrap());
+            });
+        },
+    );
+    group.bench_with_input(
+        BenchmarkId::from_parameter("1MiB bytes (shared)"),
+        &data,
+        |b, data| {
+            b.iter(|| {
+                black_box(deserialize_from_bytes::<Bytes>(black_box(data.clone())).unwrap());
+            });
+        },
+    );
+    group.finish()
+}
+
 criterion_group!(
     benches,
     bench_deserialize_u64,
     bench_deserialize_wrapped_u64,
-    bench_deserialize_evolved_u64
+    bench_deserialize_evolved_u64,
+    bench_deserialize_str,
+    bench_deserialize_byte_slice,
+    bench_deserialize_u32_vec,
+    bench_deserialize_f32_vec,
+    bench_deserialize_string_vec,
+    bench_deserialize_maps,
+    bench_deserialize_bytes
 );
 criterion_main!(benches);
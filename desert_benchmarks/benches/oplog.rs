@@ -48,6 +48,39 @@ fn bench_serialize(c: &mut Criterion) {
     group.finish()
 }
 
+fn bench_serialize_without_buffer_pool(c: &mut Criterion) {
+    let cases = generate_cases();
+    let options = Options {
+        disable_buffer_pool: true,
+        ..Default::default()
+    };
+    let mut group = c.benchmark_group("serialize oplog without buffer pool");
+    for case in cases {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(case.payload_size),
+            &case,
+            |b, case| {
+                b.iter(|| {
+                    let mut entries = Vec::with_capacity(10000);
+                    for entry in &case.entries {
+                        let bytes = black_box(
+                            serialize_with_options(
+                                black_box(&entry),
+                                Vec::with_capacity(128),
+                                options.clone(),
+                            )
+                            .unwrap(),
+                        );
+                        entries.push(bytes);
+                    }
+                    entries
+                });
+            },
+        );
+    }
+    group.finish()
+}
+
 fn bench_deserialize(c: &mut Criterion) {
     let cases = generate_cases();
     let serialized_cases = cases
@@ -146,6 +179,7 @@ fn bench_deserialize_bincode(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_serialize,
+    bench_serialize_without_buffer_pool,
     bench_serialize_bincode,
     bench_deserialize,
     bench_deserialize_bincode
@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use desert_rust::{serialize_to_byte_vec, BinaryCodec};
+use desert_rust::{serialize_to_byte_vec, BinaryCodec, DeduplicatedString};
 use std::hint::black_box;
 
 fn bench_serialize<T: BinaryCodec>(name: &str, data: T, c: &mut Criterion) {
@@ -35,10 +35,32 @@ fn bench_serialize_evolved_u64(c: &mut Criterion) {
     bench_serialize("evolved u64", EvolvedU64 { value: u64::MAX }, c);
 }
 
+fn bench_serialize_unique_deduplicated_strings(c: &mut Criterion) {
+    let strings: Vec<DeduplicatedString> = (0..1000)
+        .map(|i| DeduplicatedString(format!("unique string number {i} with some padding")))
+        .collect();
+    bench_serialize("unique deduplicated strings", strings, c);
+}
+
+fn bench_serialize_u32_vec(c: &mut Criterion) {
+    bench_serialize("u32 vec", (0..1024u32).collect::<Vec<_>>(), c);
+}
+
+fn bench_serialize_f32_vec(c: &mut Criterion) {
+    bench_serialize(
+        "f32 vec",
+        (0..1024).map(|i| i as f32 * 0.5).collect::<Vec<_>>(),
+        c,
+    );
+}
+
 criterion_group!(
     benches,
     bench_serialize_u64,
     bench_serialize_wrapped_u64,
-    bench_serialize_evolved_u64
+    bench_serialize_evolved_u64,
+    bench_serialize_unique_deduplicated_strings,
+    bench_serialize_u32_vec,
+    bench_serialize_f32_vec
 );
 criterion_main!(benches);
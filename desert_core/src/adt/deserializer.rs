@@ -1,16 +1,23 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
 use hashbrown::HashSet;
-use std::collections::BTreeMap;
 
 use crate::adt::{AdtMetadata, FieldPosition};
 use crate::deserializer::InputRegion;
 use crate::evolution::SerializedEvolutionStep;
-use crate::{BinaryDeserializer, BinaryInput, DeserializationContext, Error, Result};
+use crate::{
+    BinaryDeserializer, BinaryInput, DeduplicatedString, DeserializationContext, Error, Result,
+};
 
 pub struct AdtDeserializer<'a, 'b, 'c> {
     metadata: &'a AdtMetadata,
     context: &'b mut DeserializationContext<'c>,
     last_index_per_chunk: Vec<i8>,
     read_constructor_idx: Option<u32>,
+    read_constructor_name: Option<String>,
 
     stored_version: u8,
     made_optional_at: BTreeMap<FieldPosition, u8>,
@@ -19,6 +26,23 @@ pub struct AdtDeserializer<'a, 'b, 'c> {
 }
 
 impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
+    /// Deserializer for hand-written evolvable codecs, reading the version byte and choosing between
+    /// [AdtDeserializer::new_v0] and [AdtDeserializer::new] the same way the derived codecs do.
+    ///
+    /// The fields have to be read with [AdtDeserializer::read_field] or
+    /// [AdtDeserializer::read_optional_field] in their declaration order.
+    pub fn start(
+        metadata: &'a AdtMetadata,
+        context: &'b mut DeserializationContext<'c>,
+    ) -> Result<Self> {
+        let stored_version = context.read_u8()?;
+        if stored_version == 0 {
+            Self::new_v0(metadata, context)
+        } else {
+            Self::new(metadata, context, stored_version)
+        }
+    }
+
     pub fn new_v0(
         metadata: &'a AdtMetadata,
         context: &'b mut DeserializationContext<'c>,
@@ -28,6 +52,7 @@ impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
             context,
             last_index_per_chunk: vec![-1i8; metadata.version as usize + 1],
             read_constructor_idx: None,
+            read_constructor_name: None,
             stored_version: 0,
             made_optional_at: BTreeMap::new(),
             removed_fields: HashSet::new(),
@@ -40,6 +65,13 @@ impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
         context: &'b mut DeserializationContext<'c>,
         stored_version: u8,
     ) -> Result<Self> {
+        if context.options().reject_newer_versions && stored_version > metadata.version {
+            return Err(Error::NewerVersion {
+                stored: stored_version,
+                supported: metadata.version,
+            });
+        }
+
         let mut serialized_evolution_steps = Vec::with_capacity(stored_version as usize + 1);
         for _ in 0..=stored_version {
             let serialized_evolution_step = SerializedEvolutionStep::deserialize(context)?;
@@ -76,6 +108,7 @@ impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
             context,
             last_index_per_chunk: vec![-1i8; metadata.version as usize + 1],
             read_constructor_idx: None,
+            read_constructor_name: None,
             stored_version,
             made_optional_at,
             removed_fields,
@@ -119,14 +152,14 @@ impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
 
                     let is_defined = bool::deserialize(self.context)?;
                     if is_defined {
-                        T::deserialize(self.context)
+                        self.context.nested(T::deserialize)
                     } else {
                         Err(Error::NonOptionalFieldSerializedAsNone(
                             field_name.to_string(),
                         ))
                     }
                 } else {
-                    T::deserialize(self.context)
+                    self.context.nested(T::deserialize)
                 };
                 if has_inputs {
                     self.inputs[chunk as usize] = self.context.pop_region();
@@ -168,9 +201,9 @@ impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
                     self.context.push_region(self.inputs[chunk as usize]);
                 }
                 let result = if self.stored_version < opt_since {
-                    Ok(Some(T::deserialize(self.context)?))
+                    self.context.nested(T::deserialize).map(Some)
                 } else {
-                    Option::<T>::deserialize(self.context)
+                    self.context.nested(Option::<T>::deserialize)
                 };
                 if has_inputs {
                     self.inputs[chunk as usize] = self.context.pop_region();
@@ -180,6 +213,8 @@ impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
         }
     }
 
+    /// Deserializes the value with `deserialize_case` if its constructor id is `case_idx`, returning
+    /// `None` otherwise so the next constructor can be tried.
     pub fn read_constructor<T>(
         &mut self,
         case_idx: u32,
@@ -187,20 +222,60 @@ impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
     ) -> Result<Option<T>> {
         let constructor_idx = self.read_or_get_constructor_idx()?;
         if constructor_idx == case_idx {
-            let has_inputs = !self.inputs.is_empty();
-            if has_inputs {
-                self.context.push_region(self.inputs[0]);
-            }
-            let result = Ok(Some(deserialize_case(self.context)?));
-            if has_inputs {
-                self.inputs[0] = self.context.pop_region();
-            }
-            result
+            self.read_case(deserialize_case).map(Some)
         } else {
             Ok(None)
         }
     }
 
+    /// Like [AdtDeserializer::read_constructor], but the constructor is identified by `case_name` instead
+    /// of its index if [Options::named_constructors](crate::Options::named_constructors) is enabled.
+    pub fn read_named_constructor<T>(
+        &mut self,
+        case_idx: u32,
+        case_name: &str,
+        deserialize_case: impl FnOnce(&mut DeserializationContext<'c>) -> Result<T>,
+    ) -> Result<Option<T>> {
+        if self.context.options().named_constructors {
+            if self.read_or_get_constructor_name()? == case_name {
+                self.read_case(deserialize_case).map(Some)
+            } else {
+                Ok(None)
+            }
+        } else {
+            self.read_constructor(case_idx, deserialize_case)
+        }
+    }
+
+    /// The error to return when none of the constructors of `type_name` matched the one that has been read
+    pub fn invalid_constructor(&mut self, type_name: &str) -> Error {
+        match &self.read_constructor_name {
+            Some(constructor_name) => Error::InvalidConstructorName {
+                constructor_name: constructor_name.clone(),
+                type_name: type_name.to_string(),
+            },
+            None => Error::InvalidConstructorId {
+                constructor_id: self.read_or_get_constructor_idx().unwrap_or(u32::MAX),
+                type_name: type_name.to_string(),
+            },
+        }
+    }
+
+    fn read_case<T>(
+        &mut self,
+        deserialize_case: impl FnOnce(&mut DeserializationContext<'c>) -> Result<T>,
+    ) -> Result<T> {
+        let has_inputs = !self.inputs.is_empty();
+        if has_inputs {
+            self.context.push_region(self.inputs[0]);
+        }
+        let result = self.context.nested(deserialize_case);
+        if has_inputs {
+            self.inputs[0] = self.context.pop_region();
+        }
+        result
+    }
+
     fn record_field_index(&mut self, chunk: u8) -> FieldPosition {
         let last_index = &mut self.last_index_per_chunk[chunk as usize];
         let new_index = *last_index + 1;
@@ -209,6 +284,21 @@ impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
         fp
     }
 
+    fn read_or_get_constructor_name(&mut self) -> Result<&str> {
+        if self.read_constructor_name.is_none() {
+            let has_inputs = !self.inputs.is_empty();
+            if has_inputs {
+                self.context.push_region(self.inputs[0]);
+            }
+            let constructor_name = DeduplicatedString::deserialize(self.context);
+            if has_inputs {
+                self.inputs[0] = self.context.pop_region();
+            }
+            self.read_constructor_name = Some(constructor_name?.0);
+        }
+        Ok(self.read_constructor_name.as_deref().unwrap_or_default())
+    }
+
     pub fn read_or_get_constructor_idx(&mut self) -> Result<u32> {
         match self.read_constructor_idx {
             Some(idx) => Ok(idx),
@@ -217,13 +307,45 @@ impl<'a, 'b, 'c> AdtDeserializer<'a, 'b, 'c> {
                 if has_inputs {
                     self.context.push_region(self.inputs[0]);
                 }
-                let constructor_idx = self.context.read_var_u32()?;
+                let constructor_idx = self.context.read_var_u32();
                 if has_inputs {
                     self.inputs[0] = self.context.pop_region();
                 }
+                let constructor_idx = constructor_idx?;
                 self.read_constructor_idx = Some(constructor_idx);
                 Ok(constructor_idx)
             }
         }
     }
 }
+
+/// Reads a single field of a serialized record without deserializing its other fields.
+///
+/// The `metadata` must be built from the evolution steps of the record's type. Only fields added by an
+/// [Evolution::FieldAdded](crate::Evolution::FieldAdded) step can be read this way, because each of them is
+/// stored alone in its own chunk, which can be located by the chunk sizes of the evolution header. The
+/// position of the original fields depends on the size of the ones before them, so reading them fails.
+///
+/// Returns `None` if the data was written by a version before the field was added, or after it was removed.
+pub fn read_named_field<T: BinaryDeserializer>(
+    input: &[u8],
+    metadata: &AdtMetadata,
+    field_name: &str,
+) -> Result<Option<T>> {
+    let chunk = *metadata.field_generations.get(field_name).ok_or_else(|| {
+        Error::DeserializationFailure(format!(
+            "Failed to read field {field_name}: only fields added by an evolution step can be read on their own"
+        ))
+    })?;
+
+    let mut context = DeserializationContext::new(input);
+    let stored_version = context.read_u8()?;
+    if stored_version < chunk {
+        return Ok(None);
+    }
+    let mut deserializer = AdtDeserializer::new(metadata, &mut context, stored_version)?;
+    if deserializer.removed_fields.contains(field_name) {
+        return Ok(None);
+    }
+    deserializer.read_field(field_name, None).map(Some)
+}
@@ -1,5 +1,10 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Deref;
 use hashbrown::{HashMap, HashSet};
-use lazy_static::lazy_static;
+use once_cell::race::OnceBox;
 
 use crate::deserializer::DeserializationContext;
 use crate::error::Result;
@@ -9,14 +14,74 @@ use crate::{BinaryDeserializer, BinaryInput, BinaryOutput, BinarySerializer, Evo
 mod deserializer;
 mod serializer;
 
-pub use deserializer::AdtDeserializer;
+pub use deserializer::{read_named_field, AdtDeserializer};
 pub use serializer::AdtSerializer;
 
-lazy_static! {
-    pub static ref EMPTY_ADT_METADATA: AdtMetadata =
-        AdtMetadata::new(vec![Evolution::InitialVersion]);
+pub static EMPTY_ADT_METADATA: EmptyAdtMetadata = EmptyAdtMetadata(OnceBox::new());
+
+/// Lazily initialized metadata of a type without any evolution steps
+pub struct EmptyAdtMetadata(OnceBox<AdtMetadata>);
+
+impl Deref for EmptyAdtMetadata {
+    type Target = AdtMetadata;
+
+    fn deref(&self) -> &AdtMetadata {
+        self.0
+            .get_or_init(|| Box::new(AdtMetadata::new(vec![Evolution::InitialVersion])))
+    }
 }
 
+/// The evolution steps of a record or an enum constructor, driving how [AdtSerializer] and
+/// [AdtDeserializer] lay out and read back its fields.
+///
+/// The first step must always be [Evolution::InitialVersion]. As it is built once per type, it is
+/// usually stored in a static. A hand-written codec producing the same format as a derived one with
+/// `#[evolution(FieldAdded("priority", 0))]`:
+///
+/// ```
+/// use desert_core::adt::{AdtDeserializer, AdtMetadata, AdtSerializer};
+/// use desert_core::*;
+/// use std::sync::LazyLock;
+///
+/// struct Task {
+///     name: String,
+///     priority: u8,
+/// }
+///
+/// static TASK_METADATA: LazyLock<AdtMetadata> = LazyLock::new(|| {
+///     AdtMetadata::new(vec![
+///         Evolution::InitialVersion,
+///         Evolution::FieldAdded {
+///             name: "priority".to_string(),
+///         },
+///     ])
+/// });
+///
+/// impl BinarySerializer for Task {
+///     fn serialize<Output: BinaryOutput>(
+///         &self,
+///         context: &mut SerializationContext<Output>,
+///     ) -> Result<()> {
+///         let mut serializer = AdtSerializer::start(&TASK_METADATA, context);
+///         serializer.write_field("name", &self.name)?;
+///         serializer.write_field("priority", &self.priority)?;
+///         serializer.finish()
+///     }
+/// }
+///
+/// impl BinaryDeserializer for Task {
+///     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+///         let mut deserializer = AdtDeserializer::start(&TASK_METADATA, context)?;
+///         Ok(Task {
+///             name: deserializer.read_field("name", None)?,
+///             priority: deserializer.read_field("priority", Some(0))?,
+///         })
+///     }
+/// }
+///
+/// let data = serialize_to_byte_vec(&Task { name: "write docs".to_string(), priority: 2 }).unwrap();
+/// assert_eq!(deserialize::<Task>(&data).unwrap().priority, 2);
+/// ```
 #[derive(Debug)]
 pub struct AdtMetadata {
     version: u8,
@@ -75,6 +140,11 @@ impl AdtMetadata {
             evolution_steps,
         }
     }
+
+    /// The version written to the serialized data, which is the number of evolution steps after the initial version
+    pub fn version(&self) -> u8 {
+        self.version
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -111,7 +181,7 @@ impl BinaryDeserializer for FieldPosition {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
         let byte = context.read_i8()?;
         if byte < 0 {
-            Ok(FieldPosition::new(0, (-byte) as u8))
+            Ok(FieldPosition::new(0, byte.unsigned_abs()))
         } else {
             Ok(FieldPosition::new(byte as u8, 0))
         }
@@ -1,10 +1,12 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use hashbrown::{HashMap, HashSet};
 
 use crate::adt::{AdtMetadata, FieldPosition};
 use crate::evolution::SerializedEvolutionStep;
 use crate::{
-    BinaryOutput, BinarySerializer, Error, Evolution, Result, SerializationContext,
-    DEFAULT_CAPACITY,
+    BinaryOutput, BinarySerializer, DeduplicatedString, Error, Evolution, Result,
+    SerializationContext,
 };
 
 pub struct AdtSerializer<'a, 'b, Output: BinaryOutput> {
@@ -16,12 +18,37 @@ pub struct AdtSerializer<'a, 'b, Output: BinaryOutput> {
 }
 
 impl<'a, 'b, Output: BinaryOutput> AdtSerializer<'a, 'b, Output> {
+    /// Serializer for hand-written evolvable codecs, choosing between [AdtSerializer::new_v0] and
+    /// [AdtSerializer::new] by the version of `metadata` the same way the derived codecs do.
+    ///
+    /// The fields have to be written with [AdtSerializer::write_field] in their declaration order, and
+    /// the value is completed by [AdtSerializer::finish].
+    pub fn start(metadata: &'a AdtMetadata, context: &'b mut SerializationContext<Output>) -> Self {
+        if metadata.version == 0 {
+            Self::new_v0(metadata, context)
+        } else {
+            Self::new(metadata, context)
+        }
+    }
+
     pub fn new_v0(
         metadata: &'a AdtMetadata,
         context: &'b mut SerializationContext<Output>,
     ) -> Self {
-        assert_eq!(metadata.version, 0);
         context.write_u8(metadata.version);
+        Self::new_flat(metadata, context)
+    }
+
+    /// Serializer for types that can never evolve, writing the fields like [AdtSerializer::new_v0] but
+    /// without the leading version byte.
+    ///
+    /// The result is not compatible with the evolvable format, so it can be read back only by
+    /// [AdtDeserializer::new_v0](crate::adt::AdtDeserializer::new_v0) without reading the version first.
+    pub fn new_flat(
+        metadata: &'a AdtMetadata,
+        context: &'b mut SerializationContext<Output>,
+    ) -> Self {
+        assert_eq!(metadata.version, 0);
         Self {
             metadata,
             context,
@@ -33,12 +60,20 @@ impl<'a, 'b, Output: BinaryOutput> AdtSerializer<'a, 'b, Output> {
 
     pub fn new(metadata: &'a AdtMetadata, context: &'b mut SerializationContext<Output>) -> Self {
         context.write_u8(metadata.version);
+        // Without added fields everything is written to the first chunk, the evolution steps
+        // only refer to it in the header
+        let chunk_count = if metadata.field_generations.is_empty() {
+            1
+        } else {
+            metadata.version + 1
+        };
+        let buffers = (0..chunk_count)
+            .map(|_| Some(context.take_pooled_buffer()))
+            .collect();
         Self {
             metadata,
             context,
-            buffers: (0..=metadata.version)
-                .map(|_| Some(Vec::with_capacity(DEFAULT_CAPACITY)))
-                .collect(),
+            buffers,
             last_index_per_chunk: HashMap::new(),
             field_indices: HashMap::new(),
         }
@@ -55,7 +90,7 @@ impl<'a, 'b, Output: BinaryOutput> AdtSerializer<'a, 'b, Output> {
             self.context
                 .push_buffer(self.buffers[chunk as usize].take().unwrap());
         }
-        value.serialize(self.context)?;
+        self.context.nested(|context| value.serialize(context))?;
         if requires_buffer {
             self.buffers[chunk as usize] = Some(self.context.pop_buffer());
             self.record_field_index(field_name, chunk);
@@ -75,13 +110,58 @@ impl<'a, 'b, Output: BinaryOutput> AdtSerializer<'a, 'b, Output> {
         }
     }
 
+    /// Writes the constructor of an enum value, which is serialized by `serialize_case` - usually with
+    /// another [AdtSerializer] using the constructor's own metadata.
     pub fn write_constructor(
         &mut self,
         constructor_idx: u32,
         serialize_case: impl FnOnce(&mut SerializationContext<Output>) -> Result<()>,
     ) -> Result<()> {
-        self.context.write_var_u32(constructor_idx);
-        serialize_case(self.context)
+        self.write_case(
+            |context| {
+                context.write_var_u32(constructor_idx);
+                Ok(())
+            },
+            serialize_case,
+        )
+    }
+
+    /// Like [AdtSerializer::write_constructor], but the constructor is identified by `constructor_name`
+    /// instead of its index if [Options::named_constructors](crate::Options::named_constructors) is enabled.
+    pub fn write_named_constructor(
+        &mut self,
+        constructor_idx: u32,
+        constructor_name: &str,
+        serialize_case: impl FnOnce(&mut SerializationContext<Output>) -> Result<()>,
+    ) -> Result<()> {
+        self.write_case(
+            |context| {
+                if context.options().named_constructors {
+                    DeduplicatedString(constructor_name.to_string()).serialize(context)
+                } else {
+                    context.write_var_u32(constructor_idx);
+                    Ok(())
+                }
+            },
+            serialize_case,
+        )
+    }
+
+    fn write_case(
+        &mut self,
+        write_constructor_id: impl FnOnce(&mut SerializationContext<Output>) -> Result<()>,
+        serialize_case: impl FnOnce(&mut SerializationContext<Output>) -> Result<()>,
+    ) -> Result<()> {
+        let requires_buffer = !self.buffers.is_empty();
+        if requires_buffer {
+            self.context.push_buffer(self.buffers[0].take().unwrap());
+        }
+        let result =
+            write_constructor_id(self.context).and_then(|_| self.context.nested(serialize_case));
+        if requires_buffer {
+            self.buffers[0] = Some(self.context.pop_buffer());
+        }
+        result
     }
 
     fn record_field_index(&mut self, field_name: &str, chunk: u8) {
@@ -144,8 +224,10 @@ impl<'a, 'b, Output: BinaryOutput> AdtSerializer<'a, 'b, Output> {
     }
 
     fn write_ordered_chunks(&mut self) -> Result<()> {
-        for buffer in &self.buffers {
-            self.context.write_bytes(buffer.as_ref().unwrap());
+        for buffer in &mut self.buffers {
+            let buffer = buffer.take().unwrap();
+            self.context.write_bytes(&buffer);
+            self.context.release_pooled_buffer(buffer);
         }
         Ok(())
     }
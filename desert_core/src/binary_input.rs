@@ -1,7 +1,15 @@
-use std::io::Read;
-
+#[cfg(feature = "std")]
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use bytes::Buf;
+#[cfg(feature = "std")]
 use flate2::read::DeflateDecoder;
+#[cfg(feature = "std")]
+use std::io::Read;
 
+#[cfg(feature = "std")]
+use crate::deserializer::MAX_PREALLOCATION;
 use crate::error::Result;
 use crate::Error;
 
@@ -99,12 +107,74 @@ pub trait BinaryInput {
         Ok(((r >> 1) ^ (-((r & 1) as i32) as u32)) as i32)
     }
 
+    fn read_var_u64(&mut self) -> Result<u64> {
+        let mut r = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.read_u8()?;
+            r |= ((b & 0x7F) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(r);
+            }
+        }
+        Err(Error::DeserializationFailure(String::from(
+            "Failed to deserialize var u64: more than 10 bytes",
+        )))
+    }
+
+    fn read_var_i64(&mut self) -> Result<i64> {
+        let r = self.read_var_u64()?;
+        Ok(((r >> 1) ^ (-((r & 1) as i64) as u64)) as i64)
+    }
+
+    /// Reads an unsigned LEB128 value, see [BinaryOutput::write_leb128_u64](crate::BinaryOutput::write_leb128_u64).
+    ///
+    /// Unlike [BinaryInput::read_var_u64], it fails if the encoded value does not fit in 64 bits.
+    fn read_leb128_u64(&mut self) -> Result<u64> {
+        let mut r = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.read_u8()?;
+            if shift == 63 && b > 1 {
+                break;
+            }
+            r |= ((b & 0x7F) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(r);
+            }
+        }
+        Err(Error::DeserializationFailure(String::from(
+            "Failed to deserialize LEB128 u64: value does not fit in 64 bits",
+        )))
+    }
+
+    /// Reads a signed LEB128 value, see [BinaryOutput::write_leb128_i64](crate::BinaryOutput::write_leb128_i64).
+    fn read_leb128_i64(&mut self) -> Result<i64> {
+        let mut r = 0i64;
+        for shift in (0..64).step_by(7) {
+            let b = self.read_u8()?;
+            // The last byte can only hold the sign bit, repeated
+            if shift == 63 && b != 0 && b != 0x7F {
+                break;
+            }
+            r |= ((b & 0x7F) as i64) << shift;
+            if b & 0x80 == 0 {
+                if shift + 7 < 64 && b & 0x40 != 0 {
+                    r |= -1i64 << (shift + 7);
+                }
+                return Ok(r);
+            }
+        }
+        Err(Error::DeserializationFailure(String::from(
+            "Failed to deserialize LEB128 i64: value does not fit in 64 bits",
+        )))
+    }
+
+    #[cfg(feature = "std")]
     fn read_compressed(&mut self) -> Result<Vec<u8>> {
         let uncompressed_len = self.read_var_u32()? as usize;
         let compressed_len = self.read_var_u32()? as usize;
         let compressed = self.read_bytes(compressed_len)?;
         let mut deflater = DeflateDecoder::new(compressed);
-        let mut result = Vec::with_capacity(uncompressed_len);
+        let mut result = Vec::with_capacity(uncompressed_len.min(MAX_PREALLOCATION));
         deflater
             .read_to_end(&mut result)
             .map_err(|err| Error::DecompressionFailure(format!("{err}")))?;
@@ -128,7 +198,9 @@ impl<'a> SliceInput<'a> {
 impl BinaryInput for SliceInput<'_> {
     fn read_u8(&mut self) -> Result<u8> {
         if self.pos == self.data.len() {
-            Err(Error::InputEndedUnexpectedly)
+            Err(Error::InputEndedUnexpectedly {
+                offset: self.data.len(),
+            })
         } else {
             let result = self.data[self.pos];
             self.pos += 1;
@@ -137,8 +209,10 @@ impl BinaryInput for SliceInput<'_> {
     }
 
     fn read_bytes(&mut self, count: usize) -> Result<&[u8]> {
-        if self.pos + count > self.data.len() {
-            Err(Error::InputEndedUnexpectedly)
+        if count > self.data.len() - self.pos {
+            Err(Error::InputEndedUnexpectedly {
+                offset: self.data.len(),
+            })
         } else {
             let result = &self.data[self.pos..self.pos + count];
             self.pos += count;
@@ -147,8 +221,101 @@ impl BinaryInput for SliceInput<'_> {
     }
 
     fn skip(&mut self, count: usize) -> Result<()> {
-        if self.pos + count > self.data.len() {
-            Err(Error::InputEndedUnexpectedly)
+        if count > self.data.len() - self.pos {
+            Err(Error::InputEndedUnexpectedly {
+                offset: self.data.len(),
+            })
+        } else {
+            self.pos += count;
+            Ok(())
+        }
+    }
+}
+
+/// Input reading from a sequence of slices as if they were concatenated.
+///
+/// Reads within a single segment return a slice of it, and only reads spanning a segment boundary are
+/// copied into an internal buffer.
+pub struct ChainedInput<'a> {
+    segments: Vec<&'a [u8]>,
+    len: usize,
+    pos: usize,
+    buffer: Vec<u8>,
+}
+
+impl<'a> ChainedInput<'a> {
+    pub fn new(segments: &[&'a [u8]]) -> Self {
+        Self {
+            segments: segments.to_vec(),
+            len: segments.iter().map(|segment| segment.len()).sum(),
+            pos: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// The total length of all the segments
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `count` bytes starting at the absolute position `start`, copying them if they span
+    /// multiple segments.
+    pub(crate) fn bytes_at(&mut self, start: usize, count: usize) -> Result<&[u8]> {
+        if start + count > self.len {
+            Err(Error::InputEndedUnexpectedly { offset: self.len })
+        } else if let Some(bytes) = self.borrowed_bytes_at(start, count) {
+            Ok(bytes)
+        } else {
+            self.buffer.clear();
+            let mut offset = 0;
+            for segment in &self.segments {
+                let segment_end = offset + segment.len();
+                if segment_end > start && offset < start + count {
+                    let from = start.max(offset) - offset;
+                    let to = (start + count).min(segment_end) - offset;
+                    self.buffer.extend_from_slice(&segment[from..to]);
+                }
+                offset = segment_end;
+            }
+            Ok(&self.buffer)
+        }
+    }
+
+    /// Returns `count` bytes starting at the absolute position `start` if they are all in the same segment.
+    pub(crate) fn borrowed_bytes_at(&self, start: usize, count: usize) -> Option<&'a [u8]> {
+        let mut offset = 0;
+        for segment in &self.segments {
+            if start >= offset && start + count <= offset + segment.len() {
+                return Some(&segment[(start - offset)..(start - offset + count)]);
+            }
+            offset += segment.len();
+        }
+        None
+    }
+}
+
+impl BinaryInput for ChainedInput<'_> {
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_bytes(&mut self, count: usize) -> Result<&[u8]> {
+        let start = self.pos;
+        if count > self.len - start {
+            Err(Error::InputEndedUnexpectedly { offset: self.len })
+        } else {
+            self.pos += count;
+            self.bytes_at(start, count)
+        }
+    }
+
+    fn skip(&mut self, count: usize) -> Result<()> {
+        if count > self.len - self.pos {
+            Err(Error::InputEndedUnexpectedly { offset: self.len })
         } else {
             self.pos += count;
             Ok(())
@@ -170,7 +337,9 @@ impl OwnedInput {
 impl BinaryInput for OwnedInput {
     fn read_u8(&mut self) -> Result<u8> {
         if self.pos == self.data.len() {
-            Err(Error::InputEndedUnexpectedly)
+            Err(Error::InputEndedUnexpectedly {
+                offset: self.data.len(),
+            })
         } else {
             let result = self.data[self.pos];
             self.pos += 1;
@@ -179,8 +348,10 @@ impl BinaryInput for OwnedInput {
     }
 
     fn read_bytes(&mut self, count: usize) -> Result<&[u8]> {
-        if self.pos + count > self.data.len() {
-            Err(Error::InputEndedUnexpectedly)
+        if count > self.data.len() - self.pos {
+            Err(Error::InputEndedUnexpectedly {
+                offset: self.data.len(),
+            })
         } else {
             let result = &self.data[self.pos..self.pos + count];
             self.pos += count;
@@ -189,8 +360,10 @@ impl BinaryInput for OwnedInput {
     }
 
     fn skip(&mut self, count: usize) -> Result<()> {
-        if self.pos + count > self.data.len() {
-            Err(Error::InputEndedUnexpectedly)
+        if count > self.data.len() - self.pos {
+            Err(Error::InputEndedUnexpectedly {
+                offset: self.data.len(),
+            })
         } else {
             self.pos += count;
             Ok(())
@@ -198,9 +371,85 @@ impl BinaryInput for OwnedInput {
     }
 }
 
+/// Input reading from any [Buf], for example a chain of [Bytes](bytes::Bytes) segments.
+///
+/// Reads within the current chunk of the buffer return a slice of it, and only reads spanning multiple
+/// chunks are copied into an internal buffer. To keep using the buffer after reading from it, pass
+/// `&mut buf` - when the input is dropped, it is advanced past everything that has been read.
+pub struct BufInput<B: Buf> {
+    buf: B,
+    // Bytes of the current chunk that have been returned by the last read, but not yet advanced over
+    pending: usize,
+    pos: usize,
+    buffer: Vec<u8>,
+}
+
+impl<B: Buf> BufInput<B> {
+    pub fn new(buf: B) -> Self {
+        Self {
+            buf,
+            pending: 0,
+            pos: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn advance_pending(&mut self) {
+        self.buf.advance(self.pending);
+        self.pending = 0;
+    }
+
+    fn check_remaining(&self, count: usize) -> Result<()> {
+        if count > self.buf.remaining() {
+            Err(Error::InputEndedUnexpectedly {
+                offset: self.pos + self.buf.remaining(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<B: Buf> Drop for BufInput<B> {
+    fn drop(&mut self) {
+        self.advance_pending();
+    }
+}
+
+impl<B: Buf> BinaryInput for BufInput<B> {
+    fn read_u8(&mut self) -> Result<u8> {
+        self.advance_pending();
+        self.check_remaining(1)?;
+        self.pos += 1;
+        Ok(self.buf.get_u8())
+    }
+
+    fn read_bytes(&mut self, count: usize) -> Result<&[u8]> {
+        self.advance_pending();
+        self.check_remaining(count)?;
+        self.pos += count;
+        if self.buf.chunk().len() >= count {
+            self.pending = count;
+            Ok(&self.buf.chunk()[..count])
+        } else {
+            self.buffer.resize(count, 0);
+            self.buf.copy_to_slice(&mut self.buffer);
+            Ok(&self.buffer)
+        }
+    }
+
+    fn skip(&mut self, count: usize) -> Result<()> {
+        self.advance_pending();
+        self.check_remaining(count)?;
+        self.pos += count;
+        self.buf.advance(count);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::binary_input::OwnedInput;
+    use crate::binary_input::{ChainedInput, OwnedInput};
     use crate::{BinaryInput, BinaryOutput};
     use bytes::BytesMut;
     use proptest::prelude::*;
@@ -227,6 +476,26 @@ mod tests {
             assert_eq!(value, result);
         }
 
+        #[test]
+        fn roundtrip_var_i64(value: i64) {
+            let mut bytes = BytesMut::new();
+            bytes.write_var_i64(value);
+
+            let mut bytes = OwnedInput::new(bytes.freeze().to_vec());
+            let result = bytes.read_var_i64().unwrap();
+            assert_eq!(value, result);
+        }
+
+        #[test]
+        fn roundtrip_var_u64(value: u64) {
+            let mut bytes = BytesMut::new();
+            bytes.write_var_u64(value);
+
+            let mut bytes = OwnedInput::new(bytes.freeze().to_vec());
+            let result = bytes.read_var_u64().unwrap();
+            assert_eq!(value, result);
+        }
+
         #[test]
         fn roundtrip_compressed(bytes: Vec<u8>) {
             let mut compressed = BytesMut::new();
@@ -251,4 +520,21 @@ mod tests {
         assert_eq!(result, &[2, 3, 4, 5]);
         Ok(())
     }
+
+    #[test]
+    fn chained_reads_span_segments() -> Result<(), crate::Error> {
+        let data: [u8; 11] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let mut input = ChainedInput::new(&[&data[0..3], &[], &data[3..4], &data[4..]]);
+
+        assert_eq!(input.read_bytes(2)?, &[0, 1]);
+        assert_eq!(input.read_u32()?, 0x02030405);
+        input.skip(1)?;
+        assert_eq!(input.read_u8()?, 7);
+        assert_eq!(input.read_bytes(3)?, &[8, 9, 10]);
+        assert!(matches!(
+            input.read_u8(),
+            Err(crate::Error::InputEndedUnexpectedly { offset: 11 })
+        ));
+        Ok(())
+    }
 }
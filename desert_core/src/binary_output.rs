@@ -1,10 +1,15 @@
+use crate::error::Result;
 use crate::Error;
+use alloc::string::String;
+use alloc::vec::Vec;
 use bytes::{BufMut, BytesMut};
-use flate2::read::DeflateEncoder;
-use flate2::Compression;
-use std::io::Read;
-
-use crate::error::Result;
+#[cfg(feature = "std")]
+use {
+    alloc::format,
+    flate2::read::DeflateEncoder,
+    flate2::Compression,
+    std::io::{Read, Write},
+};
 
 pub trait BinaryOutput {
     fn write_u8(&mut self, value: u8);
@@ -92,6 +97,44 @@ pub trait BinaryOutput {
         self.write_var_u32(adjusted);
     }
 
+    fn write_var_u64(&mut self, value: u64) {
+        let mut value = value;
+        while value >> 7 != 0 {
+            self.write_u8(((value & 0x7F) | 0x80) as u8);
+            value >>= 7;
+        }
+        self.write_u8(value as u8);
+    }
+
+    fn write_var_i64(&mut self, value: i64) {
+        let adjusted = ((value << 1) ^ (value >> 63)) as u64;
+        self.write_var_u64(adjusted);
+    }
+
+    /// Writes `value` in the unsigned LEB128 encoding, which is the same as [BinaryOutput::write_var_u64].
+    fn write_leb128_u64(&mut self, value: u64) {
+        self.write_var_u64(value);
+    }
+
+    /// Writes `value` in the signed LEB128 encoding used for example by WebAssembly and DWARF.
+    ///
+    /// Unlike [BinaryOutput::write_var_i64], which zigzag encodes the value before writing it as unsigned
+    /// LEB128 (like protobuf's `sint64`), this writes the two's complement value and the reader sign-extends
+    /// it from the last byte.
+    fn write_leb128_i64(&mut self, value: i64) {
+        let mut value = value;
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+                self.write_u8(byte);
+                return;
+            }
+            self.write_u8(byte | 0x80);
+        }
+    }
+
+    #[cfg(feature = "std")]
     fn write_compressed(&mut self, bytes: &[u8], opts: Compression) -> Result<()> {
         let mut deflater = DeflateEncoder::new(bytes, opts);
         let mut compressed = Vec::new();
@@ -115,6 +158,51 @@ impl BinaryOutput for BytesMut {
     }
 }
 
+/// `BinaryOutput` implementation writing into any [BufMut].
+///
+/// Writing to a `BinaryOutput` cannot fail, so if the buffer runs out of space the write and every
+/// subsequent one gets ignored, and [BufMutOutput::finish] returns an error.
+pub struct BufMutOutput<B: BufMut> {
+    buf: B,
+    overflowed: bool,
+}
+
+impl<B: BufMut> BufMutOutput<B> {
+    pub fn new(buf: B) -> Self {
+        Self {
+            buf,
+            overflowed: false,
+        }
+    }
+
+    /// Returns the underlying buffer, or an error if it did not have enough space for everything written to it
+    pub fn finish(self) -> Result<B> {
+        if self.overflowed {
+            Err(Error::WriteFailure(String::from(
+                "Not enough space in the output buffer",
+            )))
+        } else {
+            Ok(self.buf)
+        }
+    }
+}
+
+impl<B: BufMut> BinaryOutput for BufMutOutput<B> {
+    fn write_u8(&mut self, value: u8) {
+        self.write_bytes(&[value]);
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        if !self.overflowed {
+            if bytes.len() > self.buf.remaining_mut() {
+                self.overflowed = true;
+            } else {
+                self.buf.put_slice(bytes);
+            }
+        }
+    }
+}
+
 impl BinaryOutput for Vec<u8> {
     fn write_u8(&mut self, value: u8) {
         self.push(value);
@@ -125,6 +213,54 @@ impl BinaryOutput for Vec<u8> {
     }
 }
 
+/// `BinaryOutput` implementation writing directly into a `std::io::Write` sink.
+///
+/// Writing to a `BinaryOutput` cannot fail, so the first I/O error is stored and every subsequent
+/// write gets ignored. The error is returned by [WriterOutput::finish].
+#[cfg(feature = "std")]
+pub struct WriterOutput<W: Write> {
+    writer: W,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> WriterOutput<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    /// Flushes the underlying writer and returns it, or the first error that occurred while writing to it
+    pub fn finish(mut self) -> Result<W> {
+        match self.error {
+            Some(err) => Err(Error::WriteFailure(format!("{err}"))),
+            None => {
+                self.writer
+                    .flush()
+                    .map_err(|err| Error::WriteFailure(format!("{err}")))?;
+                Ok(self.writer)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> BinaryOutput for WriterOutput<W> {
+    fn write_u8(&mut self, value: u8) {
+        self.write_bytes(&[value]);
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        if self.error.is_none() {
+            if let Err(err) = self.writer.write_all(bytes) {
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
 pub struct SizeCalculator {
     size: usize,
 }
@@ -0,0 +1,145 @@
+use alloc::format;
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::{deserialize_exact, serialize, BinaryDeserializer, BinarySerializer, Error, Result};
+
+/// Checksum algorithms supported by [serialize_with_checksum], both producing 32 bit checksums
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumAlgorithm {
+    Crc32,
+    Adler32,
+}
+
+impl ChecksumAlgorithm {
+    fn tag(&self) -> u8 {
+        match self {
+            ChecksumAlgorithm::Crc32 => 0,
+            ChecksumAlgorithm::Adler32 => 1,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self> {
+        match tag {
+            0 => Ok(ChecksumAlgorithm::Crc32),
+            1 => Ok(ChecksumAlgorithm::Adler32),
+            _ => Err(Error::DeserializationFailure(format!(
+                "Failed to deserialize checksummed value: unknown checksum algorithm {tag}"
+            ))),
+        }
+    }
+
+    fn checksum(&self, data: &[u8]) -> u32 {
+        match self {
+            ChecksumAlgorithm::Crc32 => crc32fast::hash(data),
+            ChecksumAlgorithm::Adler32 => adler2::adler32_slice(data),
+        }
+    }
+}
+
+const CHECKSUM_SIZE: usize = 4;
+
+/// Serializes a value followed by a checksum of its serialized form, to detect corrupted data when it is
+/// read back with [deserialize_with_checksum].
+///
+/// The result starts with a byte identifying the algorithm, then the serialized value and the big-endian
+/// checksum of everything before it. It is a different format than the plain serialized value.
+pub fn serialize_with_checksum<T: BinarySerializer>(
+    value: &T,
+    algorithm: ChecksumAlgorithm,
+) -> Result<Bytes> {
+    let mut output = BytesMut::new();
+    output.put_u8(algorithm.tag());
+    let mut output = serialize(value, output)?;
+    let checksum = algorithm.checksum(&output);
+    output.put_u32(checksum);
+    Ok(output.freeze())
+}
+
+/// Deserializes a value written by [serialize_with_checksum], failing with [Error::ChecksumMismatch] if the
+/// data has been corrupted.
+///
+/// The checksum is verified before deserializing, and the value has to occupy the whole space before it.
+pub fn deserialize_with_checksum<T: BinaryDeserializer>(input: &[u8]) -> Result<T> {
+    if input.len() < 1 + CHECKSUM_SIZE {
+        return Err(Error::InputEndedUnexpectedly {
+            offset: input.len(),
+        });
+    }
+    let (data, stored) = input.split_at(input.len() - CHECKSUM_SIZE);
+    let algorithm = ChecksumAlgorithm::from_tag(data[0])?;
+    let expected = u32::from_be_bytes(stored.try_into()?);
+    let actual = algorithm.checksum(data);
+    if expected != actual {
+        return Err(Error::ChecksumMismatch { expected, actual });
+    }
+    deserialize_exact(&data[1..])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deserialize_with_checksum, serialize_to_byte_vec, serialize_with_checksum,
+        ChecksumAlgorithm, Error,
+    };
+    use proptest::prelude::*;
+    use test_r::test;
+
+    fn any_algorithm() -> impl Strategy<Value = ChecksumAlgorithm> {
+        prop_oneof![
+            Just(ChecksumAlgorithm::Crc32),
+            Just(ChecksumAlgorithm::Adler32)
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip_with_checksum(value: (String, Vec<u64>), algorithm in any_algorithm()) {
+            let data = serialize_with_checksum(&value, algorithm).unwrap();
+            prop_assert_eq!(deserialize_with_checksum::<(String, Vec<u64>)>(&data).unwrap(), value);
+        }
+
+        #[test]
+        fn any_corrupted_byte_is_detected(
+            value: (String, Vec<u64>),
+            algorithm in any_algorithm(),
+            idx: prop::sample::Index,
+            flip in 1..=u8::MAX,
+        ) {
+            let mut data = serialize_with_checksum(&value, algorithm).unwrap().to_vec();
+            let idx = idx.index(data.len());
+            data[idx] ^= flip;
+            prop_assert!(deserialize_with_checksum::<(String, Vec<u64>)>(&data).is_err());
+        }
+    }
+
+    #[test]
+    fn checksum_surrounds_the_serialized_value() {
+        let value = "hello world".to_string();
+        let data = serialize_with_checksum(&value, ChecksumAlgorithm::Crc32).unwrap();
+        assert_eq!(data[0], 0);
+        assert_eq!(
+            data[1..data.len() - 4],
+            serialize_to_byte_vec(&value).unwrap()
+        );
+        assert_eq!(
+            data[data.len() - 4..],
+            crc32fast::hash(&data[..data.len() - 4]).to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn corrupted_payload_fails_with_checksum_mismatch() {
+        let mut data = serialize_with_checksum(&42u64, ChecksumAlgorithm::Adler32)
+            .unwrap()
+            .to_vec();
+        data[3] ^= 0x10;
+        assert!(matches!(
+            deserialize_with_checksum::<u64>(&data),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            deserialize_with_checksum::<u64>(&data[..3]),
+            Err(Error::InputEndedUnexpectedly { offset: 3 })
+        ));
+    }
+}
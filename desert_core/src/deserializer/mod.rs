@@ -1,21 +1,49 @@
-use std::any::Any;
-use std::char::DecodeUtf16Error;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList};
-use std::hash::Hash;
-use std::marker::PhantomData;
-use std::mem::MaybeUninit;
-use std::rc::Rc;
-use std::sync::Arc;
-use std::time::Duration;
-
-use bytes::Bytes;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList};
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::{Cell, RefCell};
+use core::cmp::Reverse;
+#[cfg(feature = "std")]
+use core::hash::BuildHasher;
+use core::hash::Hash;
+use core::marker::PhantomData;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, Saturating, Wrapping,
+};
+use core::ops::{Range, RangeInclusive};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::ffi::OsString;
+#[cfg(feature = "std")]
+use std::io::Read;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bytes::{Bytes, BytesMut};
 use castaway::cast;
 use once_cell::unsync::Lazy;
 
-use crate::binary_input::BinaryInput;
+use crate::binary_input::{BinaryInput, ChainedInput};
 use crate::error::Result;
+use crate::options::Options;
 use crate::state::State;
-use crate::{DeduplicatedString, Error, RefId, StringId};
+use crate::{
+    Deduplicated, DeduplicatedString, Error, Leb128, RefId, SharedRef, StringId, ValueId, VarInt,
+};
 
 #[allow(clippy::type_complexity)]
 mod tuples;
@@ -24,11 +52,20 @@ pub trait BinaryDeserializer: Sized {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self>;
 }
 
+/// Deserializer for values borrowing from the input of the [DeserializationContext] instead of copying it.
+///
+/// Only works with contexts created from a byte slice.
+pub trait BinaryDeserializeBorrowed<'a>: Sized {
+    fn deserialize_borrowed(context: &mut DeserializationContext<'a>) -> Result<Self>;
+}
+
 pub struct DeserializationContext<'a> {
-    input: &'a [u8],
+    input: Input<'a>,
+    options: Options,
     state: Lazy<State>,
     region_stack: Vec<ResolvedInputRegion>,
     current: ResolvedInputRegion,
+    depth: usize,
 }
 
 impl<'a> DeserializationContext<'a> {
@@ -40,13 +77,117 @@ impl<'a> DeserializationContext<'a> {
             delta: 0,
         };
         Self {
-            input,
+            input: Input::Slice(input),
+            options: Options::default(),
+            state: Lazy::new(State::default),
+            region_stack: vec![],
+            current: whole_input,
+            depth: 0,
+        }
+    }
+
+    /// Creates a context pulling its input from `reader` on demand.
+    ///
+    /// Every byte read is kept in an internal buffer until the context is dropped, because the chunks of
+    /// evolvable types are located up front and read back later. The reader is never read past the
+    /// last byte required by the deserialized value.
+    #[cfg(feature = "std")]
+    pub fn from_reader(reader: impl Read + 'a) -> Self {
+        let whole_input = ResolvedInputRegion {
+            start: 0,
+            pos: 0,
+            end: usize::MAX,
+            delta: 0,
+        };
+        Self {
+            input: Input::Reader {
+                reader: Box::new(reader),
+                buffer: Vec::new(),
+            },
+            options: Options::default(),
+            state: Lazy::new(State::default),
+            region_stack: vec![],
+            current: whole_input,
+            depth: 0,
+        }
+    }
+
+    /// Creates a context pulling its input from any [BinaryInput] on demand, taking ownership of it.
+    ///
+    /// Like with [DeserializationContext::from_reader], every byte read is kept in an internal buffer
+    /// until the context is dropped.
+    pub fn from_input(input: impl BinaryInput + 'a) -> Self {
+        let whole_input = ResolvedInputRegion {
+            start: 0,
+            pos: 0,
+            end: usize::MAX,
+            delta: 0,
+        };
+        Self {
+            input: Input::Pulled {
+                input: Box::new(input),
+                buffer: Vec::new(),
+            },
+            options: Options::default(),
+            state: Lazy::new(State::default),
+            region_stack: vec![],
+            current: whole_input,
+            depth: 0,
+        }
+    }
+
+    /// Creates a context reading from a shared [Bytes] buffer.
+    ///
+    /// Deserialized [Bytes] values are slices of `input` instead of copies, but borrowing values with
+    /// [BinaryDeserializeBorrowed] is not possible.
+    pub fn from_bytes(input: Bytes) -> Self {
+        let whole_input = ResolvedInputRegion {
+            start: 0,
+            pos: 0,
+            end: input.len(),
+            delta: 0,
+        };
+        Self {
+            input: Input::Bytes(input),
+            options: Options::default(),
             state: Lazy::new(State::default),
             region_stack: vec![],
             current: whole_input,
+            depth: 0,
         }
     }
 
+    /// Creates a context reading from `segments` as if they were a single concatenated slice.
+    ///
+    /// Values spanning the boundary of two segments are copied, everything else is read directly from
+    /// the segments.
+    pub fn from_chain(segments: &[&'a [u8]]) -> Self {
+        let input = ChainedInput::new(segments);
+        let whole_input = ResolvedInputRegion {
+            start: 0,
+            pos: 0,
+            end: input.len(),
+            delta: 0,
+        };
+        Self {
+            input: Input::Chained(input),
+            options: Options::default(),
+            state: Lazy::new(State::default),
+            region_stack: vec![],
+            current: whole_input,
+            depth: 0,
+        }
+    }
+
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
     pub fn state(&self) -> &State {
         &self.state
     }
@@ -55,6 +196,12 @@ impl<'a> DeserializationContext<'a> {
         &mut self.state
     }
 
+    /// Registers the dictionary of strings used by the serializer, see
+    /// [SerializationContext::register_string_dictionary](crate::SerializationContext::register_string_dictionary).
+    pub fn register_string_dictionary<S: AsRef<str>>(&mut self, dictionary: &[S]) -> Result<()> {
+        self.state.preload_strings(dictionary)
+    }
+
     pub fn try_read_ref(&mut self) -> Result<Option<&dyn Any>> {
         let id = self.read_var_u32()?;
         if id == 0 {
@@ -68,6 +215,34 @@ impl<'a> DeserializationContext<'a> {
         }
     }
 
+    /// Reads what [SerializationContext::store_rc_or_object](crate::SerializationContext::store_rc_or_object)
+    /// wrote: `None` if the value is new and has to be deserialized next, otherwise the `Rc` it refers to.
+    pub fn try_read_rc<T: Any>(&mut self) -> Result<Option<Rc<T>>> {
+        match self.try_read_ref()? {
+            Some(value) => match value.downcast_ref::<Rc<T>>() {
+                Some(rc) => Ok(Some(rc.clone())),
+                None => Err(Error::DeserializationFailure(format!(
+                    "Reference points to a value of a different type than {}",
+                    core::any::type_name::<Rc<T>>()
+                ))),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Registers a new `Rc` after [DeserializationContext::try_read_rc] returned `None`, so references to it
+    /// can be resolved while its contents are still being deserialized.
+    ///
+    /// The serializer assigns the id before writing the value, so this has to be called before reading
+    /// anything that can contain references, usually by creating the `Rc` with placeholder contents and
+    /// filling them in afterwards through a `RefCell`. The context keeps a clone of the `Rc` until it is
+    /// dropped.
+    pub fn register_ref<T: Any>(&mut self, value: &Rc<T>) -> Result<RefId> {
+        let id = self.state.reserve_ref_id()?;
+        self.state.store_owned_ref(id, Box::new(value.clone()));
+        Ok(id)
+    }
+
     pub(crate) fn push_region(&mut self, region: InputRegion) {
         let resolved_region = ResolvedInputRegion {
             start: self.current.start + region.start,
@@ -85,39 +260,239 @@ impl<'a> DeserializationContext<'a> {
         result
     }
 
-    pub(crate) fn pos(&self) -> usize {
+    /// The read position within the current input region, which is the whole input outside of
+    /// evolvable types.
+    pub fn pos(&self) -> usize {
         self.current.pos
     }
+
+    /// The number of bytes left in the current input region.
+    ///
+    /// When reading from a `Read` source the end of the input is not known, so this is not meaningful.
+    pub fn remaining(&self) -> usize {
+        self.current.end - self.current.start - self.current.pos
+    }
+
+    /// Error for reading past the end of the current input region, reporting the absolute offset of its end
+    fn input_ended(&self) -> Error {
+        Error::InputEndedUnexpectedly {
+            offset: self.current.end,
+        }
+    }
+
+    /// Continues counting the nesting levels from `depth`, for contexts deserializing a part of a value
+    /// separately, so [Options::max_depth] applies to the whole value
+    pub(crate) fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Runs `f` one nesting level deeper, failing with [Error::RecursionLimitExceeded] if that exceeds
+    /// [Options::max_depth]. Hand-written codecs of recursive types can use it to be protected by the limit.
+    pub fn nested<R>(&mut self, f: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        if let Some(limit) = self.options.max_depth {
+            if self.depth >= limit {
+                return Err(Error::RecursionLimitExceeded { limit });
+            }
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Fails if a collection of `length` elements exceeds the configured maximum collection length
+    pub(crate) fn check_collection_length(&self, length: usize) -> Result<()> {
+        match self.options.max_collection_length {
+            Some(limit) if length > limit => Err(Error::CollectionTooLong { length, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads `count` bytes, returning a slice pointing directly into the input.
+    pub fn read_borrowed_bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        let input = match &self.input {
+            Input::Slice(slice) => *slice,
+            Input::Chained(input) => {
+                if count > self.remaining() {
+                    return Err(self.input_ended());
+                }
+                let start = self.current.start + self.current.pos;
+                let bytes = input
+                    .borrowed_bytes_at(start, count)
+                    .ok_or(Error::BorrowingAcrossSegments)?;
+                self.current.pos += count;
+                return Ok(bytes);
+            }
+            Input::Bytes(_) => return Err(Error::BorrowingFromBytes),
+            Input::Pulled { .. } => return Err(Error::BorrowingFromReader),
+            #[cfg(feature = "std")]
+            Input::Reader { .. } => return Err(Error::BorrowingFromReader),
+        };
+        if count > self.remaining() {
+            Err(self.input_ended())
+        } else {
+            let start = self.current.start + self.current.pos;
+            self.current.pos += count;
+            Ok(&input[start..(start + count)])
+        }
+    }
+
+    /// Reads `count` bytes as [Bytes], sharing the underlying buffer instead of copying if the context
+    /// was created by [DeserializationContext::from_bytes].
+    pub fn read_shared_bytes(&mut self, count: usize) -> Result<Bytes> {
+        match &self.input {
+            Input::Bytes(input) => {
+                if count > self.remaining() {
+                    Err(self.input_ended())
+                } else {
+                    let start = self.current.start + self.current.pos;
+                    self.current.pos += count;
+                    Ok(input.slice(start..(start + count)))
+                }
+            }
+            _ => Ok(Bytes::copy_from_slice(self.read_bytes(count)?)),
+        }
+    }
+}
+
+// Reads the fixed size numeric types in the byte order selected by Options::little_endian
+macro_rules! read_with_byte_order {
+    ($($name:ident: $t:ty),*) => {
+        $(
+            fn $name(&mut self) -> Result<$t> {
+                let bytes = self.read_bytes(size_of::<$t>())?.try_into()?;
+                if self.options.little_endian {
+                    Ok(<$t>::from_le_bytes(bytes))
+                } else {
+                    Ok(<$t>::from_be_bytes(bytes))
+                }
+            }
+        )*
+    };
 }
 
 impl BinaryInput for DeserializationContext<'_> {
     fn read_u8(&mut self) -> Result<u8> {
-        if self.current.pos == self.current.end {
-            Err(Error::InputEndedUnexpectedly)
+        if self.remaining() == 0 {
+            Err(self.input_ended())
         } else {
+            let idx = self.current.start + self.current.pos;
+            self.input.ensure(idx + 1)?;
             self.current.pos += 1;
-            Ok(self.input[self.current.start + self.current.pos - 1])
+            Ok(self.input.read_at(idx, 1)?[0])
         }
     }
 
     fn read_bytes(&mut self, count: usize) -> Result<&[u8]> {
-        if self.current.pos + count > self.current.end {
-            Err(Error::InputEndedUnexpectedly)
+        if count > self.remaining() {
+            Err(self.input_ended())
         } else {
             let start = self.current.start + self.current.pos;
+            self.input.ensure(start + count)?;
             self.current.pos += count;
-            Ok(&self.input[start..(self.current.start + self.current.pos)])
+            self.input.read_at(start, count)
         }
     }
 
     fn skip(&mut self, count: usize) -> Result<()> {
-        if self.current.pos + count > self.current.end {
-            Err(Error::InputEndedUnexpectedly)
+        if count > self.remaining() {
+            Err(self.input_ended())
         } else {
+            self.input
+                .ensure(self.current.start + self.current.pos + count)?;
             self.current.pos += count;
             Ok(())
         }
     }
+
+    read_with_byte_order!(
+        read_u16: u16,
+        read_i16: i16,
+        read_u32: u32,
+        read_i32: i32,
+        read_u64: u64,
+        read_i64: i64,
+        read_u128: u128,
+        read_i128: i128,
+        read_f32: f32,
+        read_f64: f64
+    );
+}
+
+enum Input<'a> {
+    Slice(&'a [u8]),
+    Bytes(Bytes),
+    Chained(ChainedInput<'a>),
+    Pulled {
+        input: Box<dyn BinaryInput + 'a>,
+        buffer: Vec<u8>,
+    },
+    #[cfg(feature = "std")]
+    Reader {
+        reader: Box<dyn Read + 'a>,
+        buffer: Vec<u8>,
+    },
+}
+
+impl Input<'_> {
+    /// Returns `count` bytes starting at `start`, which must have been made available by [Input::ensure]
+    fn read_at(&mut self, start: usize, count: usize) -> Result<&[u8]> {
+        match self {
+            Input::Slice(slice) => Ok(&slice[start..(start + count)]),
+            Input::Bytes(bytes) => Ok(&bytes[start..(start + count)]),
+            Input::Chained(input) => input.bytes_at(start, count),
+            Input::Pulled { buffer, .. } => Ok(&buffer[start..(start + count)]),
+            #[cfg(feature = "std")]
+            Input::Reader { buffer, .. } => Ok(&buffer[start..(start + count)]),
+        }
+    }
+
+    /// Makes sure that the first `len` bytes of the input are available for [Input::read_at]
+    fn ensure(&mut self, len: usize) -> Result<()> {
+        match self {
+            Input::Slice(_) | Input::Bytes(_) | Input::Chained(_) => Ok(()),
+            Input::Pulled { input, buffer } => {
+                if len > buffer.len() {
+                    match input.read_bytes(len - buffer.len()) {
+                        Ok(bytes) => buffer.extend_from_slice(bytes),
+                        Err(Error::InputEndedUnexpectedly { .. }) => {
+                            // Reading the rest byte by byte to report where the input really ended
+                            while let Ok(byte) = input.read_u8() {
+                                buffer.push(byte);
+                            }
+                            return Err(Error::InputEndedUnexpectedly {
+                                offset: buffer.len(),
+                            });
+                        }
+                        Err(err) => return Err(err),
+                    }
+                }
+                Ok(())
+            }
+            #[cfg(feature = "std")]
+            Input::Reader { reader, buffer } => {
+                if len > buffer.len() {
+                    let missing = (len - buffer.len()) as u64;
+                    reader
+                        .by_ref()
+                        .take(missing)
+                        .read_to_end(buffer)
+                        .map_err(|err| Error::ReadFailure(format!("{err}")))?;
+                    if len > buffer.len() {
+                        return Err(Error::InputEndedUnexpectedly {
+                            offset: buffer.len(),
+                        });
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 impl BinaryDeserializer for u8 {
@@ -206,10 +581,21 @@ impl BinaryDeserializer for () {
 
 impl BinaryDeserializer for char {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        if context.options.full_unicode_chars {
+            let code = context.read_u32()?;
+            return char::from_u32(code).ok_or_else(|| {
+                Error::DeserializationFailure(format!(
+                    "Failed to deserialize char: invalid code point {code:#x}"
+                ))
+            });
+        }
         let code = context.read_u16()?;
-        let chars: std::result::Result<Vec<char>, DecodeUtf16Error> =
-            char::decode_utf16([code]).collect();
-        Ok(chars?[0])
+        match char::decode_utf16([code]).next() {
+            Some(char) => Ok(char?),
+            None => Err(Error::DeserializationFailure(format!(
+                "Failed to decode character from {code:#06x}"
+            ))),
+        }
     }
 }
 
@@ -221,11 +607,66 @@ impl BinaryDeserializer for String {
     }
 }
 
+#[cfg(feature = "std")]
+impl BinaryDeserializer for PathBuf {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(PathBuf::from(String::deserialize(context)?))
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+impl BinaryDeserializer for OsString {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        use std::os::unix::ffi::OsStringExt;
+
+        match context.read_u8()? {
+            crate::OS_STRING_BYTES_TAG => Ok(OsString::from_vec(Vec::deserialize(context)?)),
+            tag @ crate::OS_STRING_WIDE_TAG => Err(Error::IncompatibleOsStringEncoding(tag)),
+            tag => Err(Error::DeserializationFailure(format!(
+                "Invalid OS string encoding tag: {tag}"
+            ))),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", windows))]
+impl BinaryDeserializer for OsString {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        use std::os::windows::ffi::OsStringExt;
+
+        match context.read_u8()? {
+            crate::OS_STRING_WIDE_TAG => {
+                let wide: Vec<u16> = Vec::deserialize(context)?;
+                Ok(OsString::from_wide(&wide))
+            }
+            tag @ crate::OS_STRING_BYTES_TAG => Err(Error::IncompatibleOsStringEncoding(tag)),
+            tag => Err(Error::DeserializationFailure(format!(
+                "Invalid OS string encoding tag: {tag}"
+            ))),
+        }
+    }
+}
+
+impl<'a> BinaryDeserializeBorrowed<'a> for &'a str {
+    fn deserialize_borrowed(context: &mut DeserializationContext<'a>) -> Result<Self> {
+        let length = context.read_var_i32()?;
+        let bytes = context.read_borrowed_bytes(length as usize)?;
+        Ok(core::str::from_utf8(bytes)?)
+    }
+}
+
+impl<'a> BinaryDeserializeBorrowed<'a> for &'a [u8] {
+    fn deserialize_borrowed(context: &mut DeserializationContext<'a>) -> Result<Self> {
+        let length = context.read_var_u32()?;
+        context.read_borrowed_bytes(length as usize)
+    }
+}
+
 impl BinaryDeserializer for DeduplicatedString {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
         let count_or_id = context.read_var_i32()?;
         if count_or_id < 0 {
-            let id = StringId(-count_or_id);
+            let id = StringId(count_or_id.wrapping_neg());
             match context.state().get_string_by_id(id) {
                 Some(s) => Ok(DeduplicatedString(s.to_string())),
                 None => Err(Error::InvalidStringId(id)),
@@ -233,17 +674,186 @@ impl BinaryDeserializer for DeduplicatedString {
         } else {
             let bytes = context.read_bytes(count_or_id as usize)?;
             let s = String::from_utf8(bytes.to_vec())?;
-            context.state_mut().store_string(s.clone());
+            context.state_mut().store_deserialized_string(s.clone())?;
             Ok(DeduplicatedString(s))
         }
     }
 }
 
+impl BinaryDeserializer for Leb128<u32> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let value = context.read_leb128_u64()?;
+        let value = u32::try_from(value).map_err(|_| {
+            Error::DeserializationFailure(format!(
+                "Failed to deserialize Leb128<u32>: {value} is out of range"
+            ))
+        })?;
+        Ok(Leb128(value))
+    }
+}
+
+impl BinaryDeserializer for Leb128<i32> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let value = context.read_leb128_i64()?;
+        let value = i32::try_from(value).map_err(|_| {
+            Error::DeserializationFailure(format!(
+                "Failed to deserialize Leb128<i32>: {value} is out of range"
+            ))
+        })?;
+        Ok(Leb128(value))
+    }
+}
+
+impl BinaryDeserializer for Leb128<u64> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Leb128(context.read_leb128_u64()?))
+    }
+}
+
+impl BinaryDeserializer for Leb128<i64> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Leb128(context.read_leb128_i64()?))
+    }
+}
+
+impl BinaryDeserializer for VarInt<u32> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(VarInt(context.read_var_u32()?))
+    }
+}
+
+impl BinaryDeserializer for VarInt<i32> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(VarInt(context.read_var_i32()?))
+    }
+}
+
+impl BinaryDeserializer for VarInt<u64> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(VarInt(context.read_var_u64()?))
+    }
+}
+
+impl BinaryDeserializer for VarInt<i64> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(VarInt(context.read_var_i64()?))
+    }
+}
+
+impl<T: BinaryDeserializer + Clone + 'static> BinaryDeserializer for Deduplicated<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let length_or_id = context.read_var_i32()?;
+        if length_or_id < 0 {
+            let id = ValueId(length_or_id.wrapping_neg());
+            match context.state().get_value_by_id(id) {
+                Some(value) => value
+                    .downcast_ref::<T>()
+                    .map(|value| Deduplicated(value.clone()))
+                    .ok_or(Error::InvalidValueId(id)),
+                None => Err(Error::InvalidValueId(id)),
+            }
+        } else {
+            let options = context.options.clone();
+            let depth = context.depth;
+            let value = {
+                let bytes = context.read_bytes(length_or_id as usize)?;
+                let mut inner = DeserializationContext::new(bytes)
+                    .with_options(options)
+                    .with_depth(depth);
+                T::deserialize(&mut inner)?
+            };
+            context
+                .state_mut()
+                .store_deserialized_value(Box::new(value.clone()))?;
+            Ok(Deduplicated(value))
+        }
+    }
+}
+
 impl BinaryDeserializer for Duration {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
         let seconds = context.read_u64()?;
         let nanos = context.read_u32()?;
-        Ok(Duration::new(seconds, nanos))
+        if nanos >= 1_000_000_000 {
+            Err(Error::DeserializationFailure(format!(
+                "Failed to deserialize Duration: invalid nanoseconds {nanos}"
+            )))
+        } else {
+            Ok(Duration::new(seconds, nanos))
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl BinaryDeserializer for crate::RelativeInstant {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(crate::RelativeInstant(Duration::deserialize(context)?))
+    }
+}
+
+#[cfg(feature = "std")]
+impl BinaryDeserializer for SystemTime {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let duration = Duration::deserialize(context)?;
+        UNIX_EPOCH.checked_add(duration).ok_or_else(|| {
+            Error::DeserializationFailure(format!(
+                "System time {duration:?} after the Unix epoch is out of range"
+            ))
+        })
+    }
+}
+
+impl BinaryDeserializer for Ipv4Addr {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let octets: [u8; 4] = context.read_bytes(4)?.try_into()?;
+        Ok(Ipv4Addr::from(octets))
+    }
+}
+
+impl BinaryDeserializer for Ipv6Addr {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let octets: [u8; 16] = context.read_bytes(16)?.try_into()?;
+        Ok(Ipv6Addr::from(octets))
+    }
+}
+
+impl BinaryDeserializer for IpAddr {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        match context.read_u8()? {
+            0 => Ok(IpAddr::V4(Ipv4Addr::deserialize(context)?)),
+            1 => Ok(IpAddr::V6(Ipv6Addr::deserialize(context)?)),
+            other => Err(Error::DeserializationFailure(format!(
+                "Failed to deserialize IpAddr: invalid tag: {other}"
+            ))),
+        }
+    }
+}
+
+impl BinaryDeserializer for SocketAddrV4 {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let ip = Ipv4Addr::deserialize(context)?;
+        let port = context.read_u16()?;
+        Ok(SocketAddrV4::new(ip, port))
+    }
+}
+
+impl BinaryDeserializer for SocketAddrV6 {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let ip = Ipv6Addr::deserialize(context)?;
+        let port = context.read_u16()?;
+        Ok(SocketAddrV6::new(ip, port, 0, 0))
+    }
+}
+
+impl BinaryDeserializer for SocketAddr {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        match context.read_u8()? {
+            0 => Ok(SocketAddr::V4(SocketAddrV4::deserialize(context)?)),
+            1 => Ok(SocketAddr::V6(SocketAddrV6::deserialize(context)?)),
+            other => Err(Error::DeserializationFailure(format!(
+                "Failed to deserialize SocketAddr: invalid tag: {other}"
+            ))),
+        }
     }
 }
 
@@ -260,7 +870,7 @@ impl<T: BinaryDeserializer> BinaryDeserializer for Option<T> {
 }
 
 impl<R: BinaryDeserializer, E: BinaryDeserializer> BinaryDeserializer
-    for std::result::Result<R, E>
+    for core::result::Result<R, E>
 {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
         match context.read_u8()? {
@@ -276,8 +886,17 @@ impl<R: BinaryDeserializer, E: BinaryDeserializer> BinaryDeserializer
 impl BinaryDeserializer for Bytes {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
         let length = context.read_var_u32()?; // NOTE: this is inconsistent with the generic case, but this way it is compatible with the Scala version's Chunk serializer
+        context.check_collection_length(length as usize)?;
+        context.read_shared_bytes(length as usize)
+    }
+}
+
+impl BinaryDeserializer for BytesMut {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let length = context.read_var_u32()?;
+        context.check_collection_length(length as usize)?;
         let bytes = context.read_bytes(length as usize)?;
-        Ok(Bytes::from(bytes.to_vec()))
+        Ok(BytesMut::from(bytes))
     }
 }
 
@@ -286,15 +905,133 @@ impl<T: BinaryDeserializer, const L: usize> BinaryDeserializer for [T; L] {
         let empty: [T; 0] = [];
         if cast!(empty, [u8; 0]).is_ok() {
             let length = context.read_var_u32()?; // NOTE: this is inconsistent with the generic case, but this way it is compatible with the Scala version's Chunk serializer
-            let bytes = context.read_bytes(length as usize)?;
-            Ok(unsafe { std::mem::transmute_copy::<_, [T; L]>(&bytes) })
+            context.check_collection_length(length as usize)?;
+            if length as usize != L {
+                return Err(array_length_mismatch::<L>());
+            }
+            let bytes: [u8; L] = context.read_bytes(L)?.try_into()?;
+            Ok(unsafe { core::mem::transmute_copy::<[u8; L], [T; L]>(&bytes) })
+        } else if let Some(result) = try_deserialize_numeric_vec::<T>(context) {
+            let mut array = PartialArray::<T, L>::new();
+            for item in result? {
+                if array.initialized == L {
+                    return Err(array_length_mismatch::<L>());
+                }
+                array.push(item);
+            }
+            array.finish()
         } else {
-            let mut array: [MaybeUninit<T>; L] = unsafe { MaybeUninit::uninit().assume_init() };
-            for (target, item) in array.iter_mut().zip(deserialize_iterator(context)) {
-                *target = MaybeUninit::new(item?);
+            let mut array = PartialArray::<T, L>::new();
+            for item in deserialize_iterator(context).0 {
+                if array.initialized == L {
+                    return Err(array_length_mismatch::<L>());
+                }
+                array.push(item?);
             }
-            let array: [T; L] = unsafe { std::mem::transmute_copy(&array) };
-            Ok(array)
+            array.finish()
+        }
+    }
+}
+
+/// Reinterprets a `Vec<E>` as `Vec<T>` without copying.
+///
+/// # Safety
+/// `E` and `T` must be the same type.
+unsafe fn reinterpret_vec<E, T>(vec: Vec<E>) -> Vec<T> {
+    let mut vec = ManuallyDrop::new(vec);
+    Vec::from_raw_parts(vec.as_mut_ptr() as *mut T, vec.len(), vec.capacity())
+}
+
+/// Deserializes a vector of a fixed size numeric type, reading all the elements with a single `read_bytes`
+/// call when the length is known up front. Returns `None` if `T` is not one of these types.
+fn try_deserialize_numeric_vec<T>(
+    context: &mut DeserializationContext<'_>,
+) -> Option<Result<Vec<T>>> {
+    macro_rules! try_types {
+        ($($t:ty),*) => {
+            $(
+                let empty: Vec<T> = Vec::new();
+                if cast!(empty, Vec<$t>).is_ok() {
+                    let from_bytes = if context.options.little_endian {
+                        <$t>::from_le_bytes
+                    } else {
+                        <$t>::from_be_bytes
+                    };
+                    let result = deserialize_numeric_bytes(context, from_bytes);
+                    // T is known to be $t here
+                    return Some(result.map(|vec| unsafe { reinterpret_vec(vec) }));
+                }
+            )*
+        };
+    }
+    try_types!(u16, i16, u32, i32, u64, i64, u128, i128, f32, f64);
+    None
+}
+
+fn deserialize_numeric_bytes<E: BinaryDeserializer, const N: usize>(
+    context: &mut DeserializationContext<'_>,
+    from_bytes: fn([u8; N]) -> E,
+) -> Result<Vec<E>> {
+    match context.read_var_i32()? {
+        -1 => DeserializerIterator::UnknownSize {
+            context,
+            element: PhantomData,
+        }
+        .collect(),
+        length => {
+            context.check_collection_length(length as usize)?;
+            let size = (length as usize)
+                .checked_mul(N)
+                .ok_or(Error::LengthTooLarge)?;
+            context
+                .read_bytes(size)?
+                .chunks_exact(N)
+                .map(|chunk| Ok(from_bytes(chunk.try_into()?)))
+                .collect()
+        }
+    }
+}
+
+fn array_length_mismatch<const L: usize>() -> Error {
+    Error::DeserializationFailure(format!(
+        "Serialized array length does not match the expected length {L}"
+    ))
+}
+
+/// Array being deserialized, dropping the already initialized elements if it is not completed
+struct PartialArray<T, const L: usize> {
+    array: [MaybeUninit<T>; L],
+    initialized: usize,
+}
+
+impl<T, const L: usize> PartialArray<T, L> {
+    fn new() -> Self {
+        Self {
+            array: [const { MaybeUninit::uninit() }; L],
+            initialized: 0,
+        }
+    }
+
+    fn push(&mut self, item: T) {
+        self.array[self.initialized].write(item);
+        self.initialized += 1;
+    }
+
+    fn finish(mut self) -> Result<[T; L]> {
+        if self.initialized == L {
+            // Ownership of the elements moves to the result, so the guard must not drop them
+            self.initialized = 0;
+            Ok(unsafe { core::mem::transmute_copy::<[MaybeUninit<T>; L], [T; L]>(&self.array) })
+        } else {
+            Err(array_length_mismatch::<L>())
+        }
+    }
+}
+
+impl<T, const L: usize> Drop for PartialArray<T, L> {
+    fn drop(&mut self) {
+        for item in &mut self.array[..self.initialized] {
+            unsafe { item.assume_init_drop() };
         }
     }
 }
@@ -304,11 +1041,17 @@ impl<T: BinaryDeserializer> BinaryDeserializer for Vec<T> {
         let empty: Self = Vec::new();
         if cast!(empty, Vec<u8>).is_ok() {
             let length = context.read_var_u32()?; // NOTE: this is inconsistent with the generic case, but this way it is compatible with the Scala version's Chunk serializer
+            context.check_collection_length(length as usize)?;
             let bytes = context.read_bytes(length as usize)?;
-            unsafe { Ok(std::mem::transmute::<Vec<u8>, Vec<T>>(bytes.to_vec())) }
+            // T is known to be u8 here, so the buffer can be taken over as it is
+            Ok(unsafe { reinterpret_vec(bytes.to_vec()) })
+        } else if let Some(result) = try_deserialize_numeric_vec::<T>(context) {
+            result
         } else {
-            let mut vec = Vec::new();
-            for item in deserialize_iterator(context) {
+            let remaining = context.remaining();
+            let (items, size) = deserialize_iterator(context);
+            let mut vec = Vec::with_capacity(initial_capacity::<T>(size, remaining));
+            for item in items {
                 vec.push(item?);
             }
             Ok(vec)
@@ -316,53 +1059,213 @@ impl<T: BinaryDeserializer> BinaryDeserializer for Vec<T> {
     }
 }
 
-impl<T: BinaryDeserializer + Eq + Hash> BinaryDeserializer for HashSet<T> {
+#[cfg(feature = "std")]
+impl<T: BinaryDeserializer + Eq + Hash, S: BuildHasher + Default> BinaryDeserializer
+    for HashSet<T, S>
+{
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
-        deserialize_iterator(context).collect()
+        deserialize_iterator(context).0.collect()
     }
 }
 
 impl<T: BinaryDeserializer + Ord> BinaryDeserializer for BTreeSet<T> {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
-        deserialize_iterator(context).collect()
+        deserialize_iterator(context).0.collect()
     }
 }
 
-impl<K: BinaryDeserializer + Eq + Hash, V: BinaryDeserializer> BinaryDeserializer
-    for HashMap<K, V>
+#[cfg(feature = "std")]
+impl<K: BinaryDeserializer + Eq + Hash, V: BinaryDeserializer, S: BuildHasher + Default>
+    BinaryDeserializer for HashMap<K, V, S>
 {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
-        deserialize_iterator(context).collect()
+        deserialize_iterator(context).0.collect()
     }
 }
 
 impl<K: BinaryDeserializer + Ord, V: BinaryDeserializer> BinaryDeserializer for BTreeMap<K, V> {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
-        deserialize_iterator(context).collect()
+        // Collecting buffers the entries and sorts them, which only takes a single pass over the already
+        // sorted input written by the BTreeMap serializer, and then bulk-builds the tree instead of
+        // inserting the entries one by one
+        deserialize_iterator(context).0.collect()
     }
 }
 
 impl<T: BinaryDeserializer + Eq + Hash> BinaryDeserializer for LinkedList<T> {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
-        deserialize_iterator(context).collect()
+        deserialize_iterator(context).0.collect()
+    }
+}
+
+impl<T: BinaryDeserializer + Ord> BinaryDeserializer for BinaryHeap<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        deserialize_iterator(context).0.collect()
     }
 }
 
 impl<T: BinaryDeserializer> BinaryDeserializer for Box<T> {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
-        Ok(Box::new(T::deserialize(context)?))
+        Ok(Box::new(context.nested(T::deserialize)?))
     }
 }
 
 impl<T: BinaryDeserializer> BinaryDeserializer for Rc<T> {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
-        Ok(Rc::new(T::deserialize(context)?))
+        Ok(Rc::new(context.nested(T::deserialize)?))
     }
 }
 
 impl<T: BinaryDeserializer> BinaryDeserializer for Arc<T> {
     fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
-        Ok(Arc::new(T::deserialize(context)?))
+        Ok(Arc::new(context.nested(T::deserialize)?))
+    }
+}
+
+impl<T: BinaryDeserializer + 'static> BinaryDeserializer for SharedRef<Rc<T>> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(SharedRef(deserialize_shared_ref(context, Rc::new)?))
+    }
+}
+
+impl<T: BinaryDeserializer + 'static> BinaryDeserializer for SharedRef<Arc<T>> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(SharedRef(deserialize_shared_ref(context, Arc::new)?))
+    }
+}
+
+fn deserialize_shared_ref<T: BinaryDeserializer, P: Clone + 'static>(
+    context: &mut DeserializationContext<'_>,
+    wrap: impl FnOnce(T) -> P,
+) -> Result<P> {
+    match context.try_read_ref()? {
+        Some(value) => value.downcast_ref::<P>().cloned().ok_or_else(|| {
+            Error::DeserializationFailure(format!(
+                "Shared reference points to a value of a different type than {}",
+                core::any::type_name::<P>()
+            ))
+        }),
+        None => {
+            let id = context.state_mut().reserve_ref_id()?;
+            let value = wrap(T::deserialize(context)?);
+            context
+                .state_mut()
+                .store_owned_ref(id, Box::new(value.clone()));
+            Ok(value)
+        }
+    }
+}
+
+impl<T: BinaryDeserializer> BinaryDeserializer for Wrapping<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Wrapping(T::deserialize(context)?))
+    }
+}
+
+impl<T: BinaryDeserializer> BinaryDeserializer for Saturating<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Saturating(T::deserialize(context)?))
+    }
+}
+
+impl<T: BinaryDeserializer> BinaryDeserializer for Reverse<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Reverse(T::deserialize(context)?))
+    }
+}
+
+macro_rules! non_zero_deserializer {
+    ($($t:ty => $inner:ty),*) => {
+        $(
+            impl BinaryDeserializer for $t {
+                fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+                    <$t>::new(<$inner>::deserialize(context)?).ok_or(Error::InvalidNonZero)
+                }
+            }
+        )*
+    };
+}
+
+non_zero_deserializer!(
+    NonZeroU8 => u8,
+    NonZeroI8 => i8,
+    NonZeroU16 => u16,
+    NonZeroI16 => i16,
+    NonZeroU32 => u32,
+    NonZeroI32 => i32,
+    NonZeroU64 => u64,
+    NonZeroI64 => i64,
+    NonZeroU128 => u128,
+    NonZeroI128 => i128
+);
+
+impl<T: BinaryDeserializer> BinaryDeserializer for Range<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let start = T::deserialize(context)?;
+        let end = T::deserialize(context)?;
+        Ok(start..end)
+    }
+}
+
+impl<T: BinaryDeserializer> BinaryDeserializer for RangeInclusive<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let start = T::deserialize(context)?;
+        let end = T::deserialize(context)?;
+        Ok(start..=end)
+    }
+}
+
+impl<T: BinaryDeserializer> BinaryDeserializer for Cell<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Cell::new(T::deserialize(context)?))
+    }
+}
+
+impl<T: BinaryDeserializer> BinaryDeserializer for RefCell<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(RefCell::new(T::deserialize(context)?))
+    }
+}
+
+impl<T: BinaryDeserializer> BinaryDeserializer for Box<[T]> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Vec::<T>::deserialize(context)?.into_boxed_slice())
+    }
+}
+
+impl BinaryDeserializer for Box<str> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(String::deserialize(context)?.into_boxed_str())
+    }
+}
+
+impl BinaryDeserializer for Rc<str> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Rc::from(String::deserialize(context)?))
+    }
+}
+
+impl BinaryDeserializer for Arc<str> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Arc::from(String::deserialize(context)?))
+    }
+}
+
+impl BinaryDeserializer for Cow<'_, str> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Cow::Owned(String::deserialize(context)?))
+    }
+}
+
+impl<T: BinaryDeserializer + Clone> BinaryDeserializer for Cow<'_, [T]> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Cow::Owned(Vec::<T>::deserialize(context)?))
+    }
+}
+
+impl<T: BinaryDeserializer + Clone> BinaryDeserializer for Cow<'_, T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(Cow::Owned(T::deserialize(context)?))
     }
 }
 
@@ -372,24 +1275,62 @@ impl<T> BinaryDeserializer for PhantomData<T> {
     }
 }
 
-fn deserialize_iterator<'a, 'b, T: BinaryDeserializer + 'a>(
+/// Helper function for implementing deserialization of collections written by
+/// [serialize_iterator](crate::serialize_iterator), returning an iterator of the elements and the number of
+/// elements if it was written before them.
+///
+/// The number of elements comes from the input, and it is only checked against
+/// [Options::max_collection_length], so it should not be trusted when preallocating for untrusted input.
+pub fn deserialize_iterator<'a, 'b, T: BinaryDeserializer + 'a>(
+    context: &'a mut DeserializationContext<'b>,
+) -> (
+    impl Iterator<Item = Result<T>> + use<'a, 'b, T>,
+    Option<usize>,
+) {
+    let iterator = iterate(context);
+    let size = match &iterator {
+        DeserializerIterator::KnownSize { remaining, .. } => Some(*remaining),
+        _ => None,
+    };
+    (iterator, size)
+}
+
+fn iterate<'a, 'b, T: BinaryDeserializer + 'a>(
     context: &'a mut DeserializationContext<'b>,
 ) -> DeserializerIterator<'a, 'b, T> {
     match context.read_var_i32() {
-        Err(_) => DeserializerIterator::InputEndedUnexpectedly,
+        Err(err) => DeserializerIterator::Failed(Some(err)),
         Ok(-1) => DeserializerIterator::UnknownSize {
             context,
             element: PhantomData,
         },
-        Ok(length) => DeserializerIterator::KnownSize {
-            context,
-            remaining: length as usize,
-            element: PhantomData,
+        Ok(length) => match context.check_collection_length(length as usize) {
+            Ok(()) => DeserializerIterator::KnownSize {
+                context,
+                remaining: length as usize,
+                element: PhantomData,
+            },
+            Err(err) => DeserializerIterator::Failed(Some(err)),
         },
     }
 }
 
-enum DeserializerIterator<'a, 'b, T: BinaryDeserializer + 'a> {
+/// Upper limit of the memory preallocated for a collection by its length prefix, so a crafted length cannot
+/// make it allocate a huge buffer before reading any of the elements
+pub(crate) const MAX_PREALLOCATION: usize = 1024 * 1024;
+
+/// Capacity to preallocate for a collection of `size` elements, if it is known, read from an input having
+/// `remaining` bytes left.
+///
+/// It is limited by the remaining input, as elements are rarely serialized to zero bytes, and by
+/// [MAX_PREALLOCATION] for inputs of unknown length.
+fn initial_capacity<T>(size: Option<usize>, remaining: usize) -> usize {
+    size.unwrap_or(0)
+        .min(remaining)
+        .min(MAX_PREALLOCATION / size_of::<T>().max(1))
+}
+
+pub(crate) enum DeserializerIterator<'a, 'b, T: BinaryDeserializer + 'a> {
     KnownSize {
         context: &'a mut DeserializationContext<'b>,
         remaining: usize,
@@ -399,7 +1340,7 @@ enum DeserializerIterator<'a, 'b, T: BinaryDeserializer + 'a> {
         context: &'a mut DeserializationContext<'b>,
         element: PhantomData<T>,
     },
-    InputEndedUnexpectedly,
+    Failed(Option<Error>),
 }
 
 impl<'a, T: BinaryDeserializer + 'a> Iterator for DeserializerIterator<'a, '_, T> {
@@ -407,9 +1348,7 @@ impl<'a, T: BinaryDeserializer + 'a> Iterator for DeserializerIterator<'a, '_, T
 
     fn next(&mut self) -> Option<Self::Item> {
         match self {
-            DeserializerIterator::InputEndedUnexpectedly => {
-                Some(Err(Error::InputEndedUnexpectedly))
-            }
+            DeserializerIterator::Failed(err) => err.take().map(Err),
             DeserializerIterator::KnownSize {
                 ref mut context,
                 remaining,
@@ -126,6 +126,303 @@ fn deserialize_tuple8<
     ))
 }
 
+fn deserialize_tuple9<
+    T1: BinaryDeserializer,
+    T2: BinaryDeserializer,
+    T3: BinaryDeserializer,
+    T4: BinaryDeserializer,
+    T5: BinaryDeserializer,
+    T6: BinaryDeserializer,
+    T7: BinaryDeserializer,
+    T8: BinaryDeserializer,
+    T9: BinaryDeserializer,
+>(
+    deserializer: &mut AdtDeserializer,
+) -> crate::Result<(T1, T2, T3, T4, T5, T6, T7, T8, T9)> {
+    Ok((
+        deserializer.read_field("_0", None)?,
+        deserializer.read_field("_1", None)?,
+        deserializer.read_field("_2", None)?,
+        deserializer.read_field("_3", None)?,
+        deserializer.read_field("_4", None)?,
+        deserializer.read_field("_5", None)?,
+        deserializer.read_field("_6", None)?,
+        deserializer.read_field("_7", None)?,
+        deserializer.read_field("_8", None)?,
+    ))
+}
+
+fn deserialize_tuple10<
+    T1: BinaryDeserializer,
+    T2: BinaryDeserializer,
+    T3: BinaryDeserializer,
+    T4: BinaryDeserializer,
+    T5: BinaryDeserializer,
+    T6: BinaryDeserializer,
+    T7: BinaryDeserializer,
+    T8: BinaryDeserializer,
+    T9: BinaryDeserializer,
+    T10: BinaryDeserializer,
+>(
+    deserializer: &mut AdtDeserializer,
+) -> crate::Result<(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10)> {
+    Ok((
+        deserializer.read_field("_0", None)?,
+        deserializer.read_field("_1", None)?,
+        deserializer.read_field("_2", None)?,
+        deserializer.read_field("_3", None)?,
+        deserializer.read_field("_4", None)?,
+        deserializer.read_field("_5", None)?,
+        deserializer.read_field("_6", None)?,
+        deserializer.read_field("_7", None)?,
+        deserializer.read_field("_8", None)?,
+        deserializer.read_field("_9", None)?,
+    ))
+}
+
+fn deserialize_tuple11<
+    T1: BinaryDeserializer,
+    T2: BinaryDeserializer,
+    T3: BinaryDeserializer,
+    T4: BinaryDeserializer,
+    T5: BinaryDeserializer,
+    T6: BinaryDeserializer,
+    T7: BinaryDeserializer,
+    T8: BinaryDeserializer,
+    T9: BinaryDeserializer,
+    T10: BinaryDeserializer,
+    T11: BinaryDeserializer,
+>(
+    deserializer: &mut AdtDeserializer,
+) -> crate::Result<(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11)> {
+    Ok((
+        deserializer.read_field("_0", None)?,
+        deserializer.read_field("_1", None)?,
+        deserializer.read_field("_2", None)?,
+        deserializer.read_field("_3", None)?,
+        deserializer.read_field("_4", None)?,
+        deserializer.read_field("_5", None)?,
+        deserializer.read_field("_6", None)?,
+        deserializer.read_field("_7", None)?,
+        deserializer.read_field("_8", None)?,
+        deserializer.read_field("_9", None)?,
+        deserializer.read_field("_10", None)?,
+    ))
+}
+
+fn deserialize_tuple12<
+    T1: BinaryDeserializer,
+    T2: BinaryDeserializer,
+    T3: BinaryDeserializer,
+    T4: BinaryDeserializer,
+    T5: BinaryDeserializer,
+    T6: BinaryDeserializer,
+    T7: BinaryDeserializer,
+    T8: BinaryDeserializer,
+    T9: BinaryDeserializer,
+    T10: BinaryDeserializer,
+    T11: BinaryDeserializer,
+    T12: BinaryDeserializer,
+>(
+    deserializer: &mut AdtDeserializer,
+) -> crate::Result<(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12)> {
+    Ok((
+        deserializer.read_field("_0", None)?,
+        deserializer.read_field("_1", None)?,
+        deserializer.read_field("_2", None)?,
+        deserializer.read_field("_3", None)?,
+        deserializer.read_field("_4", None)?,
+        deserializer.read_field("_5", None)?,
+        deserializer.read_field("_6", None)?,
+        deserializer.read_field("_7", None)?,
+        deserializer.read_field("_8", None)?,
+        deserializer.read_field("_9", None)?,
+        deserializer.read_field("_10", None)?,
+        deserializer.read_field("_11", None)?,
+    ))
+}
+
+fn deserialize_tuple13<
+    T1: BinaryDeserializer,
+    T2: BinaryDeserializer,
+    T3: BinaryDeserializer,
+    T4: BinaryDeserializer,
+    T5: BinaryDeserializer,
+    T6: BinaryDeserializer,
+    T7: BinaryDeserializer,
+    T8: BinaryDeserializer,
+    T9: BinaryDeserializer,
+    T10: BinaryDeserializer,
+    T11: BinaryDeserializer,
+    T12: BinaryDeserializer,
+    T13: BinaryDeserializer,
+>(
+    deserializer: &mut AdtDeserializer,
+) -> crate::Result<(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13)> {
+    Ok((
+        deserializer.read_field("_0", None)?,
+        deserializer.read_field("_1", None)?,
+        deserializer.read_field("_2", None)?,
+        deserializer.read_field("_3", None)?,
+        deserializer.read_field("_4", None)?,
+        deserializer.read_field("_5", None)?,
+        deserializer.read_field("_6", None)?,
+        deserializer.read_field("_7", None)?,
+        deserializer.read_field("_8", None)?,
+        deserializer.read_field("_9", None)?,
+        deserializer.read_field("_10", None)?,
+        deserializer.read_field("_11", None)?,
+        deserializer.read_field("_12", None)?,
+    ))
+}
+
+fn deserialize_tuple14<
+    T1: BinaryDeserializer,
+    T2: BinaryDeserializer,
+    T3: BinaryDeserializer,
+    T4: BinaryDeserializer,
+    T5: BinaryDeserializer,
+    T6: BinaryDeserializer,
+    T7: BinaryDeserializer,
+    T8: BinaryDeserializer,
+    T9: BinaryDeserializer,
+    T10: BinaryDeserializer,
+    T11: BinaryDeserializer,
+    T12: BinaryDeserializer,
+    T13: BinaryDeserializer,
+    T14: BinaryDeserializer,
+>(
+    deserializer: &mut AdtDeserializer,
+) -> crate::Result<(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14)> {
+    Ok((
+        deserializer.read_field("_0", None)?,
+        deserializer.read_field("_1", None)?,
+        deserializer.read_field("_2", None)?,
+        deserializer.read_field("_3", None)?,
+        deserializer.read_field("_4", None)?,
+        deserializer.read_field("_5", None)?,
+        deserializer.read_field("_6", None)?,
+        deserializer.read_field("_7", None)?,
+        deserializer.read_field("_8", None)?,
+        deserializer.read_field("_9", None)?,
+        deserializer.read_field("_10", None)?,
+        deserializer.read_field("_11", None)?,
+        deserializer.read_field("_12", None)?,
+        deserializer.read_field("_13", None)?,
+    ))
+}
+
+fn deserialize_tuple15<
+    T1: BinaryDeserializer,
+    T2: BinaryDeserializer,
+    T3: BinaryDeserializer,
+    T4: BinaryDeserializer,
+    T5: BinaryDeserializer,
+    T6: BinaryDeserializer,
+    T7: BinaryDeserializer,
+    T8: BinaryDeserializer,
+    T9: BinaryDeserializer,
+    T10: BinaryDeserializer,
+    T11: BinaryDeserializer,
+    T12: BinaryDeserializer,
+    T13: BinaryDeserializer,
+    T14: BinaryDeserializer,
+    T15: BinaryDeserializer,
+>(
+    deserializer: &mut AdtDeserializer,
+) -> crate::Result<(
+    T1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    T8,
+    T9,
+    T10,
+    T11,
+    T12,
+    T13,
+    T14,
+    T15,
+)> {
+    Ok((
+        deserializer.read_field("_0", None)?,
+        deserializer.read_field("_1", None)?,
+        deserializer.read_field("_2", None)?,
+        deserializer.read_field("_3", None)?,
+        deserializer.read_field("_4", None)?,
+        deserializer.read_field("_5", None)?,
+        deserializer.read_field("_6", None)?,
+        deserializer.read_field("_7", None)?,
+        deserializer.read_field("_8", None)?,
+        deserializer.read_field("_9", None)?,
+        deserializer.read_field("_10", None)?,
+        deserializer.read_field("_11", None)?,
+        deserializer.read_field("_12", None)?,
+        deserializer.read_field("_13", None)?,
+        deserializer.read_field("_14", None)?,
+    ))
+}
+
+fn deserialize_tuple16<
+    T1: BinaryDeserializer,
+    T2: BinaryDeserializer,
+    T3: BinaryDeserializer,
+    T4: BinaryDeserializer,
+    T5: BinaryDeserializer,
+    T6: BinaryDeserializer,
+    T7: BinaryDeserializer,
+    T8: BinaryDeserializer,
+    T9: BinaryDeserializer,
+    T10: BinaryDeserializer,
+    T11: BinaryDeserializer,
+    T12: BinaryDeserializer,
+    T13: BinaryDeserializer,
+    T14: BinaryDeserializer,
+    T15: BinaryDeserializer,
+    T16: BinaryDeserializer,
+>(
+    deserializer: &mut AdtDeserializer,
+) -> crate::Result<(
+    T1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    T8,
+    T9,
+    T10,
+    T11,
+    T12,
+    T13,
+    T14,
+    T15,
+    T16,
+)> {
+    Ok((
+        deserializer.read_field("_0", None)?,
+        deserializer.read_field("_1", None)?,
+        deserializer.read_field("_2", None)?,
+        deserializer.read_field("_3", None)?,
+        deserializer.read_field("_4", None)?,
+        deserializer.read_field("_5", None)?,
+        deserializer.read_field("_6", None)?,
+        deserializer.read_field("_7", None)?,
+        deserializer.read_field("_8", None)?,
+        deserializer.read_field("_9", None)?,
+        deserializer.read_field("_10", None)?,
+        deserializer.read_field("_11", None)?,
+        deserializer.read_field("_12", None)?,
+        deserializer.read_field("_13", None)?,
+        deserializer.read_field("_14", None)?,
+        deserializer.read_field("_15", None)?,
+    ))
+}
+
 impl<T1: BinaryDeserializer> BinaryDeserializer for (T1,) {
     fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
         let stored_version = context.read_u8()?;
@@ -287,3 +584,274 @@ impl<
         }
     }
 }
+
+impl<
+        T1: BinaryDeserializer,
+        T2: BinaryDeserializer,
+        T3: BinaryDeserializer,
+        T4: BinaryDeserializer,
+        T5: BinaryDeserializer,
+        T6: BinaryDeserializer,
+        T7: BinaryDeserializer,
+        T8: BinaryDeserializer,
+        T9: BinaryDeserializer,
+    > BinaryDeserializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9)
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+        let stored_version = context.read_u8()?;
+
+        if stored_version == 0 {
+            let mut deserializer = AdtDeserializer::new_v0(&EMPTY_ADT_METADATA, context)?;
+            deserialize_tuple9(&mut deserializer)
+        } else {
+            let mut deserializer =
+                AdtDeserializer::new(&EMPTY_ADT_METADATA, context, stored_version)?;
+            deserialize_tuple9(&mut deserializer)
+        }
+    }
+}
+
+impl<
+        T1: BinaryDeserializer,
+        T2: BinaryDeserializer,
+        T3: BinaryDeserializer,
+        T4: BinaryDeserializer,
+        T5: BinaryDeserializer,
+        T6: BinaryDeserializer,
+        T7: BinaryDeserializer,
+        T8: BinaryDeserializer,
+        T9: BinaryDeserializer,
+        T10: BinaryDeserializer,
+    > BinaryDeserializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10)
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+        let stored_version = context.read_u8()?;
+
+        if stored_version == 0 {
+            let mut deserializer = AdtDeserializer::new_v0(&EMPTY_ADT_METADATA, context)?;
+            deserialize_tuple10(&mut deserializer)
+        } else {
+            let mut deserializer =
+                AdtDeserializer::new(&EMPTY_ADT_METADATA, context, stored_version)?;
+            deserialize_tuple10(&mut deserializer)
+        }
+    }
+}
+
+impl<
+        T1: BinaryDeserializer,
+        T2: BinaryDeserializer,
+        T3: BinaryDeserializer,
+        T4: BinaryDeserializer,
+        T5: BinaryDeserializer,
+        T6: BinaryDeserializer,
+        T7: BinaryDeserializer,
+        T8: BinaryDeserializer,
+        T9: BinaryDeserializer,
+        T10: BinaryDeserializer,
+        T11: BinaryDeserializer,
+    > BinaryDeserializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11)
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+        let stored_version = context.read_u8()?;
+
+        if stored_version == 0 {
+            let mut deserializer = AdtDeserializer::new_v0(&EMPTY_ADT_METADATA, context)?;
+            deserialize_tuple11(&mut deserializer)
+        } else {
+            let mut deserializer =
+                AdtDeserializer::new(&EMPTY_ADT_METADATA, context, stored_version)?;
+            deserialize_tuple11(&mut deserializer)
+        }
+    }
+}
+
+impl<
+        T1: BinaryDeserializer,
+        T2: BinaryDeserializer,
+        T3: BinaryDeserializer,
+        T4: BinaryDeserializer,
+        T5: BinaryDeserializer,
+        T6: BinaryDeserializer,
+        T7: BinaryDeserializer,
+        T8: BinaryDeserializer,
+        T9: BinaryDeserializer,
+        T10: BinaryDeserializer,
+        T11: BinaryDeserializer,
+        T12: BinaryDeserializer,
+    > BinaryDeserializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12)
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+        let stored_version = context.read_u8()?;
+
+        if stored_version == 0 {
+            let mut deserializer = AdtDeserializer::new_v0(&EMPTY_ADT_METADATA, context)?;
+            deserialize_tuple12(&mut deserializer)
+        } else {
+            let mut deserializer =
+                AdtDeserializer::new(&EMPTY_ADT_METADATA, context, stored_version)?;
+            deserialize_tuple12(&mut deserializer)
+        }
+    }
+}
+
+impl<
+        T1: BinaryDeserializer,
+        T2: BinaryDeserializer,
+        T3: BinaryDeserializer,
+        T4: BinaryDeserializer,
+        T5: BinaryDeserializer,
+        T6: BinaryDeserializer,
+        T7: BinaryDeserializer,
+        T8: BinaryDeserializer,
+        T9: BinaryDeserializer,
+        T10: BinaryDeserializer,
+        T11: BinaryDeserializer,
+        T12: BinaryDeserializer,
+        T13: BinaryDeserializer,
+    > BinaryDeserializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13)
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+        let stored_version = context.read_u8()?;
+
+        if stored_version == 0 {
+            let mut deserializer = AdtDeserializer::new_v0(&EMPTY_ADT_METADATA, context)?;
+            deserialize_tuple13(&mut deserializer)
+        } else {
+            let mut deserializer =
+                AdtDeserializer::new(&EMPTY_ADT_METADATA, context, stored_version)?;
+            deserialize_tuple13(&mut deserializer)
+        }
+    }
+}
+
+impl<
+        T1: BinaryDeserializer,
+        T2: BinaryDeserializer,
+        T3: BinaryDeserializer,
+        T4: BinaryDeserializer,
+        T5: BinaryDeserializer,
+        T6: BinaryDeserializer,
+        T7: BinaryDeserializer,
+        T8: BinaryDeserializer,
+        T9: BinaryDeserializer,
+        T10: BinaryDeserializer,
+        T11: BinaryDeserializer,
+        T12: BinaryDeserializer,
+        T13: BinaryDeserializer,
+        T14: BinaryDeserializer,
+    > BinaryDeserializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14)
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+        let stored_version = context.read_u8()?;
+
+        if stored_version == 0 {
+            let mut deserializer = AdtDeserializer::new_v0(&EMPTY_ADT_METADATA, context)?;
+            deserialize_tuple14(&mut deserializer)
+        } else {
+            let mut deserializer =
+                AdtDeserializer::new(&EMPTY_ADT_METADATA, context, stored_version)?;
+            deserialize_tuple14(&mut deserializer)
+        }
+    }
+}
+
+impl<
+        T1: BinaryDeserializer,
+        T2: BinaryDeserializer,
+        T3: BinaryDeserializer,
+        T4: BinaryDeserializer,
+        T5: BinaryDeserializer,
+        T6: BinaryDeserializer,
+        T7: BinaryDeserializer,
+        T8: BinaryDeserializer,
+        T9: BinaryDeserializer,
+        T10: BinaryDeserializer,
+        T11: BinaryDeserializer,
+        T12: BinaryDeserializer,
+        T13: BinaryDeserializer,
+        T14: BinaryDeserializer,
+        T15: BinaryDeserializer,
+    > BinaryDeserializer
+    for (
+        T1,
+        T2,
+        T3,
+        T4,
+        T5,
+        T6,
+        T7,
+        T8,
+        T9,
+        T10,
+        T11,
+        T12,
+        T13,
+        T14,
+        T15,
+    )
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+        let stored_version = context.read_u8()?;
+
+        if stored_version == 0 {
+            let mut deserializer = AdtDeserializer::new_v0(&EMPTY_ADT_METADATA, context)?;
+            deserialize_tuple15(&mut deserializer)
+        } else {
+            let mut deserializer =
+                AdtDeserializer::new(&EMPTY_ADT_METADATA, context, stored_version)?;
+            deserialize_tuple15(&mut deserializer)
+        }
+    }
+}
+
+impl<
+        T1: BinaryDeserializer,
+        T2: BinaryDeserializer,
+        T3: BinaryDeserializer,
+        T4: BinaryDeserializer,
+        T5: BinaryDeserializer,
+        T6: BinaryDeserializer,
+        T7: BinaryDeserializer,
+        T8: BinaryDeserializer,
+        T9: BinaryDeserializer,
+        T10: BinaryDeserializer,
+        T11: BinaryDeserializer,
+        T12: BinaryDeserializer,
+        T13: BinaryDeserializer,
+        T14: BinaryDeserializer,
+        T15: BinaryDeserializer,
+        T16: BinaryDeserializer,
+    > BinaryDeserializer
+    for (
+        T1,
+        T2,
+        T3,
+        T4,
+        T5,
+        T6,
+        T7,
+        T8,
+        T9,
+        T10,
+        T11,
+        T12,
+        T13,
+        T14,
+        T15,
+        T16,
+    )
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+        let stored_version = context.read_u8()?;
+
+        if stored_version == 0 {
+            let mut deserializer = AdtDeserializer::new_v0(&EMPTY_ADT_METADATA, context)?;
+            deserialize_tuple16(&mut deserializer)
+        } else {
+            let mut deserializer =
+                AdtDeserializer::new(&EMPTY_ADT_METADATA, context, stored_version)?;
+            deserialize_tuple16(&mut deserializer)
+        }
+    }
+}
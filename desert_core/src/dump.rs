@@ -0,0 +1,240 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::adt::FieldPosition;
+use crate::evolution::{FIELD_MADE_OPTIONAL, FIELD_REMOVED, UNKNOWN};
+use crate::{BinaryInput, Error, Result, SliceInput};
+
+/// Produces a human-readable, nested description of serialized data without knowing its type.
+///
+/// The input is walked as a sequence of values serialized with an evolution header: the version byte,
+/// the evolution steps and the chunks they describe. The contents of the chunks are interpreted with
+/// heuristics - a chunk that looks like a nested evolved value (optionally prefixed by a constructor
+/// index) is dumped recursively, anything else as hex bytes. Values with version 0 have no header, so
+/// everything after their version byte is dumped as bytes.
+pub fn debug_dump(input: &[u8]) -> Result<String> {
+    if input.is_empty() {
+        return Err(Error::InputEndedUnexpectedly { offset: 0 });
+    }
+    let mut output = String::new();
+    let mut offset = 0;
+    while offset < input.len() {
+        line(&mut output, 0, &format!("value at offset {offset}"));
+        offset += dump_value(&input[offset..], offset, 1, &mut output)?;
+    }
+    Ok(output)
+}
+
+enum HeaderStep {
+    Chunk { size: usize },
+    FieldMadeOptional { position: FieldPosition },
+    FieldRemoved { field_name: String },
+    Unknown,
+}
+
+struct Header {
+    version: u8,
+    steps: Vec<HeaderStep>,
+    length: usize,
+}
+
+impl Header {
+    fn total_length(&self) -> usize {
+        self.length
+            + self
+                .steps
+                .iter()
+                .map(|step| match step {
+                    HeaderStep::Chunk { size } => *size,
+                    _ => 0,
+                })
+                .sum::<usize>()
+    }
+}
+
+fn read_header(input: &mut SliceInput) -> Result<Header> {
+    let version = input.read_u8()?;
+    let mut steps = Vec::new();
+    if version > 0 {
+        for _ in 0..=version {
+            let code_or_size = input.read_var_i32()?;
+            let step = match code_or_size {
+                UNKNOWN => HeaderStep::Unknown,
+                FIELD_MADE_OPTIONAL => {
+                    let byte = input.read_i8()?;
+                    let position = if byte < 0 {
+                        FieldPosition::new(0, byte.unsigned_abs())
+                    } else {
+                        FieldPosition::new(byte as u8, 0)
+                    };
+                    HeaderStep::FieldMadeOptional { position }
+                }
+                FIELD_REMOVED => {
+                    // The name is a deduplicated string, a negative length refers to an earlier string
+                    let length = input.read_var_i32()?;
+                    let field_name = if length < 0 {
+                        format!("<string #{}>", -length)
+                    } else {
+                        String::from_utf8_lossy(input.read_bytes(length as usize)?).into_owned()
+                    };
+                    HeaderStep::FieldRemoved { field_name }
+                }
+                size if size > 0 => HeaderStep::Chunk {
+                    size: size as usize,
+                },
+                code => {
+                    return Err(Error::DeserializationFailure(format!(
+                        "Failed to dump evolution header: invalid step code {code}"
+                    )))
+                }
+            };
+            steps.push(step);
+        }
+    }
+    Ok(Header {
+        version,
+        steps,
+        length: input.pos,
+    })
+}
+
+fn dump_value(input: &[u8], offset: usize, indent: usize, output: &mut String) -> Result<usize> {
+    let mut slice_input = SliceInput::new(input);
+    let header = read_header(&mut slice_input)?;
+    if header.version == 0 {
+        line(output, indent, "version 0 without evolution header");
+        dump_bytes(&input[1..], offset + 1, indent + 1, output);
+        return Ok(input.len());
+    }
+
+    line(output, indent, &format!("version {}", header.version));
+    line(output, indent + 1, "evolution header");
+    for (idx, step) in header.steps.iter().enumerate() {
+        let description = match step {
+            HeaderStep::Chunk { size } => format!("chunk of {size} bytes"),
+            HeaderStep::FieldMadeOptional { position } => format!(
+                "field made optional at chunk {} position {}",
+                position.chunk, position.position
+            ),
+            HeaderStep::FieldRemoved { field_name } => format!("field {field_name} removed"),
+            HeaderStep::Unknown => String::from("unknown step"),
+        };
+        line(output, indent + 2, &format!("[{idx}] {description}"));
+    }
+
+    let mut pos = header.length;
+    for (idx, step) in header.steps.iter().enumerate() {
+        if let HeaderStep::Chunk { size } = step {
+            if pos + size > input.len() {
+                return Err(Error::InputEndedUnexpectedly {
+                    offset: offset + input.len(),
+                });
+            }
+            line(
+                output,
+                indent + 1,
+                &format!("chunk {idx}: {size} bytes at offset {}", offset + pos),
+            );
+            dump_chunk(&input[pos..pos + size], offset + pos, indent + 2, output)?;
+            pos += size;
+        }
+    }
+    Ok(pos)
+}
+
+fn dump_chunk(chunk: &[u8], offset: usize, indent: usize, output: &mut String) -> Result<()> {
+    if is_evolved_value(chunk) {
+        dump_value(chunk, offset, indent, output)?;
+    } else {
+        let mut slice_input = SliceInput::new(chunk);
+        match slice_input.read_var_u32() {
+            Ok(constructor_idx) if is_evolved_value(&chunk[slice_input.pos..]) => {
+                line(output, indent, &format!("constructor {constructor_idx}"));
+                dump_value(
+                    &chunk[slice_input.pos..],
+                    offset + slice_input.pos,
+                    indent + 1,
+                    output,
+                )?;
+            }
+            _ => dump_bytes(chunk, offset, indent, output),
+        }
+    }
+    Ok(())
+}
+
+/// True if the data looks like exactly one value with an evolution header
+fn is_evolved_value(data: &[u8]) -> bool {
+    match read_header(&mut SliceInput::new(data)) {
+        Ok(header) => header.version > 0 && header.total_length() == data.len(),
+        Err(_) => false,
+    }
+}
+
+fn dump_bytes(data: &[u8], offset: usize, indent: usize, output: &mut String) {
+    for (idx, row) in data.chunks(16).enumerate() {
+        let hex = row
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let ascii = row
+            .iter()
+            .map(|byte| {
+                if byte.is_ascii_graphic() || *byte == b' ' {
+                    *byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+        line(
+            output,
+            indent,
+            &format!("{:08x}: {hex:<47}  {ascii}", offset + idx * 16),
+        );
+    }
+}
+
+fn line(output: &mut String, indent: usize, text: &str) {
+    for _ in 0..indent {
+        output.push_str("  ");
+    }
+    output.push_str(text);
+    output.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::debug_dump;
+    use test_r::test;
+
+    #[test]
+    fn version_0_is_dumped_as_bytes() {
+        let dump = debug_dump(&[0, 1, 2, 65]).unwrap();
+        assert_eq!(
+            dump,
+            "value at offset 0\n  version 0 without evolution header\n    00000001: 01 02 41                                         ..A\n"
+        );
+    }
+
+    #[test]
+    fn evolution_header_and_chunks_are_dumped() {
+        // version 1, chunks of 2 and 1 bytes
+        let dump = debug_dump(&[1, 4, 2, 10, 11, 12]).unwrap();
+        assert!(dump.contains("version 1"));
+        assert!(dump.contains("[0] chunk of 2 bytes"));
+        assert!(dump.contains("[1] chunk of 1 bytes"));
+        assert!(dump.contains("chunk 0: 2 bytes at offset 3"));
+        assert!(dump.contains("chunk 1: 1 bytes at offset 5"));
+    }
+
+    #[test]
+    fn truncated_chunk_fails() {
+        assert!(matches!(
+            debug_dump(&[1, 4, 2, 10]),
+            Err(crate::Error::InputEndedUnexpectedly { offset: 4 })
+        ));
+    }
+}
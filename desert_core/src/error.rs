@@ -1,9 +1,11 @@
-use crate::{RefId, StringId};
-use std::array::TryFromSliceError;
-use std::char::DecodeUtf16Error;
-use std::fmt::{Display, Formatter};
-use std::num::TryFromIntError;
-use std::string::FromUtf8Error;
+use crate::{RefId, StringId, ValueId};
+use alloc::string::{FromUtf8Error, String, ToString};
+use core::array::TryFromSliceError;
+use core::char::DecodeUtf16Error;
+use core::fmt::{Display, Formatter};
+use core::num::TryFromIntError;
+use core::str::Utf8Error;
+use core::time::Duration;
 
 #[derive(Debug)]
 pub enum Error {
@@ -11,11 +13,35 @@ pub enum Error {
     FailedToDecodeCharacter(u16),
     LengthTooLarge,
     InvalidTimeZone(String),
-    InputEndedUnexpectedly,
+    /// The input ended before the value was fully read. `offset` is the position of the end of the input,
+    /// or of the chunk of an evolvable type being read, that was reached.
+    InputEndedUnexpectedly {
+        offset: usize,
+    },
+    /// The input has `remaining` bytes left after the deserialized value
+    TrailingData {
+        remaining: usize,
+    },
     CompressionFailure(String),
     DecompressionFailure(String),
+    ReadFailure(String),
+    WriteFailure(String),
+    /// Borrowing is not possible when the input is pulled from a reader or a [crate::BinaryInput]
+    BorrowingFromReader,
+    BorrowingFromBytes,
+    /// A borrowed value spans the boundary of two segments of a chained input
+    BorrowingAcrossSegments,
+    NonUtf8Path(String),
+    /// A `SystemTime` earlier than the Unix epoch, which cannot be serialized. Holds how much earlier it is.
+    SystemTimeBeforeUnixEpoch(Duration),
+    IncompatibleOsStringEncoding(u8),
+    CollectionTooLong {
+        length: usize,
+        limit: usize,
+    },
     FailedToDecodeString(String),
     InvalidStringId(StringId),
+    InvalidValueId(ValueId),
     DeserializationFailure(String),
     UnknownFieldReferenceInEvolutionStep(String),
     InvalidConstructorName {
@@ -27,6 +53,12 @@ pub enum Error {
     FieldWithoutDefaultValueIsMissing(String),
     NonOptionalFieldSerializedAsNone(String),
     InvalidRefId(RefId),
+    /// More distinct references in a single stream than a [RefId] can identify
+    TooManyReferences,
+    /// More distinct deduplicated strings in a single stream than a [StringId] can identify
+    TooManyStrings,
+    /// More distinct deduplicated values in a single stream than a [ValueId] can identify
+    TooManyValues,
     InvalidConstructorId {
         constructor_id: u32,
         type_name: String,
@@ -39,20 +71,79 @@ pub enum Error {
         constructor_name: String,
         type_name: String,
     },
+    /// The data was written by a newer version of an evolvable type than the one reading it, and
+    /// [crate::Options::reject_newer_versions] is enabled
+    NewerVersion {
+        stored: u8,
+        supported: u8,
+    },
+    /// A trait object's concrete type is not registered in the [crate::TypeRegistry] used to serialize it
+    UnregisteredType(String),
+    /// The type tag read from the input is not registered in the [crate::TypeRegistry] used to deserialize it
+    UnknownTypeTag(String),
+    /// A zero was read for a `NonZero` integer type
+    InvalidNonZero,
+    /// The value is nested deeper than [crate::Options::max_depth]
+    RecursionLimitExceeded {
+        limit: usize,
+    },
+    /// The checksum stored with a value does not match the data, see [crate::deserialize_with_checksum]
+    ChecksumMismatch {
+        expected: u32,
+        actual: u32,
+    },
 }
 
 impl Display for Error {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::UnsupportedCharacter(c) => write!(f, "Unsupported character: {}", c),
             Error::FailedToDecodeCharacter(c) => write!(f, "Failed to decode character: {}", c),
             Error::LengthTooLarge => write!(f, "Length too large"),
             Error::InvalidTimeZone(msg) => write!(f, "Invalid timezone: {}", msg),
-            Error::InputEndedUnexpectedly => write!(f, "Input ended unexpectedly"),
+            Error::InputEndedUnexpectedly { offset } => {
+                write!(f, "Input ended unexpectedly at offset {offset}")
+            }
+            Error::TrailingData { remaining } => {
+                write!(f, "{remaining} bytes left after the deserialized value")
+            }
             Error::CompressionFailure(msg) => write!(f, "Compression failure: {}", msg),
             Error::DecompressionFailure(msg) => write!(f, "Decompression failure: {}", msg),
+            Error::ReadFailure(msg) => write!(f, "Read failure: {}", msg),
+            Error::WriteFailure(msg) => write!(f, "Write failure: {}", msg),
+            Error::BorrowingFromReader => {
+                write!(
+                    f,
+                    "Cannot borrow from the input when deserializing from a reader"
+                )
+            }
+            Error::BorrowingFromBytes => {
+                write!(
+                    f,
+                    "Cannot borrow from the input when deserializing from a shared Bytes buffer"
+                )
+            }
+            Error::BorrowingAcrossSegments => {
+                write!(f, "Cannot borrow a value spanning multiple input segments")
+            }
+            Error::NonUtf8Path(path) => write!(f, "Path is not valid UTF-8: {}", path),
+            Error::SystemTimeBeforeUnixEpoch(duration) => write!(
+                f,
+                "System time is {:?} before the Unix epoch, only later times can be serialized",
+                duration
+            ),
+            Error::IncompatibleOsStringEncoding(tag) => write!(
+                f,
+                "OS string encoding with tag {} is not supported on this platform",
+                tag
+            ),
+            Error::CollectionTooLong { length, limit } => write!(
+                f,
+                "Collection length {length} exceeds the maximum of {limit}"
+            ),
             Error::FailedToDecodeString(msg) => write!(f, "Failed to decode string: {}", msg),
             Error::InvalidStringId(id) => write!(f, "Invalid string id: {}", id),
+            Error::InvalidValueId(id) => write!(f, "Invalid value id: {}", id),
             Error::DeserializationFailure(msg) => write!(f, "Deserialization failure: {}", msg),
             Error::UnknownFieldReferenceInEvolutionStep(msg) => {
                 write!(f, "Unknown field reference in evolution step: {msg}")
@@ -77,6 +168,11 @@ impl Display for Error {
                 write!(f, "Non optional field serialized as None: {field_name}")
             }
             Error::InvalidRefId(id) => write!(f, "Invalid reference id: {id}"),
+            Error::TooManyReferences => write!(f, "Too many references in a single stream"),
+            Error::TooManyStrings => {
+                write!(f, "Too many deduplicated strings in a single stream")
+            }
+            Error::TooManyValues => write!(f, "Too many deduplicated values in a single stream"),
             Error::InvalidConstructorId {
                 constructor_id,
                 type_name,
@@ -98,10 +194,27 @@ impl Display for Error {
                 f,
                 "Serializing transient constructor: {constructor_name} for type: {type_name}"
             ),
+            Error::NewerVersion { stored, supported } => write!(
+                f,
+                "Stored version {stored} is newer than the supported version {supported}"
+            ),
+            Error::UnregisteredType(type_name) => {
+                write!(f, "Type {type_name} is not registered in the type registry")
+            }
+            Error::UnknownTypeTag(tag) => write!(f, "Unknown type tag: {tag}"),
+            Error::InvalidNonZero => write!(f, "Zero value for a non-zero integer type"),
+            Error::RecursionLimitExceeded { limit } => {
+                write!(f, "Value is nested deeper than the limit of {limit} levels")
+            }
+            Error::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "Checksum mismatch: expected {expected:#010x}, got {actual:#010x}"
+            ),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
 impl From<TryFromIntError> for Error {
@@ -118,8 +231,8 @@ impl From<chrono_tz::ParseError> for Error {
 }
 
 impl From<TryFromSliceError> for Error {
-    fn from(_: TryFromSliceError) -> Self {
-        Error::InputEndedUnexpectedly
+    fn from(err: TryFromSliceError) -> Self {
+        Error::DeserializationFailure(err.to_string())
     }
 }
 
@@ -135,4 +248,10 @@ impl From<FromUtf8Error> for Error {
     }
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+impl From<Utf8Error> for Error {
+    fn from(err: Utf8Error) -> Self {
+        Error::FailedToDecodeString(err.to_string())
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
@@ -1,3 +1,5 @@
+use alloc::string::String;
+
 use crate::adt::FieldPosition;
 use crate::deserializer::DeserializationContext;
 use crate::serializer::SerializationContext;
@@ -44,9 +46,9 @@ pub(crate) enum SerializedEvolutionStep {
     Unknown,
 }
 
-const UNKNOWN: i32 = 0;
-const FIELD_MADE_OPTIONAL: i32 = -1;
-const FIELD_REMOVED: i32 = -2;
+pub(crate) const UNKNOWN: i32 = 0;
+pub(crate) const FIELD_MADE_OPTIONAL: i32 = -1;
+pub(crate) const FIELD_REMOVED: i32 = -2;
 
 impl BinarySerializer for SerializedEvolutionStep {
     fn serialize<Output: BinaryOutput>(
@@ -0,0 +1,176 @@
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::{deserialize_iterator, DeserializationContext};
+use crate::serializer::{serialize_iterator, SerializationContext};
+use crate::{BinaryDeserializer, BinaryInput, BinarySerializer, Error, Result};
+use alloc::vec::Vec;
+use arrayvec::{ArrayString, ArrayVec};
+use castaway::cast;
+
+// Using the same format as Vec and String, so they are interchangeable as long as the stored
+// length fits in the capacity
+
+impl<T: BinarySerializer, const CAP: usize> BinarySerializer for ArrayVec<T, CAP> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        let empty: Vec<T> = Vec::new();
+        if cast!(empty, Vec<u8>).is_ok() {
+            let bytes =
+                unsafe { core::slice::from_raw_parts(self.as_ptr() as *const u8, self.len()) };
+            context.write_var_u32(bytes.len().try_into()?);
+            context.write_bytes(bytes);
+            Ok(())
+        } else {
+            serialize_iterator(&mut self.iter(), context)
+        }
+    }
+}
+
+impl<T: BinaryDeserializer, const CAP: usize> BinaryDeserializer for ArrayVec<T, CAP> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let empty: Vec<T> = Vec::new();
+        if cast!(empty, Vec<u8>).is_ok() {
+            let items = Vec::<T>::deserialize(context)?;
+            check_capacity(items.len(), CAP)?;
+            Ok(items.into_iter().collect())
+        } else {
+            let (iterator, size) = deserialize_iterator::<T>(context);
+            if let Some(size) = size {
+                check_capacity(size, CAP)?;
+            }
+            let mut result = ArrayVec::new();
+            for item in iterator {
+                result
+                    .try_push(item?)
+                    .map_err(|_| Error::CollectionTooLong {
+                        length: CAP + 1,
+                        limit: CAP,
+                    })?;
+            }
+            Ok(result)
+        }
+    }
+}
+
+impl<const CAP: usize> BinarySerializer for ArrayString<CAP> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.as_str().serialize(context)
+    }
+}
+
+impl<const CAP: usize> BinaryDeserializer for ArrayString<CAP> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let length = context.read_var_i32()? as usize;
+        check_capacity(length, CAP)?;
+        let bytes = context.read_bytes(length)?;
+        let mut result = ArrayString::new();
+        result.push_str(core::str::from_utf8(bytes)?);
+        Ok(result)
+    }
+}
+
+fn check_capacity(length: usize, capacity: usize) -> Result<()> {
+    if length > capacity {
+        Err(Error::CollectionTooLong {
+            length,
+            limit: capacity,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use crate::{deserialize, serialize_to_byte_vec, Error};
+    use arrayvec::{ArrayString, ArrayVec};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn roundtrip_below_capacity(items in vec(any::<String>(), 0..8)) {
+            let value: ArrayVec<String, 8> = items.into_iter().collect();
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_bytes(items in vec(any::<u8>(), 0..=16)) {
+            let value: ArrayVec<u8, 16> = items.into_iter().collect();
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_string(s in "\\PC{0,8}") {
+            roundtrip(ArrayString::<32>::from(&s).unwrap());
+        }
+
+        #[test]
+        fn vec_can_be_read_as_arrayvec(items in vec(any::<i32>(), 0..=8)) {
+            let data = serialize_to_byte_vec(&items).unwrap();
+            let result = deserialize::<ArrayVec<i32, 8>>(&data).unwrap();
+            assert_eq!(result.as_slice(), items.as_slice());
+        }
+
+        #[test]
+        fn byte_vec_is_compatible_with_arrayvec(items in vec(any::<u8>(), 0..=16)) {
+            let value: ArrayVec<u8, 16> = items.iter().copied().collect();
+            assert_eq!(serialize_to_byte_vec(&value).unwrap(), serialize_to_byte_vec(&items).unwrap());
+        }
+
+        #[test]
+        fn string_is_compatible_with_arraystring(s in "\\PC{0,8}") {
+            let value = ArrayString::<32>::from(&s).unwrap();
+            let data = serialize_to_byte_vec(&value).unwrap();
+            assert_eq!(data, serialize_to_byte_vec(&s).unwrap());
+            assert_eq!(deserialize::<String>(&data).unwrap(), s);
+        }
+    }
+
+    #[test]
+    fn roundtrip_at_capacity() {
+        let value: ArrayVec<i64, 4> = [1, -2, 3, -4].into_iter().collect();
+        assert!(value.is_full());
+        roundtrip(value);
+        roundtrip(ArrayString::<5>::from("hello").unwrap());
+    }
+
+    #[test]
+    fn reading_more_items_than_capacity_fails() {
+        let data = serialize_to_byte_vec(&vec![1i32, 2, 3, 4, 5]).unwrap();
+        assert!(matches!(
+            deserialize::<ArrayVec<i32, 4>>(&data),
+            Err(Error::CollectionTooLong {
+                length: 5,
+                limit: 4
+            })
+        ));
+
+        let data = serialize_to_byte_vec(&vec![0u8; 17]).unwrap();
+        assert!(matches!(
+            deserialize::<ArrayVec<u8, 16>>(&data),
+            Err(Error::CollectionTooLong {
+                length: 17,
+                limit: 16
+            })
+        ));
+    }
+
+    #[test]
+    fn reading_longer_string_than_capacity_fails() {
+        let data = serialize_to_byte_vec(&"hello world".to_string()).unwrap();
+        assert!(matches!(
+            deserialize::<ArrayString<5>>(&data),
+            Err(Error::CollectionTooLong {
+                length: 11,
+                limit: 5
+            })
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::binary_input::BinaryInput;
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::DeserializationContext;
+use crate::serializer::SerializationContext;
+use crate::{BinaryDeserializer, BinarySerializer, Result};
+use bit_vec::BitVec;
+
+// The length of a BitVec is not always a multiple of 8, so the number of bits is written before the
+// packed bytes, and the padding bits of the last byte are dropped when reading it back
+
+impl BinarySerializer for BitVec {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_var_u32(self.len().try_into()?);
+        context.write_bytes(&self.to_bytes());
+        Ok(())
+    }
+}
+
+impl BinaryDeserializer for BitVec {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let length = context.read_var_u32()? as usize;
+        let bytes = context.read_bytes(length.div_ceil(8))?;
+        let mut result = BitVec::from_bytes(bytes);
+        result.truncate(length);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use crate::{deserialize, serialize_to_byte_vec};
+    use bit_vec::BitVec;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn roundtrip_bit_vec(bits in vec(any::<bool>(), 0..100)) {
+            let value: BitVec = bits.into_iter().collect();
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize::<BitVec>(&data).unwrap();
+            prop_assert_eq!(result.len(), value.len());
+            prop_assert_eq!(result, value);
+        }
+    }
+
+    #[test]
+    fn bit_length_is_preserved() {
+        for length in [0, 1, 7, 8, 9, 15, 16, 17] {
+            let value = BitVec::from_elem(length, true);
+            roundtrip(value.clone());
+            let data = serialize_to_byte_vec(&value).unwrap();
+            assert_eq!(data.len(), 1 + length.div_ceil(8));
+            assert_eq!(deserialize::<BitVec>(&data).unwrap().len(), length);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::binary_input::BinaryInput;
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::DeserializationContext;
+use crate::serializer::SerializationContext;
+use crate::{BinaryDeserializer, BinarySerializer, Result};
+use compact_str::CompactString;
+
+// Using the same format as String, so the two are interchangeable
+
+impl BinarySerializer for CompactString {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.as_str().serialize(context)
+    }
+}
+
+impl BinaryDeserializer for CompactString {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let length = context.read_var_i32()?;
+        let bytes = context.read_bytes(length as usize)?;
+        Ok(CompactString::from_utf8(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use crate::{deserialize, serialize_to_byte_vec};
+    use compact_str::CompactString;
+    use proptest::prelude::*;
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn roundtrip_compact_string(value: String) {
+            roundtrip(CompactString::from(value));
+        }
+    }
+
+    #[test]
+    fn roundtrip_inline_and_heap_strings() {
+        let inline = CompactString::from("short");
+        assert!(!inline.is_heap_allocated());
+        roundtrip(inline);
+
+        let heap = CompactString::from("a string that is too long to be stored inline");
+        assert!(heap.is_heap_allocated());
+        roundtrip(heap);
+    }
+
+    #[test]
+    fn compatible_with_string() {
+        for value in ["", "short", "a string that is too long to be stored inline"] {
+            let data = serialize_to_byte_vec(&value.to_string()).unwrap();
+            let result = deserialize::<CompactString>(&data).unwrap();
+            assert_eq!(result, value);
+            assert_eq!(serialize_to_byte_vec(&result).unwrap(), data);
+        }
+    }
+}
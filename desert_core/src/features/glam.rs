@@ -0,0 +1,139 @@
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::DeserializationContext;
+use crate::serializer::SerializationContext;
+use crate::{BinaryDeserializer, BinarySerializer, Result};
+use glam::{
+    DMat2, DMat3, DMat4, DQuat, DVec2, DVec3, DVec4, Mat2, Mat3, Mat3A, Mat4, Quat, Vec2, Vec3,
+    Vec3A, Vec4,
+};
+
+// Vectors and quaternions are serialized as their components in x, y, z, w order, matrices as their
+// elements in column-major order, without any length prefix.
+
+macro_rules! impl_components_codec {
+    ($ty:ty, $scalar:ty, $len:literal, $to_array:ident, $from_array:expr) => {
+        impl BinarySerializer for $ty {
+            fn serialize<Output: BinaryOutput>(
+                &self,
+                context: &mut SerializationContext<Output>,
+            ) -> Result<()> {
+                for component in self.$to_array() {
+                    component.serialize(context)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl BinaryDeserializer for $ty {
+            fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+                let mut components = [0.0; $len];
+                for component in &mut components {
+                    *component = <$scalar>::deserialize(context)?;
+                }
+                Ok($from_array(components))
+            }
+        }
+    };
+}
+
+impl_components_codec!(Vec2, f32, 2, to_array, Vec2::from_array);
+impl_components_codec!(Vec3, f32, 3, to_array, Vec3::from_array);
+impl_components_codec!(Vec3A, f32, 3, to_array, Vec3A::from_array);
+impl_components_codec!(Vec4, f32, 4, to_array, Vec4::from_array);
+impl_components_codec!(Quat, f32, 4, to_array, Quat::from_array);
+impl_components_codec!(Mat2, f32, 4, to_cols_array, |c| Mat2::from_cols_array(&c));
+impl_components_codec!(Mat3, f32, 9, to_cols_array, |c| Mat3::from_cols_array(&c));
+impl_components_codec!(Mat3A, f32, 9, to_cols_array, |c| Mat3A::from_cols_array(&c));
+impl_components_codec!(Mat4, f32, 16, to_cols_array, |c| Mat4::from_cols_array(&c));
+
+impl_components_codec!(DVec2, f64, 2, to_array, DVec2::from_array);
+impl_components_codec!(DVec3, f64, 3, to_array, DVec3::from_array);
+impl_components_codec!(DVec4, f64, 4, to_array, DVec4::from_array);
+impl_components_codec!(DQuat, f64, 4, to_array, DQuat::from_array);
+impl_components_codec!(DMat2, f64, 4, to_cols_array, |c| DMat2::from_cols_array(&c));
+impl_components_codec!(DMat3, f64, 9, to_cols_array, |c| DMat3::from_cols_array(&c));
+impl_components_codec!(DMat4, f64, 16, to_cols_array, |c| DMat4::from_cols_array(
+    &c
+));
+
+#[cfg(test)]
+mod tests {
+    use crate::{deserialize, serialize_to_byte_vec, serialized_size};
+    use glam::{DMat4, DVec3, Mat4, Quat, Vec3, Vec4};
+    use proptest::prelude::*;
+    use test_r::test;
+
+    fn floats<const N: usize>() -> impl Strategy<Value = [f32; N]> {
+        proptest::array::uniform(any::<u32>().prop_map(f32::from_bits))
+    }
+
+    fn bits<const N: usize>(values: [f32; N]) -> [u32; N] {
+        values.map(f32::to_bits)
+    }
+
+    // Comparing the bit patterns, as NaN values are not equal to themselves
+    proptest! {
+        #[test]
+        fn roundtrip_vec3(components in floats::<3>()) {
+            let value = Vec3::from_array(components);
+            let data = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(data.len(), 12);
+            let result = deserialize::<Vec3>(&data).unwrap();
+            prop_assert_eq!(bits(result.to_array()), bits(components));
+        }
+
+        #[test]
+        fn roundtrip_quat(components in floats::<4>()) {
+            let value = Quat::from_array(components);
+            let data = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(data.len(), 16);
+            let result = deserialize::<Quat>(&data).unwrap();
+            prop_assert_eq!(bits(result.to_array()), bits(components));
+        }
+
+        #[test]
+        fn roundtrip_mat4(components in floats::<16>()) {
+            let value = Mat4::from_cols_array(&components);
+            let data = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(data.len(), 64);
+            let result = deserialize::<Mat4>(&data).unwrap();
+            prop_assert_eq!(bits(result.to_cols_array()), bits(components));
+        }
+
+        #[test]
+        fn roundtrip_dvec3(x: f64, y: f64, z: f64) {
+            let value = DVec3::new(x, y, z);
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize::<DVec3>(&data).unwrap();
+            prop_assert_eq!(result.to_array().map(f64::to_bits), [x, y, z].map(f64::to_bits));
+        }
+    }
+
+    #[test]
+    fn special_components_are_preserved() {
+        let value = Vec3::new(f32::NAN, f32::INFINITY, f32::NEG_INFINITY);
+        let result = deserialize::<Vec3>(&serialize_to_byte_vec(&value).unwrap()).unwrap();
+        assert_eq!(bits(result.to_array()), bits(value.to_array()));
+    }
+
+    #[test]
+    fn components_are_written_in_order() {
+        let data = serialize_to_byte_vec(&Vec3::new(1.0, 2.0, 3.0)).unwrap();
+        assert_eq!(
+            data,
+            [1.0f32, 2.0, 3.0]
+                .iter()
+                .flat_map(|c| c.to_be_bytes())
+                .collect::<Vec<_>>()
+        );
+        let matrix = Mat4::from_cols(Vec4::X, Vec4::Y, Vec4::Z, Vec4::new(5.0, 6.0, 7.0, 1.0));
+        let data = serialize_to_byte_vec(&matrix).unwrap();
+        assert_eq!(data[48..52], 5.0f32.to_be_bytes());
+    }
+
+    #[test]
+    fn matrix_sizes() {
+        assert_eq!(serialized_size(&Mat4::IDENTITY).unwrap(), 64);
+        assert_eq!(serialized_size(&DMat4::IDENTITY).unwrap(), 128);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::binary_input::BinaryInput;
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::DeserializationContext;
+use crate::serializer::SerializationContext;
+use crate::{BinaryDeserializer, BinarySerializer, Result};
+use half::{bf16, f16};
+
+impl BinarySerializer for f16 {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_u16(self.to_bits());
+        Ok(())
+    }
+}
+
+impl BinaryDeserializer for f16 {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(f16::from_bits(context.read_u16()?))
+    }
+}
+
+impl BinarySerializer for bf16 {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_u16(self.to_bits());
+        Ok(())
+    }
+}
+
+impl BinaryDeserializer for bf16 {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(bf16::from_bits(context.read_u16()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{deserialize, serialize_to_byte_vec};
+    use half::{bf16, f16};
+    use proptest::proptest;
+    use test_r::test;
+
+    // Comparing the bit patterns, as NaN values are not equal to themselves
+    proptest! {
+        #[test]
+        fn test_f16(bits: u16) {
+            let value = f16::from_bits(bits);
+            let data = serialize_to_byte_vec(&value).unwrap();
+            assert_eq!(data.len(), 2);
+            let result = deserialize::<f16>(&data).unwrap();
+            assert_eq!(value.to_bits(), result.to_bits());
+        }
+
+        #[test]
+        fn test_bf16(bits: u16) {
+            let value = bf16::from_bits(bits);
+            let data = serialize_to_byte_vec(&value).unwrap();
+            assert_eq!(data.len(), 2);
+            let result = deserialize::<bf16>(&data).unwrap();
+            assert_eq!(value.to_bits(), result.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_special_values() {
+        for value in [f16::NAN, f16::INFINITY, f16::NEG_INFINITY, f16::NEG_ZERO] {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            assert_eq!(data, value.to_bits().to_be_bytes());
+            assert_eq!(
+                deserialize::<f16>(&data).unwrap().to_bits(),
+                value.to_bits()
+            );
+        }
+        for value in [
+            bf16::NAN,
+            bf16::INFINITY,
+            bf16::NEG_INFINITY,
+            bf16::NEG_ZERO,
+        ] {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            assert_eq!(data, value.to_bits().to_be_bytes());
+            assert_eq!(
+                deserialize::<bf16>(&data).unwrap().to_bits(),
+                value.to_bits()
+            );
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::{deserialize_iterator, DeserializationContext};
+use crate::serializer::{serialize_iterator, SerializationContext};
+use crate::{BinaryDeserializer, BinarySerializer, Result};
+use core::hash::{BuildHasher, Hash};
+use indexmap::{IndexMap, IndexSet};
+
+// Using the same format as HashMap and HashSet, writing the entries in insertion order
+
+impl<K: BinarySerializer, V: BinarySerializer, S> BinarySerializer for IndexMap<K, V, S> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        serialize_iterator(&mut self.iter(), context)
+    }
+}
+
+impl<K: BinaryDeserializer + Eq + Hash, V: BinaryDeserializer, S: BuildHasher + Default>
+    BinaryDeserializer for IndexMap<K, V, S>
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        deserialize_iterator(context).0.collect()
+    }
+}
+
+impl<T: BinarySerializer, S> BinarySerializer for IndexSet<T, S> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        serialize_iterator(&mut self.iter(), context)
+    }
+}
+
+impl<T: BinaryDeserializer + Eq + Hash, S: BuildHasher + Default> BinaryDeserializer
+    for IndexSet<T, S>
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        deserialize_iterator(context).0.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use crate::{deserialize, serialize_to_byte_vec};
+    use indexmap::{IndexMap, IndexSet};
+    use proptest::collection::{hash_map, hash_set, vec};
+    use proptest::prelude::*;
+    use std::collections::{HashMap, HashSet};
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn roundtrip_index_map_preserves_order(entries in vec((any::<String>(), any::<i32>()), 0..50)) {
+            let value: IndexMap<String, i32> = entries.into_iter().collect();
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize::<IndexMap<String, i32>>(&data).unwrap();
+            assert!(value.iter().eq(result.iter()));
+        }
+
+        #[test]
+        fn roundtrip_index_set_preserves_order(items in vec(any::<i64>(), 0..50)) {
+            let value: IndexSet<i64> = items.into_iter().collect();
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize::<IndexSet<i64>>(&data).unwrap();
+            assert!(value.iter().eq(result.iter()));
+        }
+
+        #[test]
+        fn roundtrip_index_map(value in hash_map(any::<i32>(), any::<String>(), 0..50)) {
+            roundtrip(value.into_iter().collect::<IndexMap<_, _>>());
+        }
+
+        #[test]
+        fn hash_map_can_be_read_as_index_map(value in hash_map(any::<String>(), any::<i32>(), 0..50)) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize::<IndexMap<String, i32>>(&data).unwrap();
+            assert!(value.iter().eq(result.iter()));
+        }
+
+        #[test]
+        fn index_map_can_be_read_as_hash_map(value in hash_map(any::<String>(), any::<i32>(), 0..50)) {
+            let index_map: IndexMap<String, i32> = value.clone().into_iter().collect();
+            let data = serialize_to_byte_vec(&index_map).unwrap();
+            let result = deserialize::<HashMap<String, i32>>(&data).unwrap();
+            assert_eq!(result, value);
+        }
+
+        #[test]
+        fn hash_set_can_be_read_as_index_set(value in hash_set(any::<i64>(), 0..50)) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize::<IndexSet<i64>>(&data).unwrap();
+            assert!(value.iter().eq(result.iter()));
+            assert_eq!(result.into_iter().collect::<HashSet<_>>(), value);
+        }
+    }
+
+    #[test]
+    fn index_map_serialization_is_reproducible() {
+        let value: IndexMap<String, i32> = (0..100).map(|i| (format!("key{i}"), i)).collect();
+        let first = serialize_to_byte_vec(&value).unwrap();
+        let second = serialize_to_byte_vec(&value.clone()).unwrap();
+        assert_eq!(first, second);
+    }
+}
@@ -1,8 +1,41 @@
+#[cfg(feature = "arrayvec")]
+mod arrayvec;
+
 #[cfg(feature = "bigdecimal")]
 mod bigdecimal;
 
+#[cfg(feature = "bit-vec")]
+mod bit_vec;
+
 #[cfg(feature = "chrono")]
 mod chrono;
 
+#[cfg(feature = "compact_str")]
+mod compact_str;
+
+#[cfg(feature = "glam")]
+mod glam;
+
+#[cfg(feature = "half")]
+mod half;
+
+#[cfg(feature = "indexmap")]
+mod indexmap;
+
+#[cfg(feature = "ordered-float")]
+mod ordered_float;
+
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal;
+
+#[cfg(feature = "semver")]
+mod semver;
+
+#[cfg(feature = "smallvec")]
+mod smallvec;
+
+#[cfg(feature = "time")]
+mod time;
+
 #[cfg(feature = "uuid")]
-mod uuid;
+pub(crate) mod uuid;
@@ -0,0 +1,104 @@
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::DeserializationContext;
+use crate::serializer::SerializationContext;
+use crate::{BinaryDeserializer, BinarySerializer, Error, Result};
+use alloc::string::ToString;
+use ordered_float::{FloatCore, NotNan, OrderedFloat};
+
+// Both wrappers are serialized exactly as the wrapped float, so they are interchangeable with it
+
+impl<T: BinarySerializer> BinarySerializer for OrderedFloat<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.0.serialize(context)
+    }
+}
+
+impl<T: BinaryDeserializer> BinaryDeserializer for OrderedFloat<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        Ok(OrderedFloat(T::deserialize(context)?))
+    }
+}
+
+impl<T: BinarySerializer + FloatCore> BinarySerializer for NotNan<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.as_ref().serialize(context)
+    }
+}
+
+impl<T: BinaryDeserializer + FloatCore> BinaryDeserializer for NotNan<T> {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        NotNan::new(T::deserialize(context)?).map_err(|_| {
+            Error::DeserializationFailure(
+                "Failed to deserialize NotNan: the value is NaN".to_string(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use crate::{deserialize, serialize_to_byte_vec, Error};
+    use ordered_float::{NotNan, OrderedFloat};
+    use proptest::prelude::*;
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn roundtrip_ordered_f64(bits: u64) {
+            // OrderedFloat considers all NaN values equal
+            roundtrip(OrderedFloat(f64::from_bits(bits)));
+        }
+
+        #[test]
+        fn roundtrip_ordered_f32(bits: u32) {
+            roundtrip(OrderedFloat(f32::from_bits(bits)));
+        }
+
+        #[test]
+        fn roundtrip_not_nan_f64(value in any::<f64>().prop_filter("not NaN", |v| !v.is_nan())) {
+            roundtrip(NotNan::new(value).unwrap());
+        }
+
+        #[test]
+        fn roundtrip_not_nan_f32(value in any::<f32>().prop_filter("not NaN", |v| !v.is_nan())) {
+            roundtrip(NotNan::new(value).unwrap());
+        }
+
+        #[test]
+        fn ordered_float_has_the_same_format_as_f64(bits: u64) {
+            let value = f64::from_bits(bits);
+            let data = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(&serialize_to_byte_vec(&OrderedFloat(value)).unwrap(), &data);
+            let result = deserialize::<OrderedFloat<f64>>(&data).unwrap();
+            prop_assert_eq!(result.0.to_bits(), bits);
+        }
+
+        #[test]
+        fn not_nan_can_be_read_as_f64(value in any::<f64>().prop_filter("not NaN", |v| !v.is_nan())) {
+            let data = serialize_to_byte_vec(&NotNan::new(value).unwrap()).unwrap();
+            prop_assert_eq!(deserialize::<f64>(&data).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn nan_is_rejected_as_not_nan() {
+        for bits in [
+            f64::NAN.to_bits(),
+            0x7ff0_0000_0000_0001,
+            0xfff8_0000_0000_0000,
+        ] {
+            let data = serialize_to_byte_vec(&f64::from_bits(bits)).unwrap();
+            let result = deserialize::<NotNan<f64>>(&data);
+            assert!(matches!(result, Err(Error::DeserializationFailure(_))));
+        }
+        let data = serialize_to_byte_vec(&f32::NAN).unwrap();
+        assert!(deserialize::<NotNan<f32>>(&data).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//! Codec for `rust_decimal::Decimal`.
+//!
+//! The value is stored as the crate's own 16 byte representation produced by `Decimal::serialize`, so no
+//! precision is lost. The first 4 bytes are the flags as a little-endian `u32`, with the scale (0-28) in bits
+//! 16-23 and the sign in bit 31. They are followed by the low, middle and high 32 bits of the 96 bit unsigned
+//! mantissa, each as a little-endian `u32`. The value is `(-1)^sign * mantissa / 10^scale`.
+
+use crate::binary_input::BinaryInput;
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::DeserializationContext;
+use crate::serializer::SerializationContext;
+use crate::{BinaryDeserializer, BinarySerializer, Result};
+use rust_decimal::Decimal;
+
+impl BinarySerializer for Decimal {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_bytes(&Decimal::serialize(self));
+        Ok(())
+    }
+}
+
+impl BinaryDeserializer for Decimal {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let bytes = context.read_bytes(16)?;
+        let bytes: [u8; 16] = bytes.try_into()?;
+        Ok(Decimal::deserialize(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use crate::{deserialize, serialize_to_byte_vec};
+    use proptest::prelude::*;
+    use rust_decimal::Decimal;
+    use test_r::test;
+
+    const MAX_MANTISSA: i128 = (1 << 96) - 1;
+
+    fn decimal_strategy() -> impl Strategy<Value = Decimal> {
+        (-MAX_MANTISSA..=MAX_MANTISSA, 0..=Decimal::MAX_SCALE)
+            .prop_map(|(mantissa, scale)| Decimal::from_i128_with_scale(mantissa, scale))
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip_decimal(value in decimal_strategy()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_small_decimal(mantissa: i64, scale in 0..=Decimal::MAX_SCALE) {
+            let value = Decimal::new(mantissa, scale);
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize::<Decimal>(&data).unwrap();
+            assert_eq!(value, result);
+            assert_eq!(value.scale(), result.scale());
+        }
+    }
+
+    #[test]
+    fn roundtrip_limits() {
+        roundtrip(Decimal::ZERO);
+        roundtrip(Decimal::NEGATIVE_ONE);
+        roundtrip(Decimal::MIN);
+        roundtrip(Decimal::MAX);
+        roundtrip(Decimal::new(1, Decimal::MAX_SCALE));
+        roundtrip(Decimal::new(-1, Decimal::MAX_SCALE));
+    }
+
+    #[test]
+    fn serialized_format_is_the_decimal_representation() {
+        let value = Decimal::new(-12345, 2);
+        let data = serialize_to_byte_vec(&value).unwrap();
+        assert_eq!(
+            data,
+            [0, 0, 2, 0x80, 0x39, 0x30, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::binary_input::BinaryInput;
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::DeserializationContext;
+use crate::serializer::SerializationContext;
+use crate::{BinaryDeserializer, BinarySerializer, Error, Result};
+use alloc::format;
+use alloc::string::{String, ToString};
+use semver::{BuildMetadata, Prerelease, Version, VersionReq};
+
+// Version is serialized as its major, minor and patch numbers as u64 values, followed by the
+// pre-release and build metadata strings, which are empty if they are not present.
+// VersionReq is serialized as its string representation.
+
+impl BinarySerializer for Version {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_u64(self.major);
+        context.write_u64(self.minor);
+        context.write_u64(self.patch);
+        self.pre.as_str().serialize(context)?;
+        self.build.as_str().serialize(context)
+    }
+}
+
+impl BinaryDeserializer for Version {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let major = context.read_u64()?;
+        let minor = context.read_u64()?;
+        let patch = context.read_u64()?;
+        let pre = String::deserialize(context)?;
+        let pre = Prerelease::new(&pre).map_err(|err| {
+            Error::DeserializationFailure(format!("Failed to deserialize Prerelease: {err}"))
+        })?;
+        let build = String::deserialize(context)?;
+        let build = BuildMetadata::new(&build).map_err(|err| {
+            Error::DeserializationFailure(format!("Failed to deserialize BuildMetadata: {err}"))
+        })?;
+        Ok(Version {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+}
+
+impl BinarySerializer for VersionReq {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.to_string().serialize(context)
+    }
+}
+
+impl BinaryDeserializer for VersionReq {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let value = String::deserialize(context)?;
+        VersionReq::parse(&value).map_err(|err| {
+            Error::DeserializationFailure(format!("Failed to deserialize VersionReq: {err}"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use crate::{deserialize, serialize_to_byte_vec, Error};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use semver::{BuildMetadata, Prerelease, Version, VersionReq};
+    use test_r::test;
+
+    fn identifiers(pattern: &'static str) -> impl Strategy<Value = String> {
+        vec(pattern, 0..4).prop_map(|ids| ids.join("."))
+    }
+
+    fn version() -> impl Strategy<Value = Version> {
+        (
+            any::<u64>(),
+            any::<u64>(),
+            any::<u64>(),
+            identifiers("[a-z][0-9A-Za-z-]{0,7}|[1-9][0-9]{0,5}"),
+            identifiers("[0-9A-Za-z-]{1,8}"),
+        )
+            .prop_map(|(major, minor, patch, pre, build)| Version {
+                major,
+                minor,
+                patch,
+                pre: Prerelease::new(&pre).unwrap(),
+                build: BuildMetadata::new(&build).unwrap(),
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip_version(value in version()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_version_req(value in version()) {
+            roundtrip(VersionReq::parse(&format!(">={value}, <{}", value.major.saturating_add(1))).unwrap());
+            roundtrip(VersionReq::parse(&format!("^{}.{}", value.major, value.minor)).unwrap());
+        }
+    }
+
+    #[test]
+    fn version_without_metadata() {
+        let value = Version::new(1, 2, 3);
+        let data = serialize_to_byte_vec(&value).unwrap();
+        // three u64 values and two empty strings
+        assert_eq!(data.len(), 3 * 8 + 2);
+        assert_eq!(deserialize::<Version>(&data).unwrap(), value);
+    }
+
+    #[test]
+    fn roundtrip_version_with_metadata() {
+        roundtrip(Version::parse("1.0.0-alpha.1+build.5.sha-1234").unwrap());
+        roundtrip(Version::parse("0.0.1+0001").unwrap());
+        roundtrip(VersionReq::STAR);
+    }
+
+    #[test]
+    fn invalid_prerelease_is_rejected() {
+        let data =
+            serialize_to_byte_vec(&(1u64, 2u64, 3u64, "01".to_string(), String::new())).unwrap();
+        let result = deserialize::<Version>(&data[1..]);
+        assert!(matches!(result, Err(Error::DeserializationFailure(_))));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::{deserialize_iterator, DeserializationContext};
+use crate::serializer::{serialize_iterator, SerializationContext};
+use crate::{BinaryDeserializer, BinarySerializer, Result};
+use alloc::vec::Vec;
+use castaway::cast;
+use smallvec::{Array, SmallVec};
+
+// Using the same format as Vec, so the two are interchangeable, including the special case for bytes
+
+impl<A: Array> BinarySerializer for SmallVec<A>
+where
+    A::Item: BinarySerializer,
+{
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        let empty: Vec<A::Item> = Vec::new();
+        if cast!(empty, Vec<u8>).is_ok() {
+            let bytes =
+                unsafe { core::slice::from_raw_parts(self.as_ptr() as *const u8, self.len()) };
+            context.write_var_u32(bytes.len().try_into()?);
+            context.write_bytes(bytes);
+            Ok(())
+        } else {
+            serialize_iterator(&mut self.iter(), context)
+        }
+    }
+}
+
+impl<A: Array> BinaryDeserializer for SmallVec<A>
+where
+    A::Item: BinaryDeserializer,
+{
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let empty: Vec<A::Item> = Vec::new();
+        if cast!(empty, Vec<u8>).is_ok() {
+            Ok(SmallVec::from_vec(Vec::<A::Item>::deserialize(context)?))
+        } else {
+            deserialize_iterator(context).0.collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use crate::{deserialize, serialize_to_byte_vec};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use smallvec::SmallVec;
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn roundtrip_inline(items in vec(any::<String>(), 0..=4)) {
+            let value: SmallVec<[String; 4]> = SmallVec::from_vec(items);
+            assert!(!value.spilled());
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_spilled(items in vec(any::<i64>(), 5..100)) {
+            let value: SmallVec<[i64; 4]> = SmallVec::from_vec(items);
+            assert!(value.spilled());
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_bytes(items in vec(any::<u8>(), 0..100)) {
+            let value: SmallVec<[u8; 16]> = SmallVec::from_vec(items);
+            roundtrip(value);
+        }
+
+        #[test]
+        fn vec_can_be_read_as_smallvec(items in vec(any::<i32>(), 0..20)) {
+            let data = serialize_to_byte_vec(&items).unwrap();
+            let result = deserialize::<SmallVec<[i32; 8]>>(&data).unwrap();
+            assert_eq!(result.as_slice(), items.as_slice());
+        }
+
+        #[test]
+        fn smallvec_can_be_read_as_vec(items in vec(any::<i32>(), 0..20)) {
+            let value: SmallVec<[i32; 8]> = SmallVec::from_vec(items.clone());
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize::<Vec<i32>>(&data).unwrap();
+            assert_eq!(result, items);
+        }
+
+        #[test]
+        fn byte_vec_is_compatible_with_smallvec(items in vec(any::<u8>(), 0..40)) {
+            let value: SmallVec<[u8; 16]> = SmallVec::from_vec(items.clone());
+            assert_eq!(serialize_to_byte_vec(&value).unwrap(), serialize_to_byte_vec(&items).unwrap());
+            let data = serialize_to_byte_vec(&items).unwrap();
+            let result = deserialize::<SmallVec<[u8; 16]>>(&data).unwrap();
+            assert_eq!(result.as_slice(), items.as_slice());
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Codecs for the `time` crate.
+//!
+//! The binary representations are part of the format and are kept stable:
+//!
+//! - `Date`: year as a variable-length `u32`, month and day as `u8`, the same as chrono's `NaiveDate`
+//! - `Time`: hour, minute and second as `u8`, nanoseconds as a variable-length `u32`, the same as chrono's `NaiveTime`
+//! - `UtcOffset`: a `0` tag byte followed by the offset in seconds as a variable-length `i32`, the same as chrono's `FixedOffset`
+//! - `OffsetDateTime`: unix timestamp in seconds as `i64`, nanoseconds as `u32`, followed by the `UtcOffset`
+//! - `Duration`: whole seconds as `i64` and the remaining nanoseconds as `i32`, both having the same sign
+
+use crate::binary_input::BinaryInput;
+use crate::binary_output::BinaryOutput;
+use crate::deserializer::DeserializationContext;
+use crate::serializer::SerializationContext;
+use crate::{BinaryDeserializer, BinarySerializer, Error, Result};
+use alloc::format;
+use time::{Date, Duration, Month, OffsetDateTime, Time, UtcOffset};
+
+impl BinarySerializer for Date {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_var_u32(self.year() as u32);
+        context.write_u8(self.month() as u8);
+        context.write_u8(self.day());
+        Ok(())
+    }
+}
+
+impl BinaryDeserializer for Date {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let year = context.read_var_u32()?;
+        let month = context.read_u8()?;
+        let day = context.read_u8()?;
+        Month::try_from(month)
+            .and_then(|month| Date::from_calendar_date(year as i32, month, day))
+            .map_err(|err| {
+                Error::DeserializationFailure(format!(
+                    "Failed to deserialize Date: Invalid date {} {} {}: {}",
+                    year, month, day, err
+                ))
+            })
+    }
+}
+
+impl BinarySerializer for Time {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_u8(self.hour());
+        context.write_u8(self.minute());
+        context.write_u8(self.second());
+        context.write_var_u32(self.nanosecond());
+        Ok(())
+    }
+}
+
+impl BinaryDeserializer for Time {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let hour = context.read_u8()?;
+        let minute = context.read_u8()?;
+        let second = context.read_u8()?;
+        let nanosecond = context.read_var_u32()?;
+        Time::from_hms_nano(hour, minute, second, nanosecond).map_err(|err| {
+            Error::DeserializationFailure(format!(
+                "Failed to deserialize Time: Invalid time {} {} {} {}: {}",
+                hour, minute, second, nanosecond, err
+            ))
+        })
+    }
+}
+
+impl BinarySerializer for UtcOffset {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_u8(0);
+        context.write_var_i32(self.whole_seconds());
+        Ok(())
+    }
+}
+
+impl BinaryDeserializer for UtcOffset {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let typ = context.read_u8()?;
+        if typ != 0 {
+            Err(Error::DeserializationFailure(format!(
+                "Failed to deserialize UtcOffset: Invalid type {}",
+                typ
+            )))?
+        } else {
+            let offset = context.read_var_i32()?;
+            UtcOffset::from_whole_seconds(offset).map_err(|err| {
+                Error::DeserializationFailure(format!(
+                    "Failed to deserialize UtcOffset: Invalid offset {}: {}",
+                    offset, err
+                ))
+            })
+        }
+    }
+}
+
+impl BinarySerializer for OffsetDateTime {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_i64(self.unix_timestamp());
+        context.write_u32(self.nanosecond());
+        self.offset().serialize(context)
+    }
+}
+
+impl BinaryDeserializer for OffsetDateTime {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let seconds = context.read_i64()?;
+        let nanos = context.read_u32()?;
+        let offset = UtcOffset::deserialize(context)?;
+        // Reconstructing from the local time, as near the limits of the supported range the UTC time of a valid
+        // value may itself be out of range
+        seconds
+            .checked_add(offset.whole_seconds() as i64)
+            .ok_or_else(|| {
+                Error::DeserializationFailure(format!(
+                    "Failed to deserialize OffsetDateTime: Invalid timestamp {} {}",
+                    seconds, nanos
+                ))
+            })
+            .and_then(|local_seconds| {
+                OffsetDateTime::from_unix_timestamp(local_seconds)
+                    .and_then(|local| local.replace_nanosecond(nanos))
+                    .map(|local| local.replace_offset(offset))
+                    .map_err(|err| {
+                        Error::DeserializationFailure(format!(
+                            "Failed to deserialize OffsetDateTime: Invalid timestamp {} {}: {}",
+                            seconds, nanos, err
+                        ))
+                    })
+            })
+    }
+}
+
+impl BinarySerializer for Duration {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_i64(self.whole_seconds());
+        context.write_i32(self.subsec_nanoseconds());
+        Ok(())
+    }
+}
+
+impl BinaryDeserializer for Duration {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let seconds = context.read_i64()?;
+        let nanos = context.read_i32()?;
+        if nanos.unsigned_abs() >= 1_000_000_000 {
+            Err(Error::DeserializationFailure(format!(
+                "Failed to deserialize Duration: Invalid nanoseconds {}",
+                nanos
+            )))
+        } else {
+            Ok(Duration::new(seconds, nanos))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use proptest::prelude::*;
+    use test_r::test;
+    use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+    fn date_strategy() -> impl Strategy<Value = Date> {
+        (Date::MIN.to_julian_day()..=Date::MAX.to_julian_day())
+            .prop_map(|day| Date::from_julian_day(day).unwrap())
+    }
+
+    fn time_strategy() -> impl Strategy<Value = Time> {
+        (0..24u8, 0..60u8, 0..60u8, 0..1_000_000_000u32).prop_map(
+            |(hour, minute, second, nanosecond)| {
+                Time::from_hms_nano(hour, minute, second, nanosecond).unwrap()
+            },
+        )
+    }
+
+    fn utc_offset_strategy() -> impl Strategy<Value = UtcOffset> {
+        (-93_599..=93_599).prop_map(|seconds| UtcOffset::from_whole_seconds(seconds).unwrap())
+    }
+
+    fn offset_date_time_strategy() -> impl Strategy<Value = OffsetDateTime> {
+        (date_strategy(), time_strategy(), utc_offset_strategy()).prop_map(
+            |(date, time, offset)| PrimitiveDateTime::new(date, time).assume_offset(offset),
+        )
+    }
+
+    fn duration_strategy() -> impl Strategy<Value = Duration> {
+        (any::<i64>(), 0..1_000_000_000i32).prop_map(|(seconds, nanos)| {
+            Duration::new(seconds, if seconds < 0 { -nanos } else { nanos })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip_date(value in date_strategy()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_time(value in time_strategy()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_utc_offset(value in utc_offset_strategy()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_offset_date_time(value in offset_date_time_strategy()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_duration(value in duration_strategy()) {
+            roundtrip(value);
+        }
+    }
+
+    #[test]
+    fn roundtrip_limits() {
+        roundtrip(Date::MIN);
+        roundtrip(Date::MAX);
+        roundtrip(Time::MIDNIGHT);
+        roundtrip(Time::MAX);
+        roundtrip(UtcOffset::UTC);
+        roundtrip(Duration::MIN);
+        roundtrip(Duration::MAX);
+        roundtrip(OffsetDateTime::UNIX_EPOCH);
+        for offset in [-93_599, 0, 93_599] {
+            let offset = UtcOffset::from_whole_seconds(offset).unwrap();
+            roundtrip(PrimitiveDateTime::MIN.assume_offset(offset));
+            roundtrip(PrimitiveDateTime::MAX.assume_offset(offset));
+        }
+    }
+}
@@ -2,9 +2,20 @@ use crate::binary_input::BinaryInput;
 use crate::binary_output::BinaryOutput;
 use crate::deserializer::DeserializationContext;
 use crate::serializer::SerializationContext;
-use crate::{BinaryDeserializer, BinarySerializer, Result};
+use crate::{BinaryDeserializer, BinarySerializer, Error, Result};
+use alloc::format;
+use alloc::string::String;
 use uuid::Uuid;
 
+/// Wrapper for [Uuid] values serializing them as their canonical hyphenated lowercase string
+/// representation instead of the default 16 raw bytes.
+///
+/// Useful for interoperating with systems storing UUIDs as strings. The two representations are not
+/// wire-compatible with each other, so a field cannot be switched between `Uuid` and `UuidAsString`
+/// without breaking existing data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UuidAsString(pub Uuid);
+
 impl BinarySerializer for Uuid {
     fn serialize<Output: BinaryOutput>(
         &self,
@@ -23,9 +34,32 @@ impl BinaryDeserializer for Uuid {
     }
 }
 
+impl BinarySerializer for UuidAsString {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        let mut buffer = Uuid::encode_buffer();
+        let hyphenated: &str = self.0.hyphenated().encode_lower(&mut buffer);
+        hyphenated.serialize(context)
+    }
+}
+
+impl BinaryDeserializer for UuidAsString {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let string = String::deserialize(context)?;
+        let uuid = Uuid::parse_str(&string).map_err(|err| {
+            Error::DeserializationFailure(format!("Failed to deserialize UuidAsString: {err}"))
+        })?;
+        Ok(UuidAsString(uuid))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::UuidAsString;
     use crate::tests::roundtrip;
+    use crate::{deserialize, serialize_to_byte_vec};
     use proptest::proptest;
     use proptest_arbitrary_interop::arb;
     use test_r::test;
@@ -36,5 +70,25 @@ mod tests {
         fn test_uuid(value in arb::<Uuid>()) {
             roundtrip(value);
         }
+
+        #[test]
+        fn test_uuid_as_string(value in arb::<Uuid>()) {
+            roundtrip(UuidAsString(value));
+        }
+    }
+
+    #[test]
+    fn uuid_as_string_is_the_hyphenated_form() {
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let data = serialize_to_byte_vec(&UuidAsString(uuid)).unwrap();
+        let string: String = deserialize(&data).unwrap();
+        assert_eq!(string, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+        assert_eq!(serialize_to_byte_vec(&uuid).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn invalid_uuid_string_fails() {
+        let data = serialize_to_byte_vec(&"not a uuid".to_string()).unwrap();
+        assert!(deserialize::<UuidAsString>(&data).is_err());
     }
 }
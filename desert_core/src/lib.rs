@@ -1,22 +1,50 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod adt;
 mod binary_input;
 mod binary_output;
+#[cfg(feature = "checksum")]
+mod checksum;
 mod deserializer;
+mod dump;
 mod error;
 mod evolution;
 mod features;
+mod options;
 pub mod serializer;
 mod state;
+mod type_registry;
 
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
 use bytes::{Bytes, BytesMut};
-use std::fmt::{Display, Formatter};
-
-pub use binary_input::{BinaryInput, OwnedInput, SliceInput};
-pub use binary_output::{BinaryOutput, SizeCalculator};
-pub use deserializer::{BinaryDeserializer, DeserializationContext};
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+
+pub use binary_input::{BinaryInput, BufInput, ChainedInput, OwnedInput, SliceInput};
+#[cfg(feature = "std")]
+pub use binary_output::WriterOutput;
+pub use binary_output::{BinaryOutput, BufMutOutput, SizeCalculator};
+#[cfg(feature = "checksum")]
+pub use checksum::{deserialize_with_checksum, serialize_with_checksum, ChecksumAlgorithm};
+pub use deserializer::{
+    deserialize_iterator, BinaryDeserializeBorrowed, BinaryDeserializer, DeserializationContext,
+};
+pub use dump::debug_dump;
 pub use error::{Error, Result};
 pub use evolution::Evolution;
-pub use serializer::{serialize_iterator, BinarySerializer, SerializationContext};
+#[cfg(feature = "uuid")]
+pub use features::uuid::UuidAsString;
+pub use options::Options;
+pub use serializer::{
+    serialize_iterator, serialize_unsized_iterator, BinarySerializer, SerializationContext,
+};
+use state::State;
+pub use type_registry::{DynBinarySerializer, TypeRegistry};
 
 #[cfg(test)]
 test_r::enable!();
@@ -27,6 +55,13 @@ impl<T: BinarySerializer + BinaryDeserializer> BinaryCodec for T {}
 
 const DEFAULT_CAPACITY: usize = 128;
 
+/// Tag of `OsStr` values serialized as raw bytes on Unix
+#[cfg(feature = "std")]
+const OS_STRING_BYTES_TAG: u8 = 0;
+/// Tag of `OsStr` values serialized as UTF-16 code units on Windows
+#[cfg(feature = "std")]
+const OS_STRING_WIDE_TAG: u8 = 1;
+
 pub fn serialize<T: BinarySerializer, O: BinaryOutput>(value: &T, output: O) -> Result<O> {
     let mut context = SerializationContext::new(output);
     value.serialize(&mut context)?;
@@ -38,6 +73,138 @@ pub fn deserialize<T: BinaryDeserializer>(input: &[u8]) -> Result<T> {
     T::deserialize(&mut context)
 }
 
+pub fn serialize_with_options<T: BinarySerializer, O: BinaryOutput>(
+    value: &T,
+    output: O,
+    options: Options,
+) -> Result<O> {
+    let mut context = SerializationContext::new(output).with_options(options);
+    value.serialize(&mut context)?;
+    Ok(context.into_output())
+}
+
+pub fn deserialize_with_options<T: BinaryDeserializer>(
+    input: &[u8],
+    options: Options,
+) -> Result<T> {
+    let mut context = DeserializationContext::new(input).with_options(options);
+    T::deserialize(&mut context)
+}
+
+/// Deserializes a value from the beginning of `input`, also returning the number of bytes it was read from.
+///
+/// The rest of `input` is left untouched, so this can be used to read values concatenated in a single
+/// buffer without any framing, continuing from the returned offset.
+pub fn deserialize_with_consumed<T: BinaryDeserializer>(input: &[u8]) -> Result<(T, usize)> {
+    let mut context = DeserializationContext::new(input);
+    let value = T::deserialize(&mut context)?;
+    Ok((value, context.pos()))
+}
+
+/// Deserializes a value that has to occupy the whole `input`, failing with [Error::TrailingData] if
+/// any bytes are left after it.
+pub fn deserialize_exact<T: BinaryDeserializer>(input: &[u8]) -> Result<T> {
+    let mut context = DeserializationContext::new(input);
+    let value = T::deserialize(&mut context)?;
+    match context.remaining() {
+        0 => Ok(value),
+        remaining => Err(Error::TrailingData { remaining }),
+    }
+}
+
+/// Deserializes all the values concatenated in `input`, until it is exhausted.
+///
+/// Every value is read with a fresh deduplication and reference tracking state, see
+/// [deserialize_stream_with_options] for sharing it between them.
+pub fn deserialize_stream<T: BinaryDeserializer>(
+    input: &[u8],
+) -> impl Iterator<Item = Result<T>> + '_ {
+    deserialize_stream_with_options(input, Options::default())
+}
+
+/// Deserializes all the values concatenated in `input`, until it is exhausted.
+///
+/// The values are read using a single [DeserializationContext]. The iteration stops after the first
+/// error, as the position of the next value cannot be determined. Values occupying zero bytes, such as
+/// `()`, cannot be read as a stream and fail instead of being yielded forever.
+pub fn deserialize_stream_with_options<T: BinaryDeserializer>(
+    input: &[u8],
+    options: Options,
+) -> impl Iterator<Item = Result<T>> + '_ {
+    let shared_state = options.shared_stream_state;
+    let mut context = DeserializationContext::new(input).with_options(options);
+    let mut failed = false;
+    core::iter::from_fn(move || {
+        if failed || context.pos() == input.len() {
+            None
+        } else {
+            if !shared_state {
+                *context.state_mut() = State::default();
+            }
+            let start = context.pos();
+            let result = T::deserialize(&mut context).and_then(|value| {
+                if context.pos() == start {
+                    Err(Error::DeserializationFailure(format!(
+                        "Failed to deserialize stream: value at offset {start} did not consume any input"
+                    )))
+                } else {
+                    Ok(value)
+                }
+            });
+            failed = result.is_err();
+            Some(result)
+        }
+    })
+}
+
+/// Deserializes a value borrowing from `input`, such as a `&str` or `&[u8]`, without copying it.
+pub fn deserialize_borrowed<'a, T: BinaryDeserializeBorrowed<'a>>(input: &'a [u8]) -> Result<T> {
+    let mut context = DeserializationContext::new(input);
+    T::deserialize_borrowed(&mut context)
+}
+
+/// Deserializes a value from a shared [Bytes] buffer, so the deserialized [Bytes] values are slices of
+/// `input` instead of copies.
+pub fn deserialize_from_bytes<T: BinaryDeserializer>(input: Bytes) -> Result<T> {
+    let mut context = DeserializationContext::from_bytes(input);
+    T::deserialize(&mut context)
+}
+
+/// Deserializes a value from an input split into multiple `segments`, without concatenating them first.
+pub fn deserialize_from_chain<T: BinaryDeserializer>(segments: &[&[u8]]) -> Result<T> {
+    let mut context = DeserializationContext::from_chain(segments);
+    T::deserialize(&mut context)
+}
+
+/// Deserializes a value from a [BinaryInput] such as [OwnedInput], taking ownership of it.
+///
+/// The input is read only as far as the value needs it, see [DeserializationContext::from_input].
+pub fn deserialize_owned<T: BinaryDeserializer, I: BinaryInput>(input: I) -> Result<T> {
+    let mut context = DeserializationContext::from_input(input);
+    T::deserialize(&mut context)
+}
+
+/// Deserializes a value from a `Read` source, pulling bytes from it only when they are needed.
+///
+/// Note that this does not reduce the peak memory usage compared to [deserialize]: everything read from
+/// `reader` is buffered until the value is fully deserialized. What it avoids is having to load the whole
+/// input up front, and it leaves any data following the serialized value unread in `reader`.
+#[cfg(feature = "std")]
+pub fn deserialize_from_reader<T: BinaryDeserializer, R: Read>(reader: R) -> Result<T> {
+    let mut context = DeserializationContext::from_reader(reader);
+    T::deserialize(&mut context)
+}
+
+/// Like [deserialize_from_reader], but reading with the given [Options].
+#[cfg(feature = "std")]
+pub fn deserialize_from_reader_with_options<T: BinaryDeserializer, R: Read>(
+    reader: R,
+    options: Options,
+) -> Result<T> {
+    let mut context = DeserializationContext::from_reader(reader).with_options(options);
+    T::deserialize(&mut context)
+}
+
 pub fn serialize_to_bytes<T: BinarySerializer>(value: &T) -> Result<Bytes> {
     Ok(serialize(value, BytesMut::with_capacity(DEFAULT_CAPACITY))?.freeze())
 }
@@ -46,6 +213,32 @@ pub fn serialize_to_byte_vec<T: BinarySerializer>(value: &T) -> Result<Vec<u8>>
     serialize(value, Vec::with_capacity(DEFAULT_CAPACITY))
 }
 
+/// Calculates the number of bytes `value` would be serialized to, without allocating an output buffer.
+pub fn serialized_size<T: BinarySerializer>(value: &T) -> Result<usize> {
+    Ok(serialize(value, SizeCalculator::new())?.size())
+}
+
+/// Serializes a value directly into a `Write` sink, without collecting the whole result in memory first.
+///
+/// Evolvable types still buffer their own chunks while being serialized.
+#[cfg(feature = "std")]
+pub fn serialize_into_writer<T: BinarySerializer, W: Write>(value: &T, writer: W) -> Result<()> {
+    serialize(value, WriterOutput::new(writer))?.finish()?;
+    Ok(())
+}
+
+/// Like [serialize_into_writer], but writing with the given [Options]. The result is the same as the
+/// output of [serialize_with_options] with the same options.
+#[cfg(feature = "std")]
+pub fn serialize_into_writer_with_options<T: BinarySerializer, W: Write>(
+    value: &T,
+    writer: W,
+    options: Options,
+) -> Result<()> {
+    serialize_with_options(value, WriterOutput::new(writer), options)?.finish()?;
+    Ok(())
+}
+
 /// Wrapper for strings, enabling desert's string deduplication mode.
 ///
 /// The library have a simple deduplication system, without sacrificing any extra
@@ -60,17 +253,103 @@ pub fn serialize_to_byte_vec<T: BinarySerializer>(value: &T) -> Result<Vec<u8>>
 /// same ID to the string if it is first seen.
 pub struct DeduplicatedString(pub String);
 
+/// Wrapper for arbitrary values, enabling deduplication for them similar to [DeduplicatedString].
+///
+/// The first occurrence of a value is written as the length of its serialized form followed by the
+/// serialized bytes, and gets an ID. Every other value of the same type with the same serialized form is
+/// written only as the negated ID. The value is serialized in isolation, so deduplicated strings and
+/// references inside it are not shared with the rest of the stream.
+///
+/// Like [DeduplicatedString], it breaks backward compatibility when evolving data structures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deduplicated<T>(pub T);
+
+/// Wrapper for integers, serializing them as variable length integers instead of their fixed size.
+///
+/// Small values take fewer bytes, for example 64-bit IDs below 128 are written as a single byte, while the
+/// largest values take one or two bytes more than the fixed size encoding. Signed values are zigzag encoded,
+/// so small negative numbers are compact too. Supported for `u32`, `i32`, `u64` and `i64`, and it is not
+/// compatible with the fixed size encoding of the wrapped type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct VarInt<T>(pub T);
+
+/// Wrapper for integers, serializing them in the standard LEB128 encoding, for interoperating with other
+/// formats using it.
+///
+/// Unsigned values are written the same way as by [VarInt]. Signed values are written as signed LEB128,
+/// which sign-extends the two's complement value, while [VarInt] zigzag encodes them, so the two are not
+/// compatible for signed types. Supported for `u32`, `i32`, `u64` and `i64`, the 32-bit types fail to
+/// deserialize values out of their range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Leb128<T>(pub T);
+
+/// A point in time stored as the [Duration](core::time::Duration) elapsed since an epoch [Instant](std::time::Instant).
+///
+/// `Instant` values are opaque and only meaningful within the process that created them, so they cannot be
+/// serialized. This type can be, as the duration, and converted back to an `Instant` relative to the same
+/// (or an equivalent) epoch, for example the start of a recorded session.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct RelativeInstant(pub core::time::Duration);
+
+#[cfg(feature = "std")]
+impl RelativeInstant {
+    /// The time elapsed from `epoch` to `instant`, or zero if `instant` is before `epoch`
+    pub fn new(instant: std::time::Instant, epoch: std::time::Instant) -> Self {
+        Self(instant.saturating_duration_since(epoch))
+    }
+
+    /// The current time relative to `epoch`
+    pub fn since(epoch: std::time::Instant) -> Self {
+        Self::new(std::time::Instant::now(), epoch)
+    }
+
+    /// The instant at this duration after `epoch`, or `None` if it cannot be represented, which can happen
+    /// with deserialized durations
+    pub fn to_instant(&self, epoch: std::time::Instant) -> Option<std::time::Instant> {
+        epoch.checked_add(self.0)
+    }
+}
+
+/// Wrapper for `Rc<T>` and `Arc<T>` values, enabling reference tracking for them.
+///
+/// The first time a shared value is serialized it gets an ID and it is written as usual, every other
+/// occurrence of the same allocation in the stream is written only as a reference to this ID. When
+/// deserializing, these references are resolved to clones of the same `Rc` or `Arc`, so the shape of the
+/// shared graph is preserved.
+///
+/// Cycles are not supported, as the value has to be fully deserialized before it can be referenced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedRef<P>(pub P);
+
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct StringId(pub i32);
 
 impl StringId {
-    fn next(&mut self) {
-        self.0 += 1;
+    fn next(&mut self) -> Result<()> {
+        self.0 = self.0.checked_add(1).ok_or(Error::TooManyStrings)?;
+        Ok(())
     }
 }
 
 impl Display for StringId {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ValueId(pub i32);
+
+impl ValueId {
+    fn next(&mut self) -> Result<()> {
+        self.0 = self.0.checked_add(1).ok_or(Error::TooManyValues)?;
+        Ok(())
+    }
+}
+
+impl Display for ValueId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
@@ -79,13 +358,14 @@ impl Display for StringId {
 pub struct RefId(pub u32);
 
 impl RefId {
-    fn next(&mut self) {
-        self.0 += 1;
+    fn next(&mut self) -> Result<()> {
+        self.0 = self.0.checked_add(1).ok_or(Error::TooManyReferences)?;
+        Ok(())
     }
 }
 
 impl Display for RefId {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0)
     }
 }
@@ -93,15 +373,36 @@ impl Display for RefId {
 #[cfg(test)]
 mod tests {
     use crate::{
-        deserialize, serialize_to_byte_vec, serialize_to_bytes, BinaryDeserializer, BinaryOutput,
-        BinarySerializer, DeserializationContext, SerializationContext,
+        deserialize, deserialize_borrowed, deserialize_exact, deserialize_from_bytes,
+        deserialize_from_reader, deserialize_from_reader_with_options, deserialize_iterator,
+        deserialize_owned, deserialize_with_consumed, deserialize_with_options, serialize,
+        serialize_into_writer, serialize_into_writer_with_options, serialize_iterator,
+        serialize_to_byte_vec, serialize_to_bytes, serialize_unsized_iterator,
+        serialize_with_options, serialized_size, BinaryDeserializeBorrowed, BinaryDeserializer,
+        BinaryInput, BinaryOutput, BinarySerializer, BufInput, BufMutOutput, Deduplicated,
+        DeduplicatedString, DeserializationContext, Error, Leb128, Options, OwnedInput,
+        RelativeInstant, SerializationContext, SharedRef, SliceInput, VarInt,
     };
+    use bytes::{Buf, Bytes, BytesMut};
     use proptest::prelude::*;
-    use std::cell::RefCell;
-    use std::collections::LinkedList;
+    use std::borrow::Cow;
+    use std::cell::{Cell, RefCell};
+    use std::cmp::Reverse;
+    use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList};
+    use std::ffi::OsString;
     use std::fmt::Debug;
+    use std::io::{Cursor, Read, Write};
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+    use std::num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+        NonZeroU32, NonZeroU64, NonZeroU8, Saturating, Wrapping,
+    };
     use std::ops::Deref;
-    use std::rc::Rc;
+    use std::ops::{Range, RangeInclusive};
+    use std::path::{Path, PathBuf};
+    use std::rc::{Rc, Weak};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
     use test_r::test;
 
     pub(crate) fn roundtrip<
@@ -114,6 +415,15 @@ mod tests {
         assert_eq!(value, result);
     }
 
+    fn arb_socket_addr() -> impl Strategy<Value = SocketAddr> {
+        prop_oneof![
+            (any::<Ipv4Addr>(), any::<u16>())
+                .prop_map(|(ip, port)| SocketAddr::V4(SocketAddrV4::new(ip, port))),
+            (any::<Ipv6Addr>(), any::<u16>())
+                .prop_map(|(ip, port)| SocketAddr::V6(SocketAddrV6::new(ip, port, 0, 0))),
+        ]
+    }
+
     fn is_supported_char(char: char) -> bool {
         let code = char as u32;
         let code: Result<u16, _> = code.try_into();
@@ -202,6 +512,129 @@ mod tests {
             roundtrip(value);
         }
 
+        #[test]
+        fn roundtrip_u32_vec(value: Vec<u32>) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn numeric_vec_has_the_same_format_as_per_element_serialization(
+            a: Vec<u16>, b: Vec<i32>, c: Vec<f32>, d: Vec<f64>, e: Vec<i128>,
+        ) {
+            fn per_element<T: BinarySerializer + Clone>(value: &[T]) -> Vec<u8> {
+                serialize_to_byte_vec(&value.iter().cloned().collect::<LinkedList<_>>()).unwrap()
+            }
+            prop_assert_eq!(serialize_to_byte_vec(&a).unwrap(), per_element(&a));
+            prop_assert_eq!(serialize_to_byte_vec(&b).unwrap(), per_element(&b));
+            prop_assert_eq!(serialize_to_byte_vec(&c).unwrap(), per_element(&c));
+            prop_assert_eq!(serialize_to_byte_vec(&d).unwrap(), per_element(&d));
+            prop_assert_eq!(serialize_to_byte_vec(&e).unwrap(), per_element(&e));
+            let mut context = SerializationContext::new(Vec::new());
+            b.as_slice().serialize(&mut context).unwrap();
+            prop_assert_eq!(context.into_output(), per_element(&b));
+
+            let data = per_element(&d);
+            let result = deserialize::<Vec<f64>>(&data).unwrap();
+            prop_assert_eq!(
+                result.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+                d.iter().map(|v| v.to_bits()).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn numeric_array_has_the_same_format_as_per_element_serialization(value: [u64; 5]) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(&data, &serialize_to_byte_vec(&value.iter().copied().collect::<LinkedList<_>>()).unwrap());
+            prop_assert_eq!(deserialize::<[u64; 5]>(&data).unwrap(), value);
+        }
+
+        #[test]
+        fn numeric_vec_can_be_read_from_unknown_size_collection(value: Vec<u32>) {
+            let mut context = SerializationContext::new(Vec::new());
+            serialize_iterator(&mut value.iter().filter(|_| true), &mut context).unwrap();
+            let data = context.into_output();
+            prop_assert_eq!(deserialize::<Vec<u32>>(&data).unwrap(), value);
+        }
+
+        #[test]
+        fn unsized_iterator_ignores_a_wrong_size_hint(value: Vec<u32>) {
+            let mut context = SerializationContext::new(Vec::new());
+            serialize_unsized_iterator(&mut WrongSizeHint(value.iter()), &mut context).unwrap();
+            let data = context.into_output();
+            prop_assert_eq!(deserialize::<Vec<u32>>(&data).unwrap(), value);
+        }
+
+        #[test]
+        fn deserialized_byte_vec_owns_its_buffer(mut value: Vec<u8>, extra: Vec<u8>) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let mut result = deserialize::<Vec<u8>>(&data).unwrap();
+            drop(data);
+            result.extend_from_slice(&extra);
+            value.extend_from_slice(&extra);
+            prop_assert_eq!(result, value);
+        }
+
+        #[test]
+        fn roundtrip_borrowed_str(value: String) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize_borrowed::<&str>(&data).unwrap();
+            prop_assert_eq!(result, value);
+        }
+
+        #[test]
+        fn roundtrip_borrowed_bytes(value: Vec<u8>) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize_borrowed::<&[u8]>(&data).unwrap();
+            prop_assert_eq!(result, value);
+        }
+
+        #[test]
+        fn roundtrip_shared_str(value: String) {
+            roundtrip::<Box<str>>(value.clone().into_boxed_str());
+            roundtrip::<Rc<str>>(Rc::from(value.clone()));
+            roundtrip::<Arc<str>>(Arc::from(value));
+        }
+
+        #[test]
+        fn string_can_be_read_as_arc_str(value: String) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(&serialize_to_byte_vec(&Arc::<str>::from(value.as_str())).unwrap(), &data);
+            let result = deserialize::<Arc<str>>(&data).unwrap();
+            prop_assert_eq!(result.as_ref(), value.as_str());
+        }
+
+        #[test]
+        fn roundtrip_boxed_slice(bytes: Vec<u8>, strings: Vec<String>) {
+            roundtrip(bytes.into_boxed_slice());
+            roundtrip(strings.into_boxed_slice());
+        }
+
+        #[test]
+        fn vec_can_be_read_as_boxed_slice(bytes: Vec<u8>, strings: Vec<String>) {
+            let data = serialize_to_byte_vec(&bytes).unwrap();
+            prop_assert_eq!(&serialize_to_byte_vec(&bytes.clone().into_boxed_slice()).unwrap(), &data);
+            prop_assert_eq!(deserialize::<Box<[u8]>>(&data).unwrap(), bytes.into_boxed_slice());
+
+            let data = serialize_to_byte_vec(&strings).unwrap();
+            prop_assert_eq!(&serialize_to_byte_vec(&strings.clone().into_boxed_slice()).unwrap(), &data);
+            prop_assert_eq!(deserialize::<Box<[String]>>(&data).unwrap(), strings.into_boxed_slice());
+        }
+
+        #[test]
+        fn roundtrip_cow_str(value: String) {
+            roundtrip::<Cow<'static, str>>(Cow::Owned(value));
+        }
+
+        #[test]
+        fn roundtrip_cow_bytes(value: Vec<u8>) {
+            roundtrip::<Cow<'static, [u8]>>(Cow::Owned(value));
+        }
+
+        #[test]
+        fn roundtrip_cow_slice(value: Vec<String>) {
+            roundtrip::<Cow<'static, [String]>>(Cow::Owned(value));
+        }
+
         #[test]
         fn roundtrip_option(value: Option<u32>) {
             roundtrip(value);
@@ -247,11 +680,85 @@ mod tests {
             roundtrip(value);
         }
 
+        #[test]
+        fn roundtrip_tuple9(value: (u32, String, bool, u64, i32, i64, u128, i128, u8)) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_tuple10(value: (u32, String, bool, u64, i32, i64, u128, i128, u8, i8)) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_tuple11(a: (u32, String, bool, u64, i32, i64, u128, i128), b: (u8, i8, u16)) {
+            roundtrip((a.0, a.1, a.2, a.3, a.4, a.5, a.6, a.7, b.0, b.1, b.2));
+        }
+
+        #[test]
+        fn roundtrip_tuple12(a: (u32, String, bool, u64, i32, i64, u128, i128), b: (u8, i8, u16, i16)) {
+            roundtrip((a.0, a.1, a.2, a.3, a.4, a.5, a.6, a.7, b.0, b.1, b.2, b.3));
+        }
+
+        #[test]
+        fn roundtrip_tuple13(a: (u32, String, bool, u64, i32, i64, u128, i128), b: (u8, i8, u16, i16, Option<u32>)) {
+            // no Debug and PartialEq for tuples this large, so the result is compared in two halves
+            let (a0, a1, a2, a3, a4, a5, a6, a7) = a.clone();
+            let (b0, b1, b2, b3, b4) = b;
+            let data = serialize_to_byte_vec(&(a0, a1, a2, a3, a4, a5, a6, a7, b0, b1, b2, b3, b4)).unwrap();
+            let (r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11, r12) = deserialize(&data).unwrap();
+            prop_assert_eq!((r0, r1, r2, r3, r4, r5, r6, r7), a);
+            prop_assert_eq!((r8, r9, r10, r11, r12), b);
+        }
+
+        #[test]
+        fn roundtrip_tuple14(a: (u32, String, bool, u64, i32, i64, u128, i128), b: (u8, i8, u16, i16, Option<u32>, Vec<u8>)) {
+            // no Debug and PartialEq for tuples this large, so the result is compared in two halves
+            let (a0, a1, a2, a3, a4, a5, a6, a7) = a.clone();
+            let (b0, b1, b2, b3, b4, b5) = b.clone();
+            let data = serialize_to_byte_vec(&(a0, a1, a2, a3, a4, a5, a6, a7, b0, b1, b2, b3, b4, b5)).unwrap();
+            let (r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11, r12, r13) = deserialize(&data).unwrap();
+            prop_assert_eq!((r0, r1, r2, r3, r4, r5, r6, r7), a);
+            prop_assert_eq!((r8, r9, r10, r11, r12, r13), b);
+        }
+
+        #[test]
+        fn roundtrip_tuple15(a: (u32, String, bool, u64, i32, i64, u128, i128), b: (u8, i8, u16, i16, Option<u32>, Vec<u8>, String)) {
+            // no Debug and PartialEq for tuples this large, so the result is compared in two halves
+            let (a0, a1, a2, a3, a4, a5, a6, a7) = a.clone();
+            let (b0, b1, b2, b3, b4, b5, b6) = b.clone();
+            let data = serialize_to_byte_vec(&(a0, a1, a2, a3, a4, a5, a6, a7, b0, b1, b2, b3, b4, b5, b6)).unwrap();
+            let (r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11, r12, r13, r14) = deserialize(&data).unwrap();
+            prop_assert_eq!((r0, r1, r2, r3, r4, r5, r6, r7), a);
+            prop_assert_eq!((r8, r9, r10, r11, r12, r13, r14), b);
+        }
+
+        #[test]
+        fn roundtrip_tuple16(a: (u32, String, bool, u64, i32, i64, u128, i128), b: (u8, i8, u16, i16, Option<u32>, Vec<u8>, String, bool)) {
+            // no Debug and PartialEq for tuples this large, so the result is compared in two halves
+            let (a0, a1, a2, a3, a4, a5, a6, a7) = a.clone();
+            let (b0, b1, b2, b3, b4, b5, b6, b7) = b.clone();
+            let data = serialize_to_byte_vec(&(a0, a1, a2, a3, a4, a5, a6, a7, b0, b1, b2, b3, b4, b5, b6, b7)).unwrap();
+            let (r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11, r12, r13, r14, r15) = deserialize(&data).unwrap();
+            prop_assert_eq!((r0, r1, r2, r3, r4, r5, r6, r7), a);
+            prop_assert_eq!((r8, r9, r10, r11, r12, r13, r14, r15), b);
+        }
+
         #[test]
         fn roundtrip_sized_array(value: [u32; 3]) {
             roundtrip(value);
         }
 
+        #[test]
+        fn roundtrip_sized_byte_array(value: [u8; 5]) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_sized_string_array(value: [String; 4]) {
+            roundtrip(value);
+        }
+
         #[test]
         fn roundtrip_hashset(value: std::collections::HashSet<String>) {
             roundtrip(value);
@@ -272,6 +779,199 @@ mod tests {
             roundtrip(value);
         }
 
+        #[test]
+        fn roundtrip_binary_heap(value: BinaryHeap<u32>) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            let result = deserialize::<BinaryHeap<u32>>(&data).unwrap();
+            assert_eq!(value.into_sorted_vec(), result.into_sorted_vec());
+        }
+
+        #[test]
+        fn roundtrip_ipv4_addr(value: Ipv4Addr) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_ipv6_addr(value: Ipv6Addr) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_ip_addr(value: IpAddr) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_socket_addr(value in arb_socket_addr()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn ip_addr_contains_standalone_encoding(v4: Ipv4Addr, v6: Ipv6Addr) {
+            let data = serialize_to_byte_vec(&IpAddr::V4(v4)).unwrap();
+            prop_assert_eq!(data[0], 0);
+            prop_assert_eq!(&data[1..], &serialize_to_byte_vec(&v4).unwrap()[..]);
+            let data = serialize_to_byte_vec(&IpAddr::V6(v6)).unwrap();
+            prop_assert_eq!(data[0], 1);
+            prop_assert_eq!(&data[1..], &serialize_to_byte_vec(&v6).unwrap()[..]);
+        }
+
+        #[test]
+        fn socket_addr_v4_contains_ipv4_addr_encoding(ip: Ipv4Addr, port: u16) {
+            let socket_addr = serialize_to_byte_vec(&SocketAddr::V4(SocketAddrV4::new(ip, port))).unwrap();
+            let ip = serialize_to_byte_vec(&ip).unwrap();
+            prop_assert_eq!(&socket_addr[1..5], &ip[..]);
+        }
+
+        #[test]
+        fn roundtrip_cell(value: u64) {
+            roundtrip(Cell::new(value));
+        }
+
+        #[test]
+        fn roundtrip_ref_cell(value: Vec<String>) {
+            roundtrip(RefCell::new(value));
+        }
+
+        #[test]
+        fn ref_cell_is_serialized_like_the_inner_value(value: String) {
+            let bare = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(&serialize_to_byte_vec(&RefCell::new(value.clone())).unwrap(), &bare);
+            let result = deserialize::<RefCell<String>>(&bare).unwrap();
+            prop_assert_eq!(result.into_inner(), value);
+        }
+
+        #[test]
+        fn cell_is_serialized_like_the_inner_value(value: i32) {
+            let bare = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(&serialize_to_byte_vec(&Cell::new(value)).unwrap(), &bare);
+            let result = deserialize::<Cell<i32>>(&bare).unwrap();
+            prop_assert_eq!(result.get(), value);
+        }
+
+        #[test]
+        fn roundtrip_deduplicated_strings(value: Vec<String>, repeats in 1..4usize) {
+            let repeated: Vec<String> = value.iter().cycle().take(value.len() * repeats).cloned().collect();
+            let wrapped: Vec<DeduplicatedString> = repeated.iter().cloned().map(DeduplicatedString).collect();
+            let data = serialize_to_byte_vec(&wrapped).unwrap();
+            let result = deserialize::<Vec<DeduplicatedString>>(&data).unwrap();
+            let result: Vec<String> = result.into_iter().map(|s| s.0).collect();
+            prop_assert_eq!(result, repeated);
+        }
+
+        #[test]
+        fn roundtrip_deduplicated_values(value: Vec<(u32, String)>, repeats in 1..4usize) {
+            let repeated: Vec<Deduplicated<(u32, String)>> = value.iter().cycle().take(value.len() * repeats).cloned().map(Deduplicated).collect();
+            roundtrip(repeated);
+        }
+
+        #[test]
+        fn roundtrip_var_int(a: u32, b: i32, c: u64, d: i64) {
+            roundtrip(VarInt(a));
+            roundtrip(VarInt(b));
+            roundtrip(VarInt(c));
+            roundtrip(VarInt(d));
+        }
+
+        #[test]
+        fn roundtrip_leb128(a: u32, b: i32, c: u64, d: i64) {
+            roundtrip(Leb128(a));
+            roundtrip(Leb128(b));
+            roundtrip(Leb128(c));
+            roundtrip(Leb128(d));
+        }
+
+        #[test]
+        fn leb128_matches_the_reference_implementation(a: u64, b: i64) {
+            use gimli::leb128;
+            use gimli::{EndianSlice, LittleEndian};
+
+            let mut expected = Vec::new();
+            leb128::write::unsigned(&mut expected, a).unwrap();
+            let data = serialize_to_byte_vec(&Leb128(a)).unwrap();
+            prop_assert_eq!(&data, &expected);
+            prop_assert_eq!(leb128::read::unsigned(&mut EndianSlice::new(&data, LittleEndian)).unwrap(), a);
+
+            let mut expected = Vec::new();
+            leb128::write::signed(&mut expected, b).unwrap();
+            let data = serialize_to_byte_vec(&Leb128(b)).unwrap();
+            prop_assert_eq!(&data, &expected);
+            prop_assert_eq!(leb128::read::signed(&mut EndianSlice::new(&data, LittleEndian)).unwrap(), b);
+        }
+
+        #[test]
+        fn roundtrip_wrapping(a: u8, b: i64) {
+            roundtrip(Wrapping(a));
+            roundtrip(Wrapping(b));
+        }
+
+        #[test]
+        fn roundtrip_saturating(a: u16, b: i128) {
+            roundtrip(Saturating(a));
+            roundtrip(Saturating(b));
+        }
+
+        #[test]
+        fn wrapping_is_compatible_with_the_bare_integer(value: u64) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(&serialize_to_byte_vec(&Wrapping(value)).unwrap(), &data);
+            prop_assert_eq!(deserialize::<Wrapping<u64>>(&data).unwrap(), Wrapping(value));
+            prop_assert_eq!(deserialize::<Saturating<u64>>(&data).unwrap(), Saturating(value));
+        }
+
+        #[test]
+        fn roundtrip_reverse(a: i32, b: Vec<String>) {
+            roundtrip(Reverse(a));
+            roundtrip(Reverse(b));
+        }
+
+        #[test]
+        fn reverse_is_compatible_with_the_bare_value(value: i32) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(&serialize_to_byte_vec(&Reverse(value)).unwrap(), &data);
+            prop_assert_eq!(deserialize::<Reverse<i32>>(&data).unwrap(), Reverse(value));
+        }
+
+        #[test]
+        fn roundtrip_non_zero(a in 1..=u8::MAX, b: i8, c: u16, d: i16, e: u32, f: i32, g: u64, h: i64, i: u128, j: i128) {
+            roundtrip(NonZeroU8::new(a).unwrap());
+            if let Some(b) = NonZeroI8::new(b) { roundtrip(b); }
+            if let Some(c) = NonZeroU16::new(c) { roundtrip(c); }
+            if let Some(d) = NonZeroI16::new(d) { roundtrip(d); }
+            if let Some(e) = NonZeroU32::new(e) { roundtrip(e); }
+            if let Some(f) = NonZeroI32::new(f) { roundtrip(f); }
+            if let Some(g) = NonZeroU64::new(g) { roundtrip(g); }
+            if let Some(h) = NonZeroI64::new(h) { roundtrip(h); }
+            if let Some(i) = NonZeroU128::new(i) { roundtrip(i); }
+            if let Some(j) = NonZeroI128::new(j) { roundtrip(j); }
+        }
+
+        #[test]
+        fn non_zero_is_compatible_with_the_bare_integer(value in 1..=u32::MAX) {
+            let data = serialize_to_byte_vec(&value).unwrap();
+            prop_assert_eq!(&serialize_to_byte_vec(&NonZeroU32::new(value).unwrap()).unwrap(), &data);
+            prop_assert_eq!(deserialize::<NonZeroU32>(&data).unwrap().get(), value);
+        }
+
+        #[test]
+        fn roundtrip_range(value: Range<i32>) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn roundtrip_range_inclusive(value: RangeInclusive<u64>) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn range_is_not_serialized_as_a_tuple(start: i32, end: i32) {
+            let range = serialize_to_byte_vec(&(start..end)).unwrap();
+            let tuple = serialize_to_byte_vec(&(start, end)).unwrap();
+            prop_assert_ne!(&range, &tuple);
+            prop_assert_eq!(&range[..4], &start.to_be_bytes());
+            prop_assert_eq!(&range[4..], &end.to_be_bytes());
+        }
+
         #[test]
         fn roundtrip_result(value: Result<u32, String>) {
             roundtrip(value);
@@ -299,7 +999,7 @@ mod tests {
             match &node.next {
                 Some(next) => {
                     true.serialize(context)?;
-                    if context.store_ref_or_object(next)? {
+                    if context.store_rc_or_object(next)? {
                         next.serialize(context)?;
                     }
                 }
@@ -315,18 +1015,14 @@ mod tests {
         fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
             let label = String::deserialize(context)?;
             let result = Rc::new(RefCell::new(Node { label, next: None }));
-            context.state_mut().store_ref(&result);
+            context.register_ref(&result)?;
             let has_next = bool::deserialize(context)?;
             if has_next {
-                match context.try_read_ref()? {
-                    Some(next) => {
-                        result.borrow_mut().next =
-                            Some(next.downcast_ref::<Rc<RefCell<Node>>>().unwrap().clone())
-                    }
-                    None => {
-                        result.borrow_mut().next = Some(Rc::<RefCell<Node>>::deserialize(context)?)
-                    }
-                }
+                let next = match context.try_read_rc()? {
+                    Some(next) => next,
+                    None => Rc::<RefCell<Node>>::deserialize(context)?,
+                };
+                result.borrow_mut().next = Some(next);
             }
             Ok(result)
         }
@@ -342,7 +1038,7 @@ mod tests {
             &self,
             context: &mut SerializationContext<Output>,
         ) -> crate::Result<()> {
-            if context.store_ref_or_object(&self.node)? {
+            if context.store_rc_or_object(&self.node)? {
                 self.node.serialize(context)?;
             }
             Ok(())
@@ -351,21 +1047,1404 @@ mod tests {
 
     impl BinaryDeserializer for Root {
         fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
-            let node = match context.try_read_ref()? {
-                Some(node) => node.downcast_ref::<Rc<RefCell<Node>>>().unwrap().clone(),
+            let node = match context.try_read_rc()? {
+                Some(node) => node,
                 None => Rc::<RefCell<Node>>::deserialize(context)?,
             };
             Ok(Root { node })
         }
     }
 
-    #[test]
-    fn known_sized_collection_is_stack_safe() {
-        let big_vec = (0..1_000_000).collect::<Vec<_>>();
-        roundtrip(big_vec);
+    /// A node of a doubly-linked list, where `prev` is a back-reference to a node that has already been
+    /// serialized when it is reached through `next`.
+    ///
+    /// The codec writes each node with [SerializationContext::store_rc_or_object], which emits either a
+    /// reference to an already written node or a marker followed by the node itself. On the reading side
+    /// [DeserializationContext::try_read_rc] resolves references, and new nodes are created empty and
+    /// registered with [DeserializationContext::register_ref] before their links are read, so the
+    /// back-references pointing to them can be resolved.
+    #[derive(Debug)]
+    struct DoublyLinkedNode {
+        value: i32,
+        prev: Option<Weak<RefCell<DoublyLinkedNode>>>,
+        next: Option<Rc<RefCell<DoublyLinkedNode>>>,
     }
 
-    #[test]
+    #[derive(Debug)]
+    struct DoublyLinkedList {
+        head: Option<Rc<RefCell<DoublyLinkedNode>>>,
+    }
+
+    fn serialize_dl_node<Output: BinaryOutput>(
+        node: &Option<Rc<RefCell<DoublyLinkedNode>>>,
+        context: &mut SerializationContext<Output>,
+    ) -> crate::Result<()> {
+        match node {
+            Some(node) => {
+                true.serialize(context)?;
+                if context.store_rc_or_object(node)? {
+                    let node = node.borrow();
+                    node.value.serialize(context)?;
+                    serialize_dl_node(&node.prev.as_ref().and_then(Weak::upgrade), context)?;
+                    serialize_dl_node(&node.next, context)?;
+                }
+            }
+            None => false.serialize(context)?,
+        }
+        Ok(())
+    }
+
+    fn deserialize_dl_node(
+        context: &mut DeserializationContext<'_>,
+    ) -> crate::Result<Option<Rc<RefCell<DoublyLinkedNode>>>> {
+        if !bool::deserialize(context)? {
+            return Ok(None);
+        }
+        if let Some(node) = context.try_read_rc()? {
+            return Ok(Some(node));
+        }
+        let node = Rc::new(RefCell::new(DoublyLinkedNode {
+            value: 0,
+            prev: None,
+            next: None,
+        }));
+        context.register_ref(&node)?;
+        let value = i32::deserialize(context)?;
+        let prev = deserialize_dl_node(context)?;
+        let next = deserialize_dl_node(context)?;
+        {
+            let mut node = node.borrow_mut();
+            node.value = value;
+            node.prev = prev.as_ref().map(Rc::downgrade);
+            node.next = next;
+        }
+        Ok(Some(node))
+    }
+
+    impl BinarySerializer for DoublyLinkedList {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            context: &mut SerializationContext<Output>,
+        ) -> crate::Result<()> {
+            serialize_dl_node(&self.head, context)
+        }
+    }
+
+    impl BinaryDeserializer for DoublyLinkedList {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+            Ok(DoublyLinkedList {
+                head: deserialize_dl_node(context)?,
+            })
+        }
+    }
+
+    impl DoublyLinkedList {
+        fn from_values(values: &[i32]) -> Self {
+            let mut head: Option<Rc<RefCell<DoublyLinkedNode>>> = None;
+            for value in values.iter().rev() {
+                let node = Rc::new(RefCell::new(DoublyLinkedNode {
+                    value: *value,
+                    prev: None,
+                    next: head.clone(),
+                }));
+                if let Some(old_head) = &head {
+                    old_head.borrow_mut().prev = Some(Rc::downgrade(&node));
+                }
+                head = Some(node);
+            }
+            DoublyLinkedList { head }
+        }
+
+        fn nodes(&self) -> Vec<Rc<RefCell<DoublyLinkedNode>>> {
+            let mut result = Vec::new();
+            let mut current = self.head.clone();
+            while let Some(node) = current {
+                current = node.borrow().next.clone();
+                result.push(node);
+            }
+            result
+        }
+    }
+
+    #[test]
+    fn known_sized_collection_is_stack_safe() {
+        let big_vec = (0..1_000_000).collect::<Vec<_>>();
+        roundtrip(big_vec);
+    }
+
+    #[test]
+    fn binary_heap_preserves_contents_but_not_order() {
+        // The heap is serialized in its internal (arbitrary) iteration order, so only the
+        // multiset of elements is guaranteed to survive, not the order they were pushed in.
+        let heap = BinaryHeap::from(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+        let data = serialize_to_byte_vec(&heap).unwrap();
+
+        let as_vec = deserialize::<Vec<i32>>(&data).unwrap();
+        let mut sorted = as_vec.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![1, 1, 2, 3, 4, 5, 6, 9]);
+
+        let as_set = deserialize::<BTreeSet<i32>>(&data).unwrap();
+        assert_eq!(
+            as_set.into_iter().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 6, 9]
+        );
+
+        let as_heap = deserialize::<BinaryHeap<i32>>(&data).unwrap();
+        assert_eq!(as_heap.into_sorted_vec(), sorted);
+    }
+
+    #[test]
+    fn min_heap_of_reverse_roundtrips() {
+        let heap = BinaryHeap::from(vec![Reverse(3), Reverse(1), Reverse(4), Reverse(2)]);
+        let data = serialize_to_byte_vec(&heap).unwrap();
+        let mut result = deserialize::<BinaryHeap<Reverse<i32>>>(&data).unwrap();
+        assert_eq!(result.pop(), Some(Reverse(1)));
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn relative_instant_roundtrips_as_duration() {
+        let epoch = Instant::now();
+        let instant = epoch + Duration::from_millis(1500);
+        let relative = RelativeInstant::new(instant, epoch);
+        assert_eq!(relative.0, Duration::from_millis(1500));
+        assert_eq!(relative.to_instant(epoch), Some(instant));
+
+        let data = serialize_to_byte_vec(&relative).unwrap();
+        assert_eq!(data, serialize_to_byte_vec(&relative.0).unwrap());
+        let result = deserialize::<RelativeInstant>(&data).unwrap();
+        assert_eq!(result, relative);
+        assert_eq!(result.to_instant(epoch), Some(instant));
+
+        assert_eq!(RelativeInstant::new(epoch, instant).0, Duration::ZERO);
+        assert!(RelativeInstant::since(epoch).to_instant(epoch).unwrap() >= epoch);
+    }
+
+    #[test]
+    fn relative_instant_out_of_range_is_not_converted() {
+        let data = serialize_to_byte_vec(&Duration::new(u64::MAX, 999_999_999)).unwrap();
+        let result = deserialize::<RelativeInstant>(&data).unwrap();
+        assert_eq!(result.to_instant(Instant::now()), None);
+    }
+
+    #[test]
+    fn ip_addr_size_depends_on_the_version() {
+        let v4 = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+        assert_eq!(serialized_size(&v4).unwrap(), 5);
+        assert_eq!(serialize_to_byte_vec(&v4).unwrap(), vec![0, 192, 168, 0, 1]);
+        let v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        assert_eq!(serialized_size(&v6).unwrap(), 17);
+    }
+
+    #[test]
+    fn borrowed_cow_is_serialized_like_the_owned_value() {
+        let text = "hello world";
+        let bytes: &[u8] = &[1, 2, 3, 4];
+        assert_eq!(
+            serialize_to_byte_vec(&Cow::Borrowed(text)).unwrap(),
+            serialize_to_byte_vec(&text.to_string()).unwrap()
+        );
+        assert_eq!(
+            serialize_to_byte_vec(&Cow::Borrowed(bytes)).unwrap(),
+            serialize_to_byte_vec(&bytes.to_vec()).unwrap()
+        );
+    }
+
+    #[test]
+    fn string_can_be_read_as_cow_str() {
+        let data = serialize_to_byte_vec(&"hello world".to_string()).unwrap();
+        let result = deserialize::<Cow<'static, str>>(&data).unwrap();
+        assert!(matches!(result, Cow::Owned(_)));
+        assert_eq!(result, "hello world");
+    }
+
+    #[test]
+    fn borrowed_str_points_into_the_input() {
+        let mut data = serialize_to_byte_vec(&"hello".to_string()).unwrap();
+        data.extend(serialize_to_byte_vec(&"world".to_string()).unwrap());
+        let mut context = DeserializationContext::new(&data);
+        let first = <&str>::deserialize_borrowed(&mut context).unwrap();
+        let second = <&str>::deserialize_borrowed(&mut context).unwrap();
+        assert_eq!((first, second), ("hello", "world"));
+        assert!(data.as_ptr_range().contains(&second.as_ptr()));
+    }
+
+    #[test]
+    fn borrowed_str_with_invalid_utf8_fails() {
+        let mut data = serialize_to_byte_vec(&"ab".to_string()).unwrap();
+        data[1..].copy_from_slice(&[0xC3, 0x28]);
+        let result = deserialize_borrowed::<&str>(&data);
+        assert!(matches!(result, Err(Error::FailedToDecodeString(_))));
+    }
+
+    #[test]
+    fn borrowing_from_reader_fails() {
+        let data = serialize_to_byte_vec(&"hello".to_string()).unwrap();
+        let mut context = DeserializationContext::from_reader(Cursor::new(data));
+        let result = <&str>::deserialize_borrowed(&mut context);
+        assert!(matches!(result, Err(Error::BorrowingFromReader)));
+    }
+
+    #[test]
+    fn borrowing_from_bytes_fails() {
+        let data = serialize_to_bytes(&"hello".to_string()).unwrap();
+        let mut context = DeserializationContext::from_bytes(data);
+        let result = <&str>::deserialize_borrowed(&mut context);
+        assert!(matches!(result, Err(Error::BorrowingFromBytes)));
+    }
+
+    #[test]
+    fn borrowing_across_segments_fails() {
+        let data = serialize_to_byte_vec(&("hello".to_string(), "world".to_string())).unwrap();
+        let (first, second) = data.split_at(4);
+        let result = DeserializationContext::from_chain(&[first, second]).read_borrowed_bytes(6);
+        assert!(matches!(result, Err(Error::BorrowingAcrossSegments)));
+
+        let (first, second) = data.split_at(2);
+        let mut context = DeserializationContext::from_chain(&[first, second]);
+        context.read_u8().unwrap();
+        let result = <&str>::deserialize_borrowed(&mut context).unwrap();
+        assert_eq!(result, "hello");
+    }
+
+    #[test]
+    fn bytes_are_sliced_from_the_shared_input() {
+        let value = (
+            "prefix".to_string(),
+            Bytes::from(vec![1u8; 1024]),
+            vec![Bytes::from_static(b"abc"), Bytes::new()],
+        );
+        let data = serialize_to_bytes(&value).unwrap();
+        let result: (String, Bytes, Vec<Bytes>) = deserialize_from_bytes(data.clone()).unwrap();
+        assert_eq!(result, value);
+
+        let range = data.as_ptr_range();
+        assert!(range.contains(&result.1.as_ptr()));
+        assert!(range.contains(&result.2[0].as_ptr()));
+    }
+
+    #[test]
+    fn bytes_mut_is_compatible_with_bytes() {
+        let value = BytesMut::from(&b"hello world"[..]);
+        roundtrip(value.clone());
+        let data = serialize_to_byte_vec(&value).unwrap();
+        assert_eq!(
+            data,
+            serialize_to_byte_vec(&value.clone().freeze()).unwrap()
+        );
+        assert_eq!(deserialize::<Bytes>(&data).unwrap(), value.freeze());
+    }
+
+    #[test]
+    fn roundtrip_system_times() {
+        roundtrip(UNIX_EPOCH);
+        roundtrip(UNIX_EPOCH + Duration::new(0, 1));
+        roundtrip(UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789));
+        roundtrip(UNIX_EPOCH + Duration::new(253_402_300_799, 999_999_999));
+        roundtrip(SystemTime::now());
+    }
+
+    #[test]
+    fn system_time_has_the_same_format_as_duration() {
+        let duration = Duration::new(1_700_000_000, 123_456_789);
+        assert_eq!(
+            serialize_to_byte_vec(&(UNIX_EPOCH + duration)).unwrap(),
+            serialize_to_byte_vec(&duration).unwrap()
+        );
+    }
+
+    #[test]
+    fn serializing_system_time_before_unix_epoch_fails() {
+        let result = serialize_to_byte_vec(&(UNIX_EPOCH - Duration::from_secs(1)));
+        assert!(matches!(
+            result,
+            Err(Error::SystemTimeBeforeUnixEpoch(duration)) if duration == Duration::from_secs(1)
+        ));
+    }
+
+    #[test]
+    fn roundtrip_paths() {
+        roundtrip(PathBuf::new());
+        roundtrip(PathBuf::from("relative/path/file.txt"));
+        roundtrip(PathBuf::from("/absolute/path/with spaces/ünicode"));
+        roundtrip(PathBuf::from(r"C:\Windows\System32"));
+    }
+
+    #[test]
+    fn path_is_serialized_as_string() {
+        let path = Path::new("/tmp/file.txt");
+        assert_eq!(
+            serialize_to_byte_vec(&path).unwrap(),
+            serialize_to_byte_vec(&"/tmp/file.txt".to_string()).unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn serializing_non_utf8_path_fails() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"invalid/\xFF/path"));
+        let result = serialize_to_byte_vec(&path);
+        assert!(matches!(result, Err(Error::NonUtf8Path(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn roundtrip_non_utf8_os_string() {
+        use std::os::unix::ffi::OsStringExt;
+
+        roundtrip(OsString::from("hello world"));
+        roundtrip(OsString::from_vec(vec![b'a', 0xFF, 0xFE, b'b']));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn os_string_is_serialized_as_tagged_raw_bytes() {
+        let data = serialize_to_byte_vec(&OsString::from("ab")).unwrap();
+        assert_eq!(data, vec![crate::OS_STRING_BYTES_TAG, 2, b'a', b'b']);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reading_wide_os_string_on_unix_fails() {
+        let data = vec![crate::OS_STRING_WIDE_TAG, 4, 0, 97];
+        let result = deserialize::<OsString>(&data);
+        assert!(matches!(
+            result,
+            Err(Error::IncompatibleOsStringEncoding(
+                crate::OS_STRING_WIDE_TAG
+            ))
+        ));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn roundtrip_unpaired_surrogate_os_string() {
+        use std::os::windows::ffi::OsStringExt;
+
+        roundtrip(OsString::from("hello world"));
+        roundtrip(OsString::from_wide(&[0x61, 0xD800, 0x62]));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn os_string_is_serialized_as_tagged_wide_units() {
+        let data = serialize_to_byte_vec(&OsString::from("ab")).unwrap();
+        assert_eq!(data, vec![crate::OS_STRING_WIDE_TAG, 4, 0, 97, 0, 98]);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn reading_unix_os_string_on_windows_fails() {
+        let data = vec![crate::OS_STRING_BYTES_TAG, 2, b'a', b'b'];
+        let result = deserialize::<OsString>(&data);
+        assert!(matches!(
+            result,
+            Err(Error::IncompatibleOsStringEncoding(
+                crate::OS_STRING_BYTES_TAG
+            ))
+        ));
+    }
+
+    #[test]
+    fn reading_os_string_with_invalid_tag_fails() {
+        let result = deserialize::<OsString>(&[42]);
+        assert!(matches!(result, Err(Error::DeserializationFailure(_))));
+    }
+
+    #[test]
+    fn roundtrip_shared_ref_cell() {
+        let value = Rc::new(RefCell::new(vec![1u32, 2, 3]));
+        let data = serialize_to_byte_vec(&value).unwrap();
+        let result = deserialize::<Rc<RefCell<Vec<u32>>>>(&data).unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn shared_ref_preserves_sharing() {
+        let shared = Rc::new("shared".to_string());
+        let other = Rc::new("shared".to_string());
+        let value = vec![
+            SharedRef(shared.clone()),
+            SharedRef(other),
+            SharedRef(shared.clone()),
+        ];
+        let data = serialize_to_byte_vec(&value).unwrap();
+        let result = deserialize::<Vec<SharedRef<Rc<String>>>>(&data).unwrap();
+
+        assert_eq!(result, value);
+        assert!(Rc::ptr_eq(&result[0].0, &result[2].0));
+        assert!(!Rc::ptr_eq(&result[0].0, &result[1].0));
+    }
+
+    #[test]
+    fn shared_ref_serializes_duplicates_as_references() {
+        let shared = Arc::new("x".repeat(100));
+        let once = serialized_size(&vec![SharedRef(shared.clone())]).unwrap();
+        let twice = serialized_size(&vec![SharedRef(shared.clone()), SharedRef(shared)]).unwrap();
+        assert_eq!(twice - once, 1);
+    }
+
+    #[test]
+    fn shared_ref_preserves_nested_dag() {
+        let leaf = Arc::new(42u64);
+        let left = Arc::new((SharedRef(leaf.clone()), 1u8));
+        let right = Arc::new((SharedRef(leaf.clone()), 2u8));
+        let root = (SharedRef(left.clone()), SharedRef(right), SharedRef(left));
+        let data = serialize_to_byte_vec(&root).unwrap();
+        let (left1, right, left2) = deserialize::<(
+            SharedRef<Arc<(SharedRef<Arc<u64>>, u8)>>,
+            SharedRef<Arc<(SharedRef<Arc<u64>>, u8)>>,
+            SharedRef<Arc<(SharedRef<Arc<u64>>, u8)>>,
+        )>(&data)
+        .unwrap();
+
+        assert_eq!(*left1.0 .0 .0, 42);
+        assert_eq!((left1.0 .1, right.0 .1), (1, 2));
+        assert!(Arc::ptr_eq(&left1.0, &left2.0));
+        assert!(Arc::ptr_eq(&left1.0 .0 .0, &right.0 .0 .0));
+    }
+
+    #[test]
+    fn concatenated_values_can_be_read_using_the_consumed_length() {
+        let mut data = serialize_to_byte_vec(&"first".to_string()).unwrap();
+        data.extend(serialize_to_byte_vec(&(42u64, vec![1i32, 2, 3])).unwrap());
+
+        let (first, consumed) = deserialize_with_consumed::<String>(&data).unwrap();
+        assert_eq!(first, "first");
+        assert_eq!(consumed, 6);
+
+        let (second, rest) =
+            deserialize_with_consumed::<(u64, Vec<i32>)>(&data[consumed..]).unwrap();
+        assert_eq!(second, (42, vec![1, 2, 3]));
+        assert_eq!(consumed + rest, data.len());
+    }
+
+    #[test]
+    fn exact_input_is_deserialized() {
+        let data = serialize_to_byte_vec(&(42u64, vec![1i32, 2, 3])).unwrap();
+        let result = deserialize_exact::<(u64, Vec<i32>)>(&data).unwrap();
+        assert_eq!(result, (42, vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn trailing_data_is_rejected() {
+        let mut data = serialize_to_byte_vec(&"value".to_string()).unwrap();
+        data.extend([1, 2, 3]);
+        let result = deserialize_exact::<String>(&data);
+        assert!(matches!(result, Err(Error::TrailingData { remaining: 3 })));
+    }
+
+    #[test]
+    fn remaining_tracks_the_read_position() {
+        let data = serialize_to_byte_vec(&(1u32, 2u64)).unwrap();
+        let mut context = DeserializationContext::new(&data);
+        assert_eq!((context.pos(), context.remaining()), (0, 13));
+        context.read_u8().unwrap();
+        u32::deserialize(&mut context).unwrap();
+        assert_eq!((context.pos(), context.remaining()), (5, 8));
+        u64::deserialize(&mut context).unwrap();
+        assert_eq!(context.remaining(), 0);
+    }
+
+    thread_local! {
+        static LIVE_DROP_COUNTED: Cell<isize> = const { Cell::new(0) };
+    }
+
+    /// Keeps track of the number of live instances, to detect leaked or double dropped values
+    struct DropCounted(#[allow(dead_code)] String);
+
+    impl BinaryDeserializer for DropCounted {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+            let value = String::deserialize(context)?;
+            LIVE_DROP_COUNTED.with(|live| live.set(live.get() + 1));
+            Ok(DropCounted(value))
+        }
+    }
+
+    impl Drop for DropCounted {
+        fn drop(&mut self) {
+            LIVE_DROP_COUNTED.with(|live| live.set(live.get() - 1));
+        }
+    }
+
+    #[test]
+    fn truncated_array_drops_the_deserialized_elements() {
+        let value = ["a", "b", "c", "d"].map(|s| s.to_string());
+        let data = serialize_to_byte_vec(&value).unwrap();
+
+        for len in 0..data.len() {
+            let result = deserialize::<[DropCounted; 4]>(&data[..len]);
+            assert!(
+                matches!(result, Err(Error::InputEndedUnexpectedly { offset }) if offset == len)
+            );
+            assert_eq!(LIVE_DROP_COUNTED.with(|live| live.get()), 0);
+        }
+
+        let result = deserialize::<[DropCounted; 4]>(&data).unwrap();
+        assert_eq!(LIVE_DROP_COUNTED.with(|live| live.get()), 4);
+        drop(result);
+        assert_eq!(LIVE_DROP_COUNTED.with(|live| live.get()), 0);
+    }
+
+    #[test]
+    fn array_with_different_length_is_rejected() {
+        let data = serialize_to_byte_vec(&vec!["a".to_string(), "b".to_string()]).unwrap();
+        let result = deserialize::<[DropCounted; 3]>(&data);
+        assert!(matches!(result, Err(Error::DeserializationFailure(_))));
+        let result = deserialize::<[DropCounted; 1]>(&data);
+        assert!(matches!(result, Err(Error::DeserializationFailure(_))));
+        assert_eq!(LIVE_DROP_COUNTED.with(|live| live.get()), 0);
+
+        let data = serialize_to_byte_vec(&[1u8, 2, 3]).unwrap();
+        let result = deserialize::<[u8; 4]>(&data);
+        assert!(matches!(result, Err(Error::DeserializationFailure(_))));
+    }
+
+    fn limited(max_collection_length: usize) -> Options {
+        Options {
+            max_collection_length: Some(max_collection_length),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn crafted_byte_vec_length_is_rejected() {
+        let mut data = Vec::new();
+        data.write_var_u32(u32::MAX);
+        let result = deserialize_with_options::<Vec<u8>>(&data, limited(1024));
+        assert!(matches!(
+            result,
+            Err(Error::CollectionTooLong {
+                length: 4294967295,
+                limit: 1024
+            })
+        ));
+        let result = deserialize_with_options::<Bytes>(&data, limited(1024));
+        assert!(matches!(result, Err(Error::CollectionTooLong { .. })));
+    }
+
+    #[test]
+    fn crafted_collection_length_is_rejected() {
+        let mut data = Vec::new();
+        data.write_var_i32(i32::MAX);
+        let result = deserialize_with_options::<Vec<String>>(&data, limited(1024));
+        assert!(matches!(
+            result,
+            Err(Error::CollectionTooLong {
+                length: 2147483647,
+                limit: 1024
+            })
+        ));
+        let result = deserialize_with_options::<BTreeSet<u64>>(&data, limited(1024));
+        assert!(matches!(result, Err(Error::CollectionTooLong { .. })));
+        let result = deserialize_with_options::<[u32; 3]>(&data, limited(2));
+        assert!(matches!(result, Err(Error::CollectionTooLong { .. })));
+    }
+
+    #[test]
+    fn collections_within_the_limit_are_accepted() {
+        let value = (vec![1u64, 2, 3], vec![1u8, 2, 3], "abc".to_string());
+        let data = serialize_to_byte_vec(&value).unwrap();
+        let result = deserialize_with_options::<(Vec<u64>, Vec<u8>, String)>(&data, limited(3));
+        assert_eq!(result.unwrap(), value);
+        let result = deserialize_with_options::<(Vec<u64>, Vec<u8>, String)>(&data, limited(2));
+        assert!(matches!(
+            result,
+            Err(Error::CollectionTooLong {
+                length: 3,
+                limit: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn leb128_edge_cases() {
+        for value in [0, 1, 63, 64, 127, 128, u32::MAX as u64, u64::MAX] {
+            roundtrip(Leb128(value));
+            assert_eq!(
+                serialize_to_byte_vec(&Leb128(value)).unwrap(),
+                serialize_to_byte_vec(&VarInt(value)).unwrap()
+            );
+        }
+        for value in [0, -1, 63, 64, -64, -65, i64::MIN, i64::MAX] {
+            roundtrip(Leb128(value));
+        }
+        assert_eq!(serialize_to_byte_vec(&Leb128(-1i64)).unwrap(), vec![0x7F]);
+        assert_eq!(
+            serialize_to_byte_vec(&Leb128(64i64)).unwrap(),
+            vec![0xC0, 0x00]
+        );
+        assert_eq!(serialize_to_byte_vec(&VarInt(-1i64)).unwrap(), vec![0x01]);
+
+        let too_large = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02];
+        assert!(deserialize::<Leb128<u64>>(&too_large).is_err());
+        assert!(deserialize::<Leb128<i64>>(&too_large).is_err());
+        let data = serialize_to_byte_vec(&Leb128(u32::MAX as u64 + 1)).unwrap();
+        assert!(deserialize::<Leb128<u32>>(&data).is_err());
+        let data = serialize_to_byte_vec(&Leb128(i32::MIN as i64 - 1)).unwrap();
+        assert!(deserialize::<Leb128<i32>>(&data).is_err());
+    }
+
+    #[test]
+    fn small_var_ints_are_compact() {
+        assert_eq!(serialized_size(&VarInt(0u64)).unwrap(), 1);
+        assert_eq!(serialized_size(&VarInt(127u64)).unwrap(), 1);
+        assert_eq!(serialized_size(&VarInt(128u64)).unwrap(), 2);
+        assert_eq!(serialized_size(&VarInt(-64i64)).unwrap(), 1);
+        assert_eq!(serialized_size(&VarInt(8191i64)).unwrap(), 2);
+        assert_eq!(serialized_size(&VarInt(u64::MAX)).unwrap(), 10);
+        assert_eq!(serialized_size(&42u64).unwrap(), 8);
+    }
+
+    #[test]
+    fn large_vec_of_strings_is_read_with_and_without_length_prefix() {
+        let value: Vec<String> = (0..100_000).map(|i| format!("item{i}")).collect();
+        let data = serialize_to_byte_vec(&value).unwrap();
+        assert_eq!(deserialize::<Vec<String>>(&data).unwrap(), value);
+
+        let mut context = SerializationContext::new(Vec::new());
+        serialize_unsized_iterator(&mut value.iter(), &mut context).unwrap();
+        let data = context.into_output();
+        assert_eq!(deserialize::<Vec<String>>(&data).unwrap(), value);
+    }
+
+    #[test]
+    fn crafted_vec_length_fails_on_the_missing_items() {
+        let mut data = Vec::new();
+        data.write_var_i32(i32::MAX);
+        data.extend_from_slice(&serialize_to_byte_vec(&"one".to_string()).unwrap());
+        assert!(matches!(
+            deserialize::<Vec<String>>(&data),
+            Err(Error::InputEndedUnexpectedly { .. })
+        ));
+        assert!(matches!(
+            deserialize_owned::<Vec<String>, _>(OwnedInput::new(data)),
+            Err(Error::InputEndedUnexpectedly { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_inputs_fail_without_panicking() {
+        let mut data = Vec::new();
+        data.write_u64(u64::MAX);
+        data.write_u32(u32::MAX);
+        assert!(matches!(
+            deserialize::<Duration>(&data),
+            Err(Error::DeserializationFailure(_))
+        ));
+
+        let mut data = Vec::new();
+        data.write_var_i32(i32::MIN);
+        assert!(matches!(
+            deserialize::<DeduplicatedString>(&data),
+            Err(Error::InvalidStringId(_))
+        ));
+
+        let mut data = Vec::new();
+        data.write_var_i32(-2);
+        assert!(matches!(
+            deserialize::<String>(&data),
+            Err(Error::InputEndedUnexpectedly { .. })
+        ));
+        assert!(matches!(
+            deserialize_owned::<String, _>(OwnedInput::new(data.clone())),
+            Err(Error::InputEndedUnexpectedly { .. })
+        ));
+        let mut input = SliceInput::new(&data);
+        input.read_u8().unwrap();
+        assert!(input.read_bytes(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn deserializing_zero_as_non_zero_fails() {
+        assert!(matches!(
+            deserialize::<NonZeroU8>(&[0]),
+            Err(Error::InvalidNonZero)
+        ));
+        assert!(matches!(
+            deserialize::<NonZeroI64>(&serialize_to_byte_vec(&0i64).unwrap()),
+            Err(Error::InvalidNonZero)
+        ));
+        assert!(matches!(
+            deserialize::<NonZeroU128>(&serialize_to_byte_vec(&0u128).unwrap()),
+            Err(Error::InvalidNonZero)
+        ));
+    }
+
+    /// Custom collection codec preallocating with the size returned by [deserialize_iterator]
+    #[derive(Debug, PartialEq)]
+    struct Preallocated(Vec<u32>);
+
+    impl BinaryDeserializer for Preallocated {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+            let (items, size) = deserialize_iterator::<u32>(context);
+            let mut result = Vec::with_capacity(size.unwrap_or_default());
+            for item in items {
+                result.push(item?);
+            }
+            Ok(Preallocated(result))
+        }
+    }
+
+    #[test]
+    fn deserialize_iterator_returns_the_known_size() {
+        let value: Vec<u32> = (0..100).collect();
+        let data = serialize_to_byte_vec(&value).unwrap();
+        let mut context = DeserializationContext::new(&data);
+        let (items, size) = deserialize_iterator::<u32>(&mut context);
+        assert_eq!(size, Some(100));
+        assert_eq!(items.collect::<crate::Result<Vec<_>>>().unwrap(), value);
+
+        let result = deserialize::<Preallocated>(&data).unwrap();
+        assert_eq!(result, Preallocated(value));
+        assert!(result.0.capacity() >= 100);
+    }
+
+    #[test]
+    fn deserialize_iterator_returns_no_size_for_unknown_sized_streams() {
+        let value: Vec<u32> = (0..100).collect();
+        let mut context = SerializationContext::new(Vec::new());
+        serialize_unsized_iterator(&mut value.iter(), &mut context).unwrap();
+        let data = context.into_output();
+        let mut context = DeserializationContext::new(&data);
+        let (items, size) = deserialize_iterator::<u32>(&mut context);
+        assert_eq!(size, None);
+        assert_eq!(items.collect::<crate::Result<Vec<_>>>().unwrap(), value);
+
+        assert_eq!(
+            deserialize::<Preallocated>(&data).unwrap(),
+            Preallocated(value)
+        );
+    }
+
+    /// Iterator claiming to have exactly one item, no matter how many it really has
+    struct WrongSizeHint<I>(I);
+
+    impl<I: Iterator> Iterator for WrongSizeHint<I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.0.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (1, Some(1))
+        }
+    }
+
+    fn sorted_maps() -> Options {
+        Options {
+            sort_maps: true,
+            ..Default::default()
+        }
+    }
+
+    fn sample_map() -> HashMap<String, u64> {
+        (0..100).map(|i| (format!("key{i}"), i)).collect()
+    }
+
+    #[test]
+    fn sorted_hash_map_serialization_is_reproducible() {
+        let expected = serialize_with_options(&sample_map(), Vec::new(), sorted_maps()).unwrap();
+        for _ in 0..10 {
+            let data = serialize_with_options(&sample_map(), Vec::new(), sorted_maps()).unwrap();
+            assert_eq!(data, expected);
+        }
+        assert_eq!(
+            deserialize::<HashMap<String, u64>>(&expected).unwrap(),
+            sample_map()
+        );
+    }
+
+    #[test]
+    fn sorted_hash_set_serialization_is_reproducible() {
+        let sample = || (0..100u64).collect::<HashSet<_>>();
+        let expected = serialize_with_options(&sample(), Vec::new(), sorted_maps()).unwrap();
+        for _ in 0..10 {
+            let data = serialize_with_options(&sample(), Vec::new(), sorted_maps()).unwrap();
+            assert_eq!(data, expected);
+        }
+        assert_eq!(deserialize::<HashSet<u64>>(&expected).unwrap(), sample());
+    }
+
+    #[test]
+    fn unsorted_hash_map_serialization_may_differ() {
+        let all = (0..10)
+            .map(|_| serialize_to_byte_vec(&sample_map()).unwrap())
+            .collect::<BTreeSet<_>>();
+        assert!(all.len() > 1);
+        for data in all {
+            assert_eq!(
+                deserialize::<HashMap<String, u64>>(&data).unwrap(),
+                sample_map()
+            );
+        }
+    }
+
+    #[test]
+    fn canonical_nan_serializes_all_nans_the_same_way() {
+        let canonical_nan = Options {
+            canonical_nan: true,
+            ..Default::default()
+        };
+        let a = f64::from_bits(0x7ff8_0000_0000_0001);
+        let b = f64::from_bits(0xfff0_0000_dead_beef);
+        assert!(a.is_nan() && b.is_nan());
+
+        let serialize_a =
+            |options: Options| serialize_with_options(&a, Vec::new(), options).unwrap();
+        let serialize_b =
+            |options: Options| serialize_with_options(&b, Vec::new(), options).unwrap();
+        assert_ne!(
+            serialize_a(Options::default()),
+            serialize_b(Options::default())
+        );
+        assert_eq!(
+            serialize_a(canonical_nan.clone()),
+            serialize_b(canonical_nan.clone())
+        );
+        assert!(deserialize::<f64>(&serialize_a(canonical_nan.clone()))
+            .unwrap()
+            .is_nan());
+
+        let floats = vec![
+            f32::from_bits(0x7fc0_0001),
+            1.5,
+            f32::from_bits(0xffff_ffff),
+        ];
+        let data = serialize_with_options(&floats, Vec::new(), canonical_nan.clone()).unwrap();
+        let expected = [f32::NAN, 1.5, -f32::NAN]
+            .iter()
+            .map(|value| serialize_with_options(value, Vec::new(), canonical_nan.clone()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(data[1..], expected.concat());
+        assert_ne!(data, serialize_to_byte_vec(&floats).unwrap());
+    }
+
+    fn roundtrip_little_endian<
+        T: BinarySerializer + BinaryDeserializer + Debug + Clone + PartialEq,
+    >(
+        value: T,
+    ) {
+        let little_endian = Options {
+            little_endian: true,
+            ..Default::default()
+        };
+        let data = serialize_with_options(&value, Vec::new(), little_endian.clone()).unwrap();
+        let result = deserialize_with_options::<T>(&data, little_endian).unwrap();
+        assert_eq!(value, result);
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip_numbers_little_endian(a: u16, b: i32, c: u64, d: i128, e: f32, f: f64) {
+            roundtrip_little_endian(a);
+            roundtrip_little_endian(b);
+            roundtrip_little_endian(c);
+            roundtrip_little_endian(d);
+            roundtrip_little_endian((a, b, c, Some(d)));
+            let little_endian = Options {
+                little_endian: true,
+                ..Default::default()
+            };
+            let data = serialize_with_options(&(e, f), Vec::new(), little_endian.clone()).unwrap();
+            let (e2, f2) = deserialize_with_options::<(f32, f64)>(&data, little_endian).unwrap();
+            prop_assert_eq!(e2.to_bits(), e.to_bits());
+            prop_assert_eq!(f2.to_bits(), f.to_bits());
+        }
+
+        #[test]
+        fn roundtrip_numeric_vecs_little_endian(a: Vec<u32>, b: Vec<i64>, c: Vec<u128>) {
+            roundtrip_little_endian(a);
+            roundtrip_little_endian(b);
+            roundtrip_little_endian(c);
+        }
+    }
+
+    #[test]
+    fn little_endian_reverses_the_byte_order_of_numbers() {
+        let little_endian = Options {
+            little_endian: true,
+            ..Default::default()
+        };
+        let value = 0x0102_0304u32;
+        assert_eq!(serialize_to_byte_vec(&value).unwrap(), [1, 2, 3, 4]);
+        assert_eq!(
+            serialize_with_options(&value, Vec::new(), little_endian.clone()).unwrap(),
+            [4, 3, 2, 1]
+        );
+
+        let values = vec![value, 0x0506_0708];
+        let data = serialize_with_options(&values, Vec::new(), little_endian.clone()).unwrap();
+        assert_eq!(data, [4, 4, 3, 2, 1, 8, 7, 6, 5]);
+        assert_eq!(
+            deserialize_with_options::<Vec<u32>>(&data, little_endian.clone()).unwrap(),
+            values
+        );
+        assert_eq!(
+            deserialize_with_options::<f64>(&1.5f64.to_le_bytes(), little_endian).unwrap(),
+            1.5
+        );
+    }
+
+    #[test]
+    fn btree_map_is_serialized_in_ascending_key_order() {
+        let ascending = (0..100)
+            .map(|i| (format!("key{i:03}"), i))
+            .collect::<Vec<_>>();
+        let forward = ascending.iter().cloned().collect::<BTreeMap<_, _>>();
+        let backward = ascending.iter().rev().cloned().collect::<BTreeMap<_, _>>();
+
+        let data = serialize_to_byte_vec(&forward).unwrap();
+        assert_eq!(data, serialize_to_byte_vec(&backward).unwrap());
+        assert_eq!(data, serialize_to_byte_vec(&ascending).unwrap());
+        assert_eq!(
+            deserialize::<BTreeMap<String, u64>>(&data).unwrap(),
+            forward
+        );
+    }
+
+    #[test]
+    fn custom_hasher_maps_are_compatible_with_default_hasher_maps() {
+        let map = sample_map()
+            .into_iter()
+            .collect::<HashMap<String, u64, ahash::RandomState>>();
+        let data = serialize_with_options(&map, Vec::new(), sorted_maps()).unwrap();
+        assert_eq!(
+            data,
+            serialize_with_options(&sample_map(), Vec::new(), sorted_maps()).unwrap()
+        );
+        assert_eq!(
+            deserialize::<HashMap<String, u64, ahash::RandomState>>(&data).unwrap(),
+            map
+        );
+        assert_eq!(
+            deserialize::<HashMap<String, u64>>(&data).unwrap(),
+            sample_map()
+        );
+    }
+
+    #[test]
+    fn custom_hasher_sets_are_compatible_with_default_hasher_sets() {
+        let set = (0..100u64).collect::<HashSet<_, ahash::RandomState>>();
+        let data = serialize_to_byte_vec(&set).unwrap();
+        assert_eq!(
+            deserialize::<HashSet<u64, ahash::RandomState>>(&data).unwrap(),
+            set
+        );
+        assert_eq!(
+            deserialize::<HashSet<u64>>(&data).unwrap(),
+            (0..100u64).collect::<HashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn deserializing_lone_surrogate_char_fails() {
+        for surrogate in [0xD800u16, 0xDBFF, 0xDC00, 0xDFFF] {
+            let data = serialize_to_byte_vec(&surrogate).unwrap();
+            let result = deserialize::<char>(&data);
+            assert!(
+                matches!(result, Err(Error::FailedToDecodeCharacter(code)) if code == surrogate)
+            );
+        }
+    }
+
+    #[test]
+    fn full_unicode_chars_support_characters_outside_of_the_bmp() {
+        let full_unicode_chars = Options {
+            full_unicode_chars: true,
+            ..Default::default()
+        };
+        let emoji = '🦀';
+        let data = serialize_with_options(&emoji, Vec::new(), full_unicode_chars.clone()).unwrap();
+        assert_eq!(data, 0x1F980u32.to_be_bytes());
+        assert_eq!(
+            deserialize_with_options::<char>(&data, full_unicode_chars.clone()).unwrap(),
+            emoji
+        );
+        let text = vec!['a', 'é', '中', emoji];
+        let data = serialize_with_options(&text, Vec::new(), full_unicode_chars.clone()).unwrap();
+        assert_eq!(
+            deserialize_with_options::<Vec<char>>(&data, full_unicode_chars.clone()).unwrap(),
+            text
+        );
+
+        assert!(matches!(
+            serialize_to_byte_vec(&emoji),
+            Err(Error::UnsupportedCharacter('🦀'))
+        ));
+        assert_eq!(serialize_to_byte_vec(&'é').unwrap(), [0x00, 0xE9]);
+        assert!(matches!(
+            deserialize_with_options::<char>(&0xD800u32.to_be_bytes(), full_unicode_chars),
+            Err(Error::DeserializationFailure(_))
+        ));
+    }
+
+    /// Recursive type without a derived codec, only nested through its `Box`
+    #[derive(Debug, Clone, PartialEq)]
+    struct Chain(Option<Box<Chain>>);
+
+    impl Chain {
+        fn with_length(length: usize) -> Self {
+            (0..length).fold(Chain(None), |chain, _| Chain(Some(Box::new(chain))))
+        }
+    }
+
+    impl BinarySerializer for Chain {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            context: &mut SerializationContext<Output>,
+        ) -> crate::Result<()> {
+            self.0.serialize(context)
+        }
+    }
+
+    impl BinaryDeserializer for Chain {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+            Ok(Chain(Option::<Box<Chain>>::deserialize(context)?))
+        }
+    }
+
+    #[test]
+    fn max_depth_limits_the_nesting_of_boxes() {
+        let max_depth = Options {
+            max_depth: Some(100),
+            ..Default::default()
+        };
+        let at_limit = Chain::with_length(100);
+        let data = serialize_with_options(&at_limit, Vec::new(), max_depth.clone()).unwrap();
+        assert_eq!(
+            deserialize_with_options::<Chain>(&data, max_depth.clone()).unwrap(),
+            at_limit
+        );
+
+        let too_deep = Chain::with_length(101);
+        assert!(matches!(
+            serialize_with_options(&too_deep, Vec::new(), max_depth.clone()),
+            Err(Error::RecursionLimitExceeded { limit: 100 })
+        ));
+        let data = serialize_to_byte_vec(&too_deep).unwrap();
+        assert!(matches!(
+            deserialize_with_options::<Chain>(&data, max_depth),
+            Err(Error::RecursionLimitExceeded { limit: 100 })
+        ));
+    }
+
+    /// Recursive type nested through a [Deduplicated] value, which is serialized separately
+    #[derive(Debug, Clone, PartialEq)]
+    struct DeduplicatedChain(Option<Box<Deduplicated<DeduplicatedChain>>>);
+
+    impl DeduplicatedChain {
+        fn with_length(length: usize) -> Self {
+            (0..length).fold(DeduplicatedChain(None), |chain, _| {
+                DeduplicatedChain(Some(Box::new(Deduplicated(chain))))
+            })
+        }
+    }
+
+    impl BinarySerializer for DeduplicatedChain {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            context: &mut SerializationContext<Output>,
+        ) -> crate::Result<()> {
+            self.0.serialize(context)
+        }
+    }
+
+    impl BinaryDeserializer for DeduplicatedChain {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+            Ok(DeduplicatedChain(Option::<
+                Box<Deduplicated<DeduplicatedChain>>,
+            >::deserialize(context)?))
+        }
+    }
+
+    #[test]
+    fn max_depth_counts_the_nesting_of_deduplicated_values() {
+        let max_depth = Options {
+            max_depth: Some(16),
+            ..Default::default()
+        };
+        let shallow = DeduplicatedChain::with_length(4);
+        let data = serialize_to_byte_vec(&shallow).unwrap();
+        assert_eq!(
+            deserialize_with_options::<DeduplicatedChain>(&data, max_depth.clone()).unwrap(),
+            shallow
+        );
+
+        let too_deep = DeduplicatedChain::with_length(32);
+        assert!(matches!(
+            serialize_with_options(&too_deep, Vec::new(), max_depth.clone()),
+            Err(Error::RecursionLimitExceeded { limit: 16 })
+        ));
+        let data = serialize_to_byte_vec(&too_deep).unwrap();
+        assert!(matches!(
+            deserialize_with_options::<DeduplicatedChain>(&data, max_depth),
+            Err(Error::RecursionLimitExceeded { limit: 16 })
+        ));
+    }
+
+    #[test]
+    fn max_depth_stops_rc_cycles() {
+        #[derive(Debug)]
+        struct Node(RefCell<Option<Rc<Node>>>);
+
+        impl BinarySerializer for Node {
+            fn serialize<Output: BinaryOutput>(
+                &self,
+                context: &mut SerializationContext<Output>,
+            ) -> crate::Result<()> {
+                self.0.borrow().serialize(context)
+            }
+        }
+
+        let node = Rc::new(Node(RefCell::new(None)));
+        *node.0.borrow_mut() = Some(node.clone());
+        let options = Options {
+            max_depth: Some(1000),
+            ..Default::default()
+        };
+        assert!(matches!(
+            serialize_with_options(&node, Vec::new(), options),
+            Err(Error::RecursionLimitExceeded { limit: 1000 })
+        ));
+        node.0.borrow_mut().take();
+    }
+
+    #[test]
+    fn max_depth_stops_rc_cycles_through_deduplicated_values() {
+        #[derive(Debug)]
+        struct Node(RefCell<Option<Rc<Deduplicated<Node>>>>);
+
+        impl BinarySerializer for Node {
+            fn serialize<Output: BinaryOutput>(
+                &self,
+                context: &mut SerializationContext<Output>,
+            ) -> crate::Result<()> {
+                self.0.borrow().serialize(context)
+            }
+        }
+
+        let node = Rc::new(Deduplicated(Node(RefCell::new(None))));
+        *node.0 .0.borrow_mut() = Some(node.clone());
+        let options = Options {
+            max_depth: Some(100),
+            ..Default::default()
+        };
+        assert!(matches!(
+            serialize_with_options(&node, Vec::new(), options),
+            Err(Error::RecursionLimitExceeded { limit: 100 })
+        ));
+        node.0 .0.borrow_mut().take();
+    }
+
+    #[test]
+    fn deduplicated_value_is_written_once() {
+        let value = (42u64, "some longer string value".to_string());
+        let once = serialized_size(&vec![Deduplicated(value.clone())]).unwrap();
+        let three_times = serialized_size(&vec![Deduplicated(value.clone()); 3]).unwrap();
+        assert_eq!(three_times - once, 2);
+    }
+
+    #[test]
+    fn deduplicated_values_of_different_types_are_not_mixed() {
+        let value = (Deduplicated(1u32), Deduplicated(1i32), Deduplicated(1u32));
+        let data = serialize_to_byte_vec(&value).unwrap();
+        let result =
+            deserialize::<(Deduplicated<u32>, Deduplicated<i32>, Deduplicated<u32>)>(&data);
+        assert_eq!(result.unwrap(), value);
+    }
+
+    #[test]
+    fn deserialize_from_reader_leaves_trailing_data_unread() {
+        let mut data = serialize_to_byte_vec(&("hello".to_string(), 42u64)).unwrap();
+        data.extend_from_slice(&[1, 2, 3]);
+
+        let mut reader = Cursor::new(data);
+        let result = deserialize_from_reader::<(String, u64), _>(&mut reader).unwrap();
+        assert_eq!(result, ("hello".to_string(), 42));
+
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn deserialize_from_owned_input() {
+        let value = ("hello".to_string(), vec![1u32, 2, 3], Some(42u64));
+        let input = OwnedInput::new(serialize_to_byte_vec(&value).unwrap());
+
+        let result = std::thread::spawn(move || {
+            deserialize_owned::<(String, Vec<u32>, Option<u64>), _>(input).unwrap()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn roundtrip_through_buf_mut_and_chained_buf() {
+        let value = (
+            "hello".to_string(),
+            vec![1u32, 2, 3, 4, 5, 6, 7, 8],
+            Some(42u64),
+        );
+        let output = serialize(&value, BufMutOutput::new(BytesMut::new())).unwrap();
+        let data = output.finish().unwrap().freeze();
+        assert_eq!(data, serialize_to_byte_vec(&value).unwrap());
+
+        // Splitting in the middle of the vector's items, so some reads span both segments
+        let first = data.slice(..13);
+        let second = data.slice(13..);
+        let mut buf = first.chain(second).chain(Bytes::from_static(&[7]));
+        let result =
+            deserialize_owned::<(String, Vec<u32>, Option<u64>), _>(BufInput::new(&mut buf))
+                .unwrap();
+        assert_eq!(result, value);
+        assert_eq!(buf.remaining(), 1);
+    }
+
+    #[test]
+    fn buf_mut_output_fails_when_out_of_space() {
+        let mut storage = [0u8; 4];
+        let output = serialize(
+            &"hello world".to_string(),
+            BufMutOutput::new(&mut storage[..]),
+        )
+        .unwrap();
+        assert!(matches!(output.finish(), Err(Error::WriteFailure(_))));
+    }
+
+    #[test]
+    fn deserialize_from_truncated_buf_input_fails() {
+        let data = serialize_to_bytes(&"hello world".to_string()).unwrap();
+        let input = BufInput::new(data.slice(..3).chain(data.slice(3..5)));
+        assert!(matches!(
+            deserialize_owned::<String, _>(input),
+            Err(Error::InputEndedUnexpectedly { offset: 5 })
+        ));
+    }
+
+    #[test]
+    fn deserialize_from_truncated_owned_input_fails() {
+        let data = serialize_to_byte_vec(&"hello world".to_string()).unwrap();
+        let result = deserialize_owned::<String, _>(OwnedInput::new(data[..5].to_vec()));
+        assert!(matches!(
+            result,
+            Err(Error::InputEndedUnexpectedly { offset: 5 })
+        ));
+    }
+
+    #[test]
+    fn deserialize_from_truncated_reader_fails() {
+        let data = serialize_to_byte_vec(&"hello world".to_string()).unwrap();
+        let result = deserialize_from_reader::<String, _>(Cursor::new(&data[..5]));
+        assert!(matches!(
+            result,
+            Err(Error::InputEndedUnexpectedly { offset: 5 })
+        ));
+    }
+
+    fn assert_serialized_size<T: BinarySerializer>(value: T) {
+        assert_eq!(
+            serialized_size(&value).unwrap(),
+            serialize_to_byte_vec(&value).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn serialized_size_matches_serialized_length() {
+        assert_serialized_size(42u8);
+        assert_serialized_size(-42i64);
+        assert_serialized_size(1.5f64);
+        assert_serialized_size("hello world".to_string());
+        assert_serialized_size(vec![1u8, 2, 3]);
+        assert_serialized_size(vec!["a".to_string(), "bb".to_string()]);
+        assert_serialized_size((1u32, Some("x".to_string()), false));
+    }
+
+    #[test]
+    fn serialized_size_counts_deduplicated_strings() {
+        let strings = vec![
+            DeduplicatedString("hello world".to_string()),
+            DeduplicatedString("hello world".to_string()),
+            DeduplicatedString("something else".to_string()),
+            DeduplicatedString("hello world".to_string()),
+        ];
+        let plain = strings.iter().map(|s| s.0.clone()).collect::<Vec<_>>();
+
+        let size = serialized_size(&strings).unwrap();
+        assert_eq!(size, serialize_to_byte_vec(&strings).unwrap().len());
+        assert!(size < serialized_size(&plain).unwrap());
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("broken pipe"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn streaming_and_buffered_serialization_use_the_same_options() {
+        let options = Options {
+            sort_maps: true,
+            canonical_nan: true,
+            little_endian: true,
+            full_unicode_chars: true,
+            max_depth: Some(16),
+            ..Default::default()
+        };
+        let value = (
+            HashMap::from([(1u32, 'a'), (2, '🦀'), (3, 'é')]),
+            vec![f64::NAN, -1.5],
+            Some(Box::new(0x0102_0304u32)),
+        );
+
+        let buffered = serialize_with_options(&value, Vec::new(), options.clone()).unwrap();
+        let mut streamed = Vec::new();
+        serialize_into_writer_with_options(&value, &mut streamed, options.clone()).unwrap();
+        assert_eq!(streamed, buffered);
+
+        let result: (HashMap<u32, char>, Vec<f64>, Option<Box<u32>>) =
+            deserialize_from_reader_with_options(Cursor::new(&streamed), options.clone()).unwrap();
+        assert_eq!(result.0, value.0);
+        assert!(result.1[0].is_nan());
+        assert_eq!(result.2, value.2);
+
+        let too_deep = Options {
+            max_depth: Some(0),
+            ..options
+        };
+        assert!(matches!(
+            serialize_into_writer_with_options(&value, &mut Vec::new(), too_deep.clone()),
+            Err(Error::RecursionLimitExceeded { limit: 0 })
+        ));
+        assert!(matches!(
+            deserialize_from_reader_with_options::<
+                (HashMap<u32, char>, Vec<f64>, Option<Box<u32>>),
+                _,
+            >(Cursor::new(&streamed), too_deep),
+            Err(Error::RecursionLimitExceeded { limit: 0 })
+        ));
+    }
+
+    #[test]
+    fn serialize_into_failing_writer_fails() {
+        let result = serialize_into_writer(&"hello world".to_string(), FailingWriter);
+        assert!(matches!(result, Err(Error::WriteFailure(_))));
+    }
+
+    #[test]
     fn reference_tracking_serializes_cycles() {
         let a = Rc::new(RefCell::new(Node {
             label: "a".to_string(),
@@ -387,9 +2466,9 @@ mod tests {
         let root = Root { node: a.clone() };
 
         let data = serialize_to_bytes(&root).unwrap();
-        let _result = deserialize::<Root>(&data).unwrap();
+        let result = deserialize::<Root>(&data).unwrap();
 
-        let a = root.node;
+        let a = result.node;
         let b = a.borrow().next.clone().unwrap();
         let c = b.borrow().next.clone().unwrap();
 
@@ -400,4 +2479,42 @@ mod tests {
         let d = c.borrow().next.clone().unwrap();
         assert!(std::ptr::eq(d.borrow().deref(), a.borrow().deref()));
     }
+
+    #[test]
+    fn register_ref_supports_doubly_linked_lists() {
+        let list = DoublyLinkedList::from_values(&[1, 2, 3, 4]);
+
+        let data = serialize_to_bytes(&list).unwrap();
+        let result = deserialize::<DoublyLinkedList>(&data).unwrap();
+
+        let nodes = result.nodes();
+        assert_eq!(
+            nodes
+                .iter()
+                .map(|node| node.borrow().value)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert!(nodes[0].borrow().prev.is_none());
+        for pair in nodes.windows(2) {
+            let prev = pair[1].borrow().prev.as_ref().unwrap().upgrade().unwrap();
+            assert!(Rc::ptr_eq(&prev, &pair[0]));
+        }
+    }
+
+    #[test]
+    fn try_read_rc_fails_on_type_mismatch() {
+        let node = Rc::new(RefCell::new(Node {
+            label: "a".to_string(),
+            next: None,
+        }));
+        let data = serialize_to_bytes(&vec![Root { node: node.clone() }, Root { node }]).unwrap();
+        let mut context = DeserializationContext::new(&data);
+        assert_eq!(context.read_var_i32().unwrap(), 2);
+        let _ = Root::deserialize(&mut context).unwrap();
+        assert!(matches!(
+            context.try_read_rc::<String>(),
+            Err(Error::DeserializationFailure(_))
+        ));
+    }
 }
@@ -0,0 +1,81 @@
+/// Options affecting how values are serialized and deserialized.
+///
+/// The default options produce the format compatible with the Scala version of desert.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Maximum number of elements a deserialized collection can have, `None` means no limit.
+    ///
+    /// Collections are prefixed by their length, so when reading untrusted input this allows rejecting
+    /// a crafted length before iterating over or allocating for that many elements.
+    pub max_collection_length: Option<usize>,
+
+    /// Serialize the entries of `HashMap` and `HashSet` values sorted by their serialized keys.
+    ///
+    /// The iteration order of hash based collections differs between runs, so by default serializing the
+    /// same value can produce different bytes. Enabling this makes the output reproducible, which is
+    /// needed for example when the serialized form is hashed for content-addressed storage, at the cost
+    /// of serializing every key twice. The serialized format is the same, so it does not affect
+    /// deserialization.
+    pub sort_maps: bool,
+
+    /// Serialize every `f32` and `f64` NaN as the same quiet NaN bit pattern.
+    ///
+    /// There are many NaN bit patterns, so by default values that are logically equal can produce different
+    /// bytes, which breaks content-addressed storage. Deserialization is not affected, the canonical NaN is
+    /// read back like any other.
+    pub canonical_nan: bool,
+
+    /// Keep the deduplication and reference tracking state between the values of a stream read by
+    /// [crate::deserialize_stream_with_options].
+    ///
+    /// By default every value of a stream is read with a fresh state, matching values that were
+    /// serialized independently. Enable it when the values were written with a single shared
+    /// [crate::SerializationContext].
+    pub shared_stream_state: bool,
+
+    /// Allocate a new buffer for every chunk of an evolved type instead of reusing the buffers of the
+    /// already finished ones.
+    ///
+    /// Reusing the buffers does not change the serialized output. This is not part of the public API, it
+    /// only exists for the benchmarks and tests comparing the two.
+    #[doc(hidden)]
+    pub disable_buffer_pool: bool,
+
+    /// Fail with [crate::Error::NewerVersion] when reading an evolvable type written by a newer version
+    /// of it than the one being deserialized.
+    ///
+    /// By default such data is read as long as the fields known by the older version can be found, and
+    /// everything added since then is skipped.
+    pub reject_newer_versions: bool,
+
+    /// Identify the constructors of derived enums by their name, written as a deduplicated string, instead
+    /// of their index.
+    ///
+    /// This costs a few bytes per value, but the data can still be read after the variants are reordered,
+    /// even without pinning their ids with `#[desert(id = N)]`. The format is different, so data written with
+    /// this enabled can only be read with it enabled too. `#[desert(compact)]` enums always use their ids.
+    pub named_constructors: bool,
+
+    /// Write and read the fixed size numeric types in little-endian byte order instead of big-endian.
+    ///
+    /// This is for interop with systems that only handle little-endian data. Variable length integers,
+    /// lengths and the evolution headers are not affected. The default big-endian order is the one the Scala
+    /// version of desert uses, data written with this enabled can only be read with it enabled too.
+    pub little_endian: bool,
+
+    /// Serialize `char` values as their 4 byte Unicode code point instead of a single UTF-16 code unit.
+    ///
+    /// By default characters outside of the Basic Multilingual Plane, like most emoji, fail to serialize
+    /// with [crate::Error::UnsupportedCharacter], because the Scala version of desert stores a JVM `Char`
+    /// in 2 bytes. Enabling this supports every character, but the data can only be read with it enabled too.
+    pub full_unicode_chars: bool,
+
+    /// Maximum nesting depth of the serialized or deserialized values, `None` means no limit.
+    ///
+    /// Every field and constructor of an evolvable type and every `Box`, `Rc` and `Arc` is one level
+    /// deeper than the value containing it. Exceeding the limit fails with
+    /// [crate::Error::RecursionLimitExceeded] instead of overflowing the stack, which protects against
+    /// cycles of `Rc` or `Arc` values that are not serialized as [crate::SharedRef], and against
+    /// maliciously deep input.
+    pub max_depth: Option<usize>,
+}
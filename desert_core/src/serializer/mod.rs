@@ -1,18 +1,42 @@
 mod tuples;
 
-use bytes::Bytes;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList};
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use bytes::{Bytes, BytesMut};
 use castaway::cast;
-use std::any::Any;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList};
-use std::marker::PhantomData;
-use std::rc::Rc;
-use std::sync::Arc;
-use std::time::Duration;
+use core::any::Any;
+use core::cell::{Cell, RefCell};
+use core::cmp::Reverse;
+use core::marker::PhantomData;
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+    NonZeroU32, NonZeroU64, NonZeroU8, Saturating, Wrapping,
+};
+use core::ops::{Range, RangeInclusive};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::binary_output::BinaryOutput;
 use crate::error::Result;
+use crate::options::Options;
 use crate::state::State;
-use crate::{DeduplicatedString, Error, RefId, StringId};
+use crate::{
+    Deduplicated, DeduplicatedString, Error, Leb128, RefId, SharedRef, StringId, ValueId, VarInt,
+    DEFAULT_CAPACITY,
+};
 
 pub trait BinarySerializer {
     fn serialize<Output: BinaryOutput>(
@@ -23,19 +47,59 @@ pub trait BinarySerializer {
 
 pub struct SerializationContext<Output: BinaryOutput> {
     output: Output,
+    options: Options,
     state: State,
     buffer_stack: Vec<Vec<u8>>, // TODO: remove it once AdtSerializer does not need it anymore
+    buffer_pool: Vec<Vec<u8>>,
+    depth: usize,
 }
 
 impl<Output: BinaryOutput> SerializationContext<Output> {
     pub fn new(output: Output) -> Self {
         Self {
             output,
+            options: Options::default(),
             state: State::default(),
             buffer_stack: Vec::new(),
+            buffer_pool: Vec::new(),
+            depth: 0,
         }
     }
 
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Continues counting the nesting levels from `depth`, for contexts serializing a part of a value
+    /// separately, so [Options::max_depth] applies to the whole value
+    pub(crate) fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Runs `f` one nesting level deeper, failing with [Error::RecursionLimitExceeded] if that exceeds
+    /// [Options::max_depth]. Hand-written codecs of recursive types can use it to be protected by the limit.
+    pub fn nested<R>(&mut self, f: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        if let Some(limit) = self.options.max_depth {
+            if self.depth >= limit {
+                return Err(Error::RecursionLimitExceeded { limit });
+            }
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
     pub fn into_output(self) -> Output {
         self.output
     }
@@ -44,8 +108,17 @@ impl<Output: BinaryOutput> SerializationContext<Output> {
         &mut self.state
     }
 
+    /// Registers a dictionary of strings that [DeduplicatedString] values can refer to without writing
+    /// them first.
+    ///
+    /// Must be called before serializing anything, and the deserializer has to register the same
+    /// dictionary with [DeserializationContext::register_string_dictionary](crate::DeserializationContext::register_string_dictionary).
+    pub fn register_string_dictionary<S: AsRef<str>>(&mut self, dictionary: &[S]) -> Result<()> {
+        self.state.preload_strings(dictionary)
+    }
+
     pub fn store_ref_or_object(&mut self, value: &impl Any) -> Result<bool> {
-        match self.state_mut().store_ref(value) {
+        match self.state_mut().store_ref(value)? {
             StoreRefResult::RefAlreadyStored { id } => {
                 self.write_var_u32(id.0);
                 Ok(false)
@@ -57,6 +130,17 @@ impl<Output: BinaryOutput> SerializationContext<Output> {
         }
     }
 
+    /// Writes a reference to the value shared by `value` if it has already been written, or a marker that
+    /// it is new, in which case it returns `true` and the value has to be serialized right after.
+    ///
+    /// Values are identified by their address, so every `Rc` pointing to the same value is written as a
+    /// reference to the first one. Together with [DeserializationContext::register_ref](crate::DeserializationContext::register_ref)
+    /// and [DeserializationContext::try_read_rc](crate::DeserializationContext::try_read_rc) this is enough
+    /// to implement codecs for cyclic data structures.
+    pub fn store_rc_or_object<T: Any>(&mut self, value: &Rc<T>) -> Result<bool> {
+        self.store_ref_or_object(&**value)
+    }
+
     pub fn push_buffer(&mut self, buffer: Vec<u8>) {
         self.buffer_stack.push(buffer);
     }
@@ -64,6 +148,36 @@ impl<Output: BinaryOutput> SerializationContext<Output> {
     pub fn pop_buffer(&mut self) -> Vec<u8> {
         self.buffer_stack.pop().unwrap()
     }
+
+    /// Takes an empty buffer from the pool of released buffers, or allocates a new one if there is none.
+    pub(crate) fn take_pooled_buffer(&mut self) -> Vec<u8> {
+        self.buffer_pool
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(DEFAULT_CAPACITY))
+    }
+
+    /// Returns a buffer that is no longer used to the pool, so nested and subsequent values can reuse it.
+    pub(crate) fn release_pooled_buffer(&mut self, mut buffer: Vec<u8>) {
+        if !self.options.disable_buffer_pool {
+            buffer.clear();
+            self.buffer_pool.push(buffer);
+        }
+    }
+}
+
+// Writes the fixed size numeric types in the byte order selected by Options::little_endian
+macro_rules! write_with_byte_order {
+    ($($name:ident: $t:ty),*) => {
+        $(
+            fn $name(&mut self, value: $t) {
+                if self.options.little_endian {
+                    self.write_bytes(&value.to_le_bytes())
+                } else {
+                    self.write_bytes(&value.to_be_bytes())
+                }
+            }
+        )*
+    };
 }
 
 impl<Output: BinaryOutput> BinaryOutput for SerializationContext<Output> {
@@ -80,11 +194,29 @@ impl<Output: BinaryOutput> BinaryOutput for SerializationContext<Output> {
             None => self.output.write_bytes(bytes),
         }
     }
+
+    write_with_byte_order!(
+        write_u16: u16,
+        write_i16: i16,
+        write_u32: u32,
+        write_i32: i32,
+        write_u64: u64,
+        write_i64: i64,
+        write_u128: u128,
+        write_i128: i128,
+        write_f32: f32,
+        write_f64: f64
+    );
 }
 
 pub enum StoreStringResult {
     StringAlreadyStored { id: StringId },
-    StringIsNew { new_id: StringId, value: String },
+    StringIsNew { new_id: StringId },
+}
+
+pub enum StoreValueResult {
+    ValueAlreadyStored { id: ValueId },
+    ValueIsNew { new_id: ValueId },
 }
 
 pub enum StoreRefResult {
@@ -197,12 +329,22 @@ impl BinarySerializer for i128 {
     }
 }
 
+/// The quiet NaN all `f32` NaNs are written as with [Options::canonical_nan](crate::Options::canonical_nan)
+const CANONICAL_NAN_F32: u32 = 0x7fc0_0000;
+
+/// The quiet NaN all `f64` NaNs are written as with [Options::canonical_nan](crate::Options::canonical_nan)
+const CANONICAL_NAN_F64: u64 = 0x7ff8_0000_0000_0000;
+
 impl BinarySerializer for f32 {
     fn serialize<Output: BinaryOutput>(
         &self,
         context: &mut SerializationContext<Output>,
     ) -> Result<()> {
-        context.write_f32(*self);
+        if context.options.canonical_nan && self.is_nan() {
+            context.write_f32(f32::from_bits(CANONICAL_NAN_F32));
+        } else {
+            context.write_f32(*self);
+        }
         Ok(())
     }
 }
@@ -212,7 +354,11 @@ impl BinarySerializer for f64 {
         &self,
         context: &mut SerializationContext<Output>,
     ) -> Result<()> {
-        context.write_f64(*self);
+        if context.options.canonical_nan && self.is_nan() {
+            context.write_f64(f64::from_bits(CANONICAL_NAN_F64));
+        } else {
+            context.write_f64(*self);
+        }
         Ok(())
     }
 }
@@ -241,6 +387,10 @@ impl BinarySerializer for char {
         &self,
         context: &mut SerializationContext<Output>,
     ) -> Result<()> {
+        if context.options.full_unicode_chars {
+            context.write_u32(*self as u32);
+            return Ok(());
+        }
         let mut buf = [0; 2];
         let result = self.encode_utf16(&mut buf);
         if result.len() == 1 {
@@ -276,18 +426,181 @@ impl BinarySerializer for String {
     }
 }
 
+#[cfg(feature = "std")]
+impl BinarySerializer for Path {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        match self.to_str() {
+            Some(str) => str.serialize(context),
+            None => Err(Error::NonUtf8Path(self.to_string_lossy().into_owned())),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl BinarySerializer for PathBuf {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.as_path().serialize(context)
+    }
+}
+
+#[cfg(all(feature = "std", unix))]
+impl BinarySerializer for OsStr {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        use std::os::unix::ffi::OsStrExt;
+
+        context.write_u8(crate::OS_STRING_BYTES_TAG);
+        self.as_bytes().serialize(context)
+    }
+}
+
+#[cfg(all(feature = "std", windows))]
+impl BinarySerializer for OsStr {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        use std::os::windows::ffi::OsStrExt;
+
+        context.write_u8(crate::OS_STRING_WIDE_TAG);
+        let wide: Vec<u16> = self.encode_wide().collect();
+        wide.serialize(context)
+    }
+}
+
+#[cfg(all(feature = "std", any(unix, windows)))]
+impl BinarySerializer for OsString {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.as_os_str().serialize(context)
+    }
+}
+
 impl BinarySerializer for DeduplicatedString {
     fn serialize<Output: BinaryOutput>(
         &self,
         context: &mut SerializationContext<Output>,
     ) -> Result<()> {
-        match context.state_mut().store_string(self.0.clone()) {
+        match context.state_mut().store_string(&self.0)? {
             StoreStringResult::StringAlreadyStored { id } => {
                 context.write_var_i32(-id.0);
                 Ok(())
             }
-            StoreStringResult::StringIsNew { value, .. } => value.serialize(context),
+            StoreStringResult::StringIsNew { .. } => self.0.serialize(context),
+        }
+    }
+}
+
+impl BinarySerializer for Leb128<u32> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_leb128_u64(self.0 as u64);
+        Ok(())
+    }
+}
+
+impl BinarySerializer for Leb128<i32> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_leb128_i64(self.0 as i64);
+        Ok(())
+    }
+}
+
+impl BinarySerializer for Leb128<u64> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_leb128_u64(self.0);
+        Ok(())
+    }
+}
+
+impl BinarySerializer for Leb128<i64> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_leb128_i64(self.0);
+        Ok(())
+    }
+}
+
+impl BinarySerializer for VarInt<u32> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_var_u32(self.0);
+        Ok(())
+    }
+}
+
+impl BinarySerializer for VarInt<i32> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_var_i32(self.0);
+        Ok(())
+    }
+}
+
+impl BinarySerializer for VarInt<u64> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_var_u64(self.0);
+        Ok(())
+    }
+}
+
+impl BinarySerializer for VarInt<i64> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_var_i64(self.0);
+        Ok(())
+    }
+}
+
+impl<T: BinarySerializer + 'static> BinarySerializer for Deduplicated<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        let mut inner = SerializationContext::new(Vec::new())
+            .with_options(context.options.clone())
+            .with_depth(context.depth);
+        self.0.serialize(&mut inner)?;
+        let serialized = inner.into_output();
+        match context.state_mut().store_value::<T>(&serialized)? {
+            StoreValueResult::ValueAlreadyStored { id } => {
+                context.write_var_i32(-id.0);
+            }
+            StoreValueResult::ValueIsNew { .. } => {
+                context.write_var_i32(serialized.len().try_into()?);
+                context.write_bytes(&serialized);
+            }
         }
+        Ok(())
     }
 }
 
@@ -302,6 +615,111 @@ impl BinarySerializer for Duration {
     }
 }
 
+#[cfg(feature = "std")]
+impl BinarySerializer for crate::RelativeInstant {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.0.serialize(context)
+    }
+}
+
+/// Serialized as the [Duration] elapsed since the Unix epoch, so times before it are rejected
+#[cfg(feature = "std")]
+impl BinarySerializer for SystemTime {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.serialize(context),
+            Err(err) => Err(Error::SystemTimeBeforeUnixEpoch(err.duration())),
+        }
+    }
+}
+
+impl BinarySerializer for Ipv4Addr {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_bytes(&self.octets());
+        Ok(())
+    }
+}
+
+impl BinarySerializer for Ipv6Addr {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_bytes(&self.octets());
+        Ok(())
+    }
+}
+
+/// Written as a tag byte (0 for v4, 1 for v6) followed by the 4 or 16 octets of the address, which is the
+/// same as the standalone [Ipv4Addr] and [Ipv6Addr] encodings prefixed by the tag.
+impl BinarySerializer for IpAddr {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        match self {
+            IpAddr::V4(addr) => {
+                context.write_u8(0);
+                addr.serialize(context)
+            }
+            IpAddr::V6(addr) => {
+                context.write_u8(1);
+                addr.serialize(context)
+            }
+        }
+    }
+}
+
+impl BinarySerializer for SocketAddrV4 {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.ip().serialize(context)?;
+        context.write_u16(self.port());
+        Ok(())
+    }
+}
+
+/// Only the address and the port are serialized, the flow info and scope id are not preserved.
+impl BinarySerializer for SocketAddrV6 {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.ip().serialize(context)?;
+        context.write_u16(self.port());
+        Ok(())
+    }
+}
+
+impl BinarySerializer for SocketAddr {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        match self {
+            SocketAddr::V4(addr) => {
+                context.write_u8(0);
+                addr.serialize(context)
+            }
+            SocketAddr::V6(addr) => {
+                context.write_u8(1);
+                addr.serialize(context)
+            }
+        }
+    }
+}
+
 impl<T: BinarySerializer> BinarySerializer for Option<T> {
     fn serialize<Output: BinaryOutput>(
         &self,
@@ -320,7 +738,7 @@ impl<T: BinarySerializer> BinarySerializer for Option<T> {
     }
 }
 
-impl<R: BinarySerializer, E: BinarySerializer> BinarySerializer for std::result::Result<R, E> {
+impl<R: BinarySerializer, E: BinarySerializer> BinarySerializer for core::result::Result<R, E> {
     fn serialize<Output: BinaryOutput>(
         &self,
         context: &mut SerializationContext<Output>,
@@ -349,14 +767,32 @@ impl BinarySerializer for Bytes {
     }
 }
 
-impl<T: BinarySerializer + 'static> BinarySerializer for [T] {
+impl BinarySerializer for BytesMut {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_var_u32(self.len().try_into()?);
+        context.write_bytes(self);
+        Ok(())
+    }
+}
+
+impl<T: BinarySerializer> BinarySerializer for [T] {
     fn serialize<Output: BinaryOutput>(
         &self,
         context: &mut SerializationContext<Output>,
     ) -> Result<()> {
-        if let Ok(byte_slice) = cast!(self, &[u8]) {
+        // Checking the element type through an empty Vec, so slices of non-'static types (like references
+        // to trait objects) can be serialized too
+        let empty: Vec<T> = Vec::new();
+        if cast!(empty, Vec<u8>).is_ok() {
+            let byte_slice =
+                unsafe { core::slice::from_raw_parts(self.as_ptr() as *const u8, self.len()) };
             context.write_var_u32(self.len().try_into()?); // NOTE: this is inconsistent with the generic case, but this way it is compatible with the Scala version's Chunk serializer
             context.write_bytes(byte_slice);
+        } else if let Some(result) = try_serialize_numeric_slice(self, context) {
+            result?;
         } else {
             context.write_var_i32(self.len().try_into()?);
             for elem in self {
@@ -375,6 +811,8 @@ impl<T: BinarySerializer, const L: usize> BinarySerializer for [T; L] {
         if let Ok(byte_slice) = cast!(self, &[u8; L]) {
             context.write_var_u32(self.len().try_into()?); // NOTE: this is inconsistent with the generic case, but this way it is compatible with the Scala version's Chunk serializer
             context.write_bytes(byte_slice);
+        } else if let Some(result) = try_serialize_numeric_slice(self.as_slice(), context) {
+            result?;
         } else {
             context.write_var_i32(self.len().try_into()?);
             for elem in self {
@@ -394,18 +832,25 @@ impl<T: BinarySerializer> BinarySerializer for Vec<T> {
             context.write_var_u32(byte_vec.len().try_into()?); // NOTE: this is inconsistent with the generic case, but this way it is compatible with the Scala version's Chunk serializer
             context.write_bytes(byte_vec);
             Ok(())
+        } else if let Some(result) = try_serialize_numeric_slice(self, context) {
+            result
         } else {
             serialize_iterator(&mut self.iter(), context)
         }
     }
 }
 
-impl<T: BinarySerializer> BinarySerializer for HashSet<T> {
+#[cfg(feature = "std")]
+impl<T: BinarySerializer, S> BinarySerializer for HashSet<T, S> {
     fn serialize<Output: BinaryOutput>(
         &self,
         context: &mut SerializationContext<Output>,
     ) -> Result<()> {
-        serialize_iterator(&mut self.iter(), context)
+        if context.options.sort_maps {
+            serialize_sorted_by_key(self.iter(), |item| *item, context)
+        } else {
+            serialize_iterator(&mut self.iter(), context)
+        }
     }
 }
 
@@ -418,15 +863,25 @@ impl<T: BinarySerializer> BinarySerializer for BTreeSet<T> {
     }
 }
 
-impl<K: BinarySerializer, V: BinarySerializer> BinarySerializer for HashMap<K, V> {
+#[cfg(feature = "std")]
+impl<K: BinarySerializer, V: BinarySerializer, S> BinarySerializer for HashMap<K, V, S> {
     fn serialize<Output: BinaryOutput>(
         &self,
         context: &mut SerializationContext<Output>,
     ) -> Result<()> {
-        serialize_iterator(&mut self.iter(), context)
+        if context.options.sort_maps {
+            serialize_sorted_by_key(self.iter(), |(key, _)| *key, context)
+        } else {
+            serialize_iterator(&mut self.iter(), context)
+        }
     }
 }
 
+/// The entries are written in ascending order of their keys, so the serialized form of a map only depends on
+/// its contents, not on the order the entries were inserted in.
+///
+/// Like `HashMap`, this is the format of desert-scala's `Map` codec: the length followed by the entries as
+/// `(key, value)` tuples. Compatibility with desert-scala's `SortedMap` is not covered by a golden test.
 impl<K: BinarySerializer, V: BinarySerializer> BinarySerializer for BTreeMap<K, V> {
     fn serialize<Output: BinaryOutput>(
         &self,
@@ -445,12 +900,21 @@ impl<T: BinarySerializer> BinarySerializer for LinkedList<T> {
     }
 }
 
-impl<T: BinarySerializer> BinarySerializer for Box<T> {
+impl<T: BinarySerializer> BinarySerializer for BinaryHeap<T> {
     fn serialize<Output: BinaryOutput>(
         &self,
         context: &mut SerializationContext<Output>,
     ) -> Result<()> {
-        (**self).serialize(context)
+        serialize_iterator(&mut self.iter(), context)
+    }
+}
+
+impl<T: BinarySerializer + ?Sized> BinarySerializer for Box<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.nested(|context| (**self).serialize(context))
     }
 }
 
@@ -459,11 +923,146 @@ impl<T: BinarySerializer + ?Sized> BinarySerializer for Rc<T> {
         &self,
         context: &mut SerializationContext<Output>,
     ) -> Result<()> {
-        (**self).serialize(context)
+        context.nested(|context| (**self).serialize(context))
+    }
+}
+
+impl<T: BinarySerializer + ?Sized> BinarySerializer for Arc<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.nested(|context| (**self).serialize(context))
+    }
+}
+
+impl<T: BinarySerializer + 'static> BinarySerializer for SharedRef<Rc<T>> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        serialize_shared_ref(&*self.0, context)
+    }
+}
+
+impl<T: BinarySerializer + 'static> BinarySerializer for SharedRef<Arc<T>> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        serialize_shared_ref(&*self.0, context)
     }
 }
 
-impl<T: BinarySerializer> BinarySerializer for Arc<T> {
+fn serialize_shared_ref<T: BinarySerializer + 'static, Output: BinaryOutput>(
+    value: &T,
+    context: &mut SerializationContext<Output>,
+) -> Result<()> {
+    if context.store_ref_or_object(value)? {
+        value.serialize(context)?;
+    }
+    Ok(())
+}
+
+impl<T: BinarySerializer> BinarySerializer for Wrapping<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.0.serialize(context)
+    }
+}
+
+impl<T: BinarySerializer> BinarySerializer for Saturating<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.0.serialize(context)
+    }
+}
+
+impl<T: BinarySerializer> BinarySerializer for Reverse<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.0.serialize(context)
+    }
+}
+
+// Non-zero integers are serialized as the underlying integer, so they are interchangeable with it
+// as long as the value is not zero
+macro_rules! non_zero_serializer {
+    ($($t:ty),*) => {
+        $(
+            impl BinarySerializer for $t {
+                fn serialize<Output: BinaryOutput>(
+                    &self,
+                    context: &mut SerializationContext<Output>,
+                ) -> Result<()> {
+                    self.get().serialize(context)
+                }
+            }
+        )*
+    };
+}
+
+non_zero_serializer!(
+    NonZeroU8,
+    NonZeroI8,
+    NonZeroU16,
+    NonZeroI16,
+    NonZeroU32,
+    NonZeroI32,
+    NonZeroU64,
+    NonZeroI64,
+    NonZeroU128,
+    NonZeroI128
+);
+
+/// Serialized as `start` followed by `end`, without the evolution header written for tuples, so it is
+/// not interchangeable with `(T, T)`.
+impl<T: BinarySerializer> BinarySerializer for Range<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.start.serialize(context)?;
+        self.end.serialize(context)
+    }
+}
+
+/// Serialized as `start` followed by `end`, in the same format as [Range].
+impl<T: BinarySerializer> BinarySerializer for RangeInclusive<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.start().serialize(context)?;
+        self.end().serialize(context)
+    }
+}
+
+impl<T: BinarySerializer + Copy> BinarySerializer for Cell<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.get().serialize(context)
+    }
+}
+
+impl<T: BinarySerializer + ?Sized> BinarySerializer for RefCell<T> {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        self.borrow().serialize(context)
+    }
+}
+
+impl<T: BinarySerializer + ToOwned + ?Sized> BinarySerializer for Cow<'_, T> {
     fn serialize<Output: BinaryOutput>(
         &self,
         context: &mut SerializationContext<Output>,
@@ -509,14 +1108,105 @@ pub fn serialize_iterator<I: Iterator<Item = T>, T: BinarySerializer, Output: Bi
                 item.serialize(context)?;
             }
         }
-        _ => {
-            context.write_var_i32(-1);
-            for item in iter {
-                context.write_u8(1);
-                item.serialize(context)?;
-            }
-            context.write_u8(0);
-        }
+        _ => serialize_unsized_iterator(iter, context)?,
+    }
+    Ok(())
+}
+
+/// Serializes the items in the unknown size format of [serialize_iterator], without relying on the
+/// iterator's `size_hint`.
+///
+/// Each item is prefixed by a tag byte and the sequence is terminated by a zero byte, so it can be used for
+/// generators and other sources whose `size_hint` may be incorrect. The result can be read back the same
+/// way as any other serialized collection.
+pub fn serialize_unsized_iterator<
+    I: Iterator<Item = T>,
+    T: BinarySerializer,
+    Output: BinaryOutput,
+>(
+    iter: &mut I,
+    context: &mut SerializationContext<Output>,
+) -> Result<()> {
+    context.write_var_i32(-1);
+    for item in iter {
+        context.write_u8(1);
+        item.serialize(context)?;
+    }
+    context.write_u8(0);
+    Ok(())
+}
+
+/// Serializes the items in the same format as [serialize_iterator], ordered by the serialized form of
+/// their keys.
+///
+/// Keys are serialized in isolation to get the sort order, the items themselves are written afterward
+/// with the original context, so deduplication and reference tracking work the same as unsorted.
+#[cfg(feature = "std")]
+fn serialize_sorted_by_key<'a, I, T, K, Output>(
+    iter: I,
+    key: impl Fn(&T) -> &'a K,
+    context: &mut SerializationContext<Output>,
+) -> Result<()>
+where
+    I: Iterator<Item = T>,
+    T: BinarySerializer,
+    K: BinarySerializer + 'a,
+    Output: BinaryOutput,
+{
+    let mut items = Vec::new();
+    for item in iter {
+        let mut inner = SerializationContext::new(Vec::new())
+            .with_options(context.options.clone())
+            .with_depth(context.depth);
+        key(&item).serialize(&mut inner)?;
+        items.push((inner.into_output(), item));
+    }
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    serialize_iterator(&mut items.into_iter().map(|(_, item)| item), context)
+}
+
+/// Serializes slices of fixed size numeric types with a single `write_bytes` call, producing the same bytes
+/// as serializing the elements one by one. Returns `None` if `T` is not one of these types.
+fn try_serialize_numeric_slice<T, Output: BinaryOutput>(
+    slice: &[T],
+    context: &mut SerializationContext<Output>,
+) -> Option<Result<()>> {
+    macro_rules! try_types {
+        ($($t:ty),*) => {
+            $(
+                let empty: Vec<T> = Vec::new();
+                if cast!(empty, Vec<$t>).is_ok() {
+                    // T is known to be $t here
+                    let items =
+                        unsafe { core::slice::from_raw_parts(slice.as_ptr() as *const $t, slice.len()) };
+                    let to_bytes = if context.options.little_endian {
+                        <$t>::to_le_bytes
+                    } else {
+                        <$t>::to_be_bytes
+                    };
+                    return Some(serialize_numeric_bytes(items, to_bytes, context));
+                }
+            )*
+        };
+    }
+    try_types!(u16, i16, u32, i32, u64, i64, u128, i128);
+    if !context.options.canonical_nan {
+        // Otherwise the NaNs have to be replaced one by one by the element serializers
+        try_types!(f32, f64);
+    }
+    None
+}
+
+fn serialize_numeric_bytes<E: Copy, const N: usize, Output: BinaryOutput>(
+    items: &[E],
+    to_bytes: fn(E) -> [u8; N],
+    context: &mut SerializationContext<Output>,
+) -> Result<()> {
+    context.write_var_i32(items.len().try_into()?);
+    let mut bytes = Vec::with_capacity(items.len() * N);
+    for item in items {
+        bytes.extend_from_slice(&to_bytes(*item));
     }
+    context.write_bytes(&bytes);
     Ok(())
 }
@@ -151,3 +151,334 @@ impl<
         Ok(())
     }
 }
+
+impl<
+        T1: BinarySerializer,
+        T2: BinarySerializer,
+        T3: BinarySerializer,
+        T4: BinarySerializer,
+        T5: BinarySerializer,
+        T6: BinarySerializer,
+        T7: BinarySerializer,
+        T8: BinarySerializer,
+        T9: BinarySerializer,
+    > BinarySerializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9)
+{
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> crate::Result<()> {
+        context.write_u8(0);
+        self.0.serialize(context)?;
+        self.1.serialize(context)?;
+        self.2.serialize(context)?;
+        self.3.serialize(context)?;
+        self.4.serialize(context)?;
+        self.5.serialize(context)?;
+        self.6.serialize(context)?;
+        self.7.serialize(context)?;
+        self.8.serialize(context)?;
+        Ok(())
+    }
+}
+
+impl<
+        T1: BinarySerializer,
+        T2: BinarySerializer,
+        T3: BinarySerializer,
+        T4: BinarySerializer,
+        T5: BinarySerializer,
+        T6: BinarySerializer,
+        T7: BinarySerializer,
+        T8: BinarySerializer,
+        T9: BinarySerializer,
+        T10: BinarySerializer,
+    > BinarySerializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10)
+{
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> crate::Result<()> {
+        context.write_u8(0);
+        self.0.serialize(context)?;
+        self.1.serialize(context)?;
+        self.2.serialize(context)?;
+        self.3.serialize(context)?;
+        self.4.serialize(context)?;
+        self.5.serialize(context)?;
+        self.6.serialize(context)?;
+        self.7.serialize(context)?;
+        self.8.serialize(context)?;
+        self.9.serialize(context)?;
+        Ok(())
+    }
+}
+
+impl<
+        T1: BinarySerializer,
+        T2: BinarySerializer,
+        T3: BinarySerializer,
+        T4: BinarySerializer,
+        T5: BinarySerializer,
+        T6: BinarySerializer,
+        T7: BinarySerializer,
+        T8: BinarySerializer,
+        T9: BinarySerializer,
+        T10: BinarySerializer,
+        T11: BinarySerializer,
+    > BinarySerializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11)
+{
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> crate::Result<()> {
+        context.write_u8(0);
+        self.0.serialize(context)?;
+        self.1.serialize(context)?;
+        self.2.serialize(context)?;
+        self.3.serialize(context)?;
+        self.4.serialize(context)?;
+        self.5.serialize(context)?;
+        self.6.serialize(context)?;
+        self.7.serialize(context)?;
+        self.8.serialize(context)?;
+        self.9.serialize(context)?;
+        self.10.serialize(context)?;
+        Ok(())
+    }
+}
+
+impl<
+        T1: BinarySerializer,
+        T2: BinarySerializer,
+        T3: BinarySerializer,
+        T4: BinarySerializer,
+        T5: BinarySerializer,
+        T6: BinarySerializer,
+        T7: BinarySerializer,
+        T8: BinarySerializer,
+        T9: BinarySerializer,
+        T10: BinarySerializer,
+        T11: BinarySerializer,
+        T12: BinarySerializer,
+    > BinarySerializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12)
+{
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> crate::Result<()> {
+        context.write_u8(0);
+        self.0.serialize(context)?;
+        self.1.serialize(context)?;
+        self.2.serialize(context)?;
+        self.3.serialize(context)?;
+        self.4.serialize(context)?;
+        self.5.serialize(context)?;
+        self.6.serialize(context)?;
+        self.7.serialize(context)?;
+        self.8.serialize(context)?;
+        self.9.serialize(context)?;
+        self.10.serialize(context)?;
+        self.11.serialize(context)?;
+        Ok(())
+    }
+}
+
+impl<
+        T1: BinarySerializer,
+        T2: BinarySerializer,
+        T3: BinarySerializer,
+        T4: BinarySerializer,
+        T5: BinarySerializer,
+        T6: BinarySerializer,
+        T7: BinarySerializer,
+        T8: BinarySerializer,
+        T9: BinarySerializer,
+        T10: BinarySerializer,
+        T11: BinarySerializer,
+        T12: BinarySerializer,
+        T13: BinarySerializer,
+    > BinarySerializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13)
+{
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> crate::Result<()> {
+        context.write_u8(0);
+        self.0.serialize(context)?;
+        self.1.serialize(context)?;
+        self.2.serialize(context)?;
+        self.3.serialize(context)?;
+        self.4.serialize(context)?;
+        self.5.serialize(context)?;
+        self.6.serialize(context)?;
+        self.7.serialize(context)?;
+        self.8.serialize(context)?;
+        self.9.serialize(context)?;
+        self.10.serialize(context)?;
+        self.11.serialize(context)?;
+        self.12.serialize(context)?;
+        Ok(())
+    }
+}
+
+impl<
+        T1: BinarySerializer,
+        T2: BinarySerializer,
+        T3: BinarySerializer,
+        T4: BinarySerializer,
+        T5: BinarySerializer,
+        T6: BinarySerializer,
+        T7: BinarySerializer,
+        T8: BinarySerializer,
+        T9: BinarySerializer,
+        T10: BinarySerializer,
+        T11: BinarySerializer,
+        T12: BinarySerializer,
+        T13: BinarySerializer,
+        T14: BinarySerializer,
+    > BinarySerializer for (T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14)
+{
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> crate::Result<()> {
+        context.write_u8(0);
+        self.0.serialize(context)?;
+        self.1.serialize(context)?;
+        self.2.serialize(context)?;
+        self.3.serialize(context)?;
+        self.4.serialize(context)?;
+        self.5.serialize(context)?;
+        self.6.serialize(context)?;
+        self.7.serialize(context)?;
+        self.8.serialize(context)?;
+        self.9.serialize(context)?;
+        self.10.serialize(context)?;
+        self.11.serialize(context)?;
+        self.12.serialize(context)?;
+        self.13.serialize(context)?;
+        Ok(())
+    }
+}
+
+impl<
+        T1: BinarySerializer,
+        T2: BinarySerializer,
+        T3: BinarySerializer,
+        T4: BinarySerializer,
+        T5: BinarySerializer,
+        T6: BinarySerializer,
+        T7: BinarySerializer,
+        T8: BinarySerializer,
+        T9: BinarySerializer,
+        T10: BinarySerializer,
+        T11: BinarySerializer,
+        T12: BinarySerializer,
+        T13: BinarySerializer,
+        T14: BinarySerializer,
+        T15: BinarySerializer,
+    > BinarySerializer
+    for (
+        T1,
+        T2,
+        T3,
+        T4,
+        T5,
+        T6,
+        T7,
+        T8,
+        T9,
+        T10,
+        T11,
+        T12,
+        T13,
+        T14,
+        T15,
+    )
+{
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> crate::Result<()> {
+        context.write_u8(0);
+        self.0.serialize(context)?;
+        self.1.serialize(context)?;
+        self.2.serialize(context)?;
+        self.3.serialize(context)?;
+        self.4.serialize(context)?;
+        self.5.serialize(context)?;
+        self.6.serialize(context)?;
+        self.7.serialize(context)?;
+        self.8.serialize(context)?;
+        self.9.serialize(context)?;
+        self.10.serialize(context)?;
+        self.11.serialize(context)?;
+        self.12.serialize(context)?;
+        self.13.serialize(context)?;
+        self.14.serialize(context)?;
+        Ok(())
+    }
+}
+
+impl<
+        T1: BinarySerializer,
+        T2: BinarySerializer,
+        T3: BinarySerializer,
+        T4: BinarySerializer,
+        T5: BinarySerializer,
+        T6: BinarySerializer,
+        T7: BinarySerializer,
+        T8: BinarySerializer,
+        T9: BinarySerializer,
+        T10: BinarySerializer,
+        T11: BinarySerializer,
+        T12: BinarySerializer,
+        T13: BinarySerializer,
+        T14: BinarySerializer,
+        T15: BinarySerializer,
+        T16: BinarySerializer,
+    > BinarySerializer
+    for (
+        T1,
+        T2,
+        T3,
+        T4,
+        T5,
+        T6,
+        T7,
+        T8,
+        T9,
+        T10,
+        T11,
+        T12,
+        T13,
+        T14,
+        T15,
+        T16,
+    )
+{
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> crate::Result<()> {
+        context.write_u8(0);
+        self.0.serialize(context)?;
+        self.1.serialize(context)?;
+        self.2.serialize(context)?;
+        self.3.serialize(context)?;
+        self.4.serialize(context)?;
+        self.5.serialize(context)?;
+        self.6.serialize(context)?;
+        self.7.serialize(context)?;
+        self.8.serialize(context)?;
+        self.9.serialize(context)?;
+        self.10.serialize(context)?;
+        self.11.serialize(context)?;
+        self.12.serialize(context)?;
+        self.13.serialize(context)?;
+        self.14.serialize(context)?;
+        self.15.serialize(context)?;
+        Ok(())
+    }
+}
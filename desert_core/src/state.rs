@@ -1,8 +1,11 @@
-use crate::serializer::{StoreRefResult, StoreStringResult};
-use crate::{RefId, StringId};
-use hashbrown::hash_map::Entry;
+use crate::serializer::{StoreRefResult, StoreStringResult, StoreValueResult};
+use crate::{RefId, Result, StringId, ValueId};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::any::{Any, TypeId};
+use hashbrown::hash_map::{Entry, EntryRef};
 use hashbrown::HashMap;
-use std::any::Any;
 
 #[derive(Default)]
 pub struct State {
@@ -12,40 +15,117 @@ pub struct State {
     refs_by_id: HashMap<RefId, *const dyn Any>,
     ids_by_ref: HashMap<*const dyn Any, RefId>,
     last_ref_id: RefId,
+    owned_refs: Vec<Box<dyn Any>>,
+    values_by_id: HashMap<ValueId, Box<dyn Any>>,
+    ids_by_value: HashMap<TypeId, HashMap<Vec<u8>, ValueId>>,
+    last_value_id: ValueId,
 }
 
 impl State {
-    pub fn store_string(&mut self, value: String) -> StoreStringResult {
-        match self.ids_by_string.entry(value) {
-            Entry::Occupied(entry) => StoreStringResult::StringAlreadyStored { id: *entry.get() },
-            Entry::Vacant(entry) => {
-                self.last_string_id.next();
+    /// Looks up the id of a serialized string, assigning a new one if it was not seen yet.
+    ///
+    /// An owned copy of the string is only made when it is new.
+    pub fn store_string(&mut self, value: &str) -> Result<StoreStringResult> {
+        match self.ids_by_string.entry_ref(value) {
+            EntryRef::Occupied(entry) => {
+                Ok(StoreStringResult::StringAlreadyStored { id: *entry.get() })
+            }
+            EntryRef::Vacant(entry) => {
+                self.last_string_id.next()?;
                 let id = self.last_string_id;
-                self.strings_by_id.insert(id, entry.key().clone());
-                let result = StoreStringResult::StringIsNew {
-                    new_id: id,
-                    value: entry.key().clone(),
-                };
                 entry.insert(id);
-                result
+                Ok(StoreStringResult::StringIsNew { new_id: id })
             }
         }
     }
 
-    pub fn store_ref(&mut self, value: &impl Any) -> StoreRefResult {
+    /// Assigns the next id to a deserialized string, so later back-references to it can be resolved.
+    pub fn store_deserialized_string(&mut self, value: String) -> Result<StringId> {
+        self.last_string_id.next()?;
+        let id = self.last_string_id;
+        self.strings_by_id.insert(id, value);
+        Ok(id)
+    }
+
+    pub fn store_ref(&mut self, value: &impl Any) -> Result<StoreRefResult> {
         match self.ids_by_ref.entry(value) {
-            Entry::Occupied(entry) => StoreRefResult::RefAlreadyStored { id: *entry.get() },
+            Entry::Occupied(entry) => Ok(StoreRefResult::RefAlreadyStored { id: *entry.get() }),
             Entry::Vacant(entry) => {
-                self.last_ref_id.next();
+                self.last_ref_id.next()?;
                 let id = self.last_ref_id;
                 self.refs_by_id.insert(id, value);
                 let result = StoreRefResult::RefIsNew { new_id: id, value };
                 entry.insert(id);
-                result
+                Ok(result)
+            }
+        }
+    }
+
+    /// Allocates the next reference id without associating a value with it yet.
+    ///
+    /// Used when deserializing to keep the ids in sync with the serializer, which assigns the id before
+    /// serializing the referenced value.
+    pub fn reserve_ref_id(&mut self) -> Result<RefId> {
+        self.last_ref_id.next()?;
+        Ok(self.last_ref_id)
+    }
+
+    /// Associates a value owned by the state with a reserved reference id.
+    ///
+    /// Unlike [State::store_ref], the value is kept alive until the state is dropped.
+    pub fn store_owned_ref(&mut self, id: RefId, value: Box<dyn Any>) {
+        let ptr: *const dyn Any = &*value;
+        self.owned_refs.push(value);
+        self.refs_by_id.insert(id, ptr);
+        self.ids_by_ref.insert(ptr, id);
+    }
+
+    /// Looks up the id of a value of type `T` by its serialized form, assigning a new one if it was not seen yet.
+    pub fn store_value<T: Any>(&mut self, serialized: &[u8]) -> Result<StoreValueResult> {
+        let ids = self.ids_by_value.entry(TypeId::of::<T>()).or_default();
+        match ids.entry_ref(serialized) {
+            EntryRef::Occupied(entry) => {
+                Ok(StoreValueResult::ValueAlreadyStored { id: *entry.get() })
+            }
+            EntryRef::Vacant(entry) => {
+                self.last_value_id.next()?;
+                let id = self.last_value_id;
+                entry.insert(id);
+                Ok(StoreValueResult::ValueIsNew { new_id: id })
             }
         }
     }
 
+    /// Assigns the next id to a deserialized value, so later back-references to it can be resolved.
+    pub fn store_deserialized_value(&mut self, value: Box<dyn Any>) -> Result<ValueId> {
+        self.last_value_id.next()?;
+        let id = self.last_value_id;
+        self.values_by_id.insert(id, value);
+        Ok(id)
+    }
+
+    /// Assigns ids to a list of strings up front, so even their first occurrence can be written as a
+    /// back-reference.
+    ///
+    /// The ids are assigned in order, skipping strings that already have one, so the serializer and the
+    /// deserializer agree on them as long as both preload the same list.
+    pub fn preload_strings<S: AsRef<str>>(&mut self, dictionary: &[S]) -> Result<()> {
+        for value in dictionary {
+            let value = value.as_ref();
+            if !self.ids_by_string.contains_key(value) {
+                self.last_string_id.next()?;
+                let id = self.last_string_id;
+                self.ids_by_string.insert(value.to_string(), id);
+                self.strings_by_id.insert(id, value.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_value_by_id(&self, id: ValueId) -> Option<&dyn Any> {
+        self.values_by_id.get(&id).map(|value| value.as_ref())
+    }
+
     pub fn get_string_by_id(&self, id: StringId) -> Option<&str> {
         self.strings_by_id.get(&id).map(|s| s.as_str())
     }
@@ -58,3 +138,61 @@ impl State {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::state::State;
+    use crate::{Error, RefId, StringId, ValueId};
+    use test_r::test;
+
+    #[test]
+    fn running_out_of_reference_ids_fails() {
+        let mut state = State {
+            last_ref_id: RefId(u32::MAX - 1),
+            ..Default::default()
+        };
+        assert_eq!(state.reserve_ref_id().unwrap(), RefId(u32::MAX));
+        assert!(matches!(
+            state.reserve_ref_id(),
+            Err(Error::TooManyReferences)
+        ));
+        assert!(matches!(
+            state.store_ref(&1u8),
+            Err(Error::TooManyReferences)
+        ));
+    }
+
+    #[test]
+    fn running_out_of_string_ids_fails() {
+        let mut state = State {
+            last_string_id: StringId(i32::MAX - 1),
+            ..Default::default()
+        };
+        assert!(state.store_string("first").is_ok());
+        assert!(state.store_string("first").is_ok());
+        assert!(matches!(
+            state.store_string("second"),
+            Err(Error::TooManyStrings)
+        ));
+        assert!(matches!(
+            state.store_deserialized_string("second".to_string()),
+            Err(Error::TooManyStrings)
+        ));
+    }
+
+    #[test]
+    fn running_out_of_value_ids_fails() {
+        let mut state = State {
+            last_value_id: ValueId(i32::MAX),
+            ..Default::default()
+        };
+        assert!(matches!(
+            state.store_value::<u8>(&[1]),
+            Err(Error::TooManyValues)
+        ));
+        assert!(matches!(
+            state.store_deserialized_value(Box::new(1u8)),
+            Err(Error::TooManyValues)
+        ));
+    }
+}
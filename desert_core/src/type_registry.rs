@@ -0,0 +1,398 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::any::{type_name, TypeId};
+
+use crate::{
+    BinaryDeserializer, BinaryInput, BinaryOutput, BinarySerializer, DeserializationContext, Error,
+    Result, SerializationContext,
+};
+
+/// Object safe serialization, to be used as a supertrait of traits whose trait objects are serialized
+/// through a [TypeRegistry].
+///
+/// It is implemented for every sized type implementing [BinarySerializer].
+pub trait DynBinarySerializer {
+    /// Serializes the value into a standalone context
+    fn serialize_dyn(&self, context: &mut SerializationContext<Vec<u8>>) -> Result<()>;
+
+    /// The [TypeId] of the concrete type behind the trait object
+    fn dyn_type_id(&self) -> TypeId;
+
+    /// The name of the concrete type behind the trait object, used in error messages
+    fn dyn_type_name(&self) -> &'static str;
+}
+
+impl<T: BinarySerializer + 'static> DynBinarySerializer for T {
+    fn serialize_dyn(&self, context: &mut SerializationContext<Vec<u8>>) -> Result<()> {
+        self.serialize(context)
+    }
+
+    fn dyn_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn dyn_type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+}
+
+type DeserializeFn<R> =
+    Box<dyn Fn(&mut DeserializationContext<'_>) -> Result<Box<R>> + Send + Sync>;
+
+/// Registry of the concrete types that can be behind a `Box<R>` trait object, for example
+/// `TypeRegistry<dyn Shape>`, each identified by a stable string tag.
+///
+/// A value is written as its tag, followed by the length of the concrete value's serialized form and the
+/// serialized bytes. The concrete value is serialized in isolation, so deduplicated strings and references
+/// inside it are not shared with the rest of the stream. Tags must not be changed once data has been
+/// written with them, but types can be renamed freely.
+///
+/// The [dyn_binary_codec](crate::dyn_binary_codec) macro implements the codec traits for the trait objects
+/// using a registry.
+pub struct TypeRegistry<R: ?Sized> {
+    by_tag: BTreeMap<String, DeserializeFn<R>>,
+    by_type: BTreeMap<TypeId, String>,
+}
+
+impl<R: ?Sized + DynBinarySerializer + 'static> TypeRegistry<R> {
+    pub fn new() -> Self {
+        Self {
+            by_tag: BTreeMap::new(),
+            by_type: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the concrete type `T` with the given tag. `into_boxed` converts a deserialized value to
+    /// the trait object, usually it is just `|value| Box::new(value)`.
+    ///
+    /// Panics if the tag or the type has already been registered.
+    pub fn register<T: BinarySerializer + BinaryDeserializer + 'static>(
+        mut self,
+        tag: &str,
+        into_boxed: fn(T) -> Box<R>,
+    ) -> Self {
+        if self.by_tag.contains_key(tag) {
+            panic!("Type tag {tag} is already registered");
+        }
+        if self
+            .by_type
+            .insert(TypeId::of::<T>(), tag.to_string())
+            .is_some()
+        {
+            panic!("Type {} is already registered", type_name::<T>());
+        }
+        self.by_tag.insert(
+            tag.to_string(),
+            Box::new(move |context| Ok(into_boxed(T::deserialize(context)?))),
+        );
+        self
+    }
+
+    /// Writes the tag of the value's concrete type, followed by the value itself
+    pub fn serialize<Output: BinaryOutput>(
+        &self,
+        value: &R,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        let tag = self
+            .by_type
+            .get(&value.dyn_type_id())
+            .ok_or_else(|| Error::UnregisteredType(value.dyn_type_name().to_string()))?;
+        tag.serialize(context)?;
+
+        let mut inner = SerializationContext::new(Vec::new())
+            .with_options(context.options().clone())
+            .with_depth(context.depth());
+        value.serialize_dyn(&mut inner)?;
+        let serialized = inner.into_output();
+        context.write_var_u32(serialized.len().try_into()?);
+        context.write_bytes(&serialized);
+        Ok(())
+    }
+
+    /// Reads a tag and deserializes the value with the concrete type registered for it
+    pub fn deserialize(&self, context: &mut DeserializationContext<'_>) -> Result<Box<R>> {
+        let tag = String::deserialize(context)?;
+        let deserialize = self
+            .by_tag
+            .get(&tag)
+            .ok_or_else(|| Error::UnknownTypeTag(tag.clone()))?;
+
+        let length = context.read_var_u32()? as usize;
+        let options = context.options().clone();
+        let depth = context.depth();
+        let bytes = context.read_bytes(length)?;
+        let mut inner = DeserializationContext::new(bytes)
+            .with_options(options)
+            .with_depth(depth);
+        deserialize(&mut inner)
+    }
+}
+
+impl<R: ?Sized + DynBinarySerializer + 'static> Default for TypeRegistry<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Implements [BinarySerializer] for `dyn Trait` and [BinaryDeserializer] for `Box<dyn Trait>` with a
+/// [TypeRegistry]. Through the generic implementations, boxed and borrowed trait objects and collections of
+/// them can be serialized too, for example both `Vec<Box<dyn Trait>>` and `&[&dyn Trait]`.
+///
+/// The trait must have [DynBinarySerializer] as a supertrait, and the registry expression must evaluate
+/// to a `&TypeRegistry<dyn Trait>`, for example a reference to a static:
+///
+/// ```
+/// use desert_core::{dyn_binary_codec, DynBinarySerializer, TypeRegistry};
+/// use std::sync::LazyLock;
+///
+/// trait Shape: DynBinarySerializer {}
+///
+/// impl Shape for u32 {}
+///
+/// static SHAPES: LazyLock<TypeRegistry<dyn Shape>> =
+///     LazyLock::new(|| TypeRegistry::<dyn Shape>::new().register::<u32>("u32", |value| Box::new(value)));
+///
+/// dyn_binary_codec!(Shape, &*SHAPES);
+/// ```
+#[macro_export]
+macro_rules! dyn_binary_codec {
+    ($trait:path, $registry:expr) => {
+        impl $crate::BinarySerializer for dyn $trait {
+            fn serialize<Output: $crate::BinaryOutput>(
+                &self,
+                context: &mut $crate::SerializationContext<Output>,
+            ) -> $crate::Result<()> {
+                $registry.serialize(self, context)
+            }
+        }
+
+        impl $crate::BinaryDeserializer for Box<dyn $trait> {
+            fn deserialize(
+                context: &mut $crate::DeserializationContext<'_>,
+            ) -> $crate::Result<Self> {
+                $registry.deserialize(context)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::roundtrip;
+    use crate::{
+        deserialize, deserialize_with_options, serialize_to_byte_vec, serialize_with_options,
+        BinaryDeserializer, BinaryInput, BinaryOutput, BinarySerializer, DeserializationContext,
+        DynBinarySerializer, Error, Options, SerializationContext, TypeRegistry,
+    };
+    use std::fmt::Debug;
+    use std::sync::LazyLock;
+    use test_r::test;
+
+    trait Shape: DynBinarySerializer + Debug {
+        fn area(&self) -> f64;
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Circle {
+        radius: f64,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Rectangle {
+        width: f64,
+        height: f64,
+    }
+
+    #[derive(Debug)]
+    struct Triangle;
+
+    /// Shape containing another shape, with a hand-written codec counting each level for `max_depth`
+    #[derive(Debug)]
+    struct Group(Option<Box<dyn Shape>>);
+
+    impl Group {
+        fn with_depth(depth: usize) -> Self {
+            (0..depth).fold(Group(None), |group, _| Group(Some(Box::new(group))))
+        }
+    }
+
+    impl Shape for Circle {
+        fn area(&self) -> f64 {
+            3.0 * self.radius * self.radius
+        }
+    }
+
+    impl Shape for Rectangle {
+        fn area(&self) -> f64 {
+            self.width * self.height
+        }
+    }
+
+    impl Shape for Group {
+        fn area(&self) -> f64 {
+            self.0.as_ref().map(|shape| shape.area()).unwrap_or(0.0)
+        }
+    }
+
+    impl Shape for Triangle {
+        fn area(&self) -> f64 {
+            0.0
+        }
+    }
+
+    impl BinarySerializer for Circle {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            context: &mut SerializationContext<Output>,
+        ) -> crate::Result<()> {
+            context.write_f64(self.radius);
+            Ok(())
+        }
+    }
+
+    impl BinaryDeserializer for Circle {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+            Ok(Circle {
+                radius: context.read_f64()?,
+            })
+        }
+    }
+
+    impl BinarySerializer for Rectangle {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            context: &mut SerializationContext<Output>,
+        ) -> crate::Result<()> {
+            context.write_f64(self.width);
+            context.write_f64(self.height);
+            Ok(())
+        }
+    }
+
+    impl BinaryDeserializer for Rectangle {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+            Ok(Rectangle {
+                width: context.read_f64()?,
+                height: context.read_f64()?,
+            })
+        }
+    }
+
+    impl BinarySerializer for Group {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            context: &mut SerializationContext<Output>,
+        ) -> crate::Result<()> {
+            context.nested(|context| self.0.serialize(context))
+        }
+    }
+
+    impl BinaryDeserializer for Group {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> crate::Result<Self> {
+            context.nested(|context| Ok(Group(Option::<Box<dyn Shape>>::deserialize(context)?)))
+        }
+    }
+
+    impl BinarySerializer for Triangle {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            _context: &mut SerializationContext<Output>,
+        ) -> crate::Result<()> {
+            Ok(())
+        }
+    }
+
+    static SHAPES: LazyLock<TypeRegistry<dyn Shape>> = LazyLock::new(|| {
+        TypeRegistry::<dyn Shape>::new()
+            .register::<Circle>("circle", |circle| Box::new(circle))
+            .register::<Rectangle>("rectangle", |rectangle| Box::new(rectangle))
+            .register::<Group>("group", |group| Box::new(group))
+    });
+
+    dyn_binary_codec!(Shape, &*SHAPES);
+
+    #[test]
+    fn heterogeneous_vec_roundtrip() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Circle { radius: 1.0 }),
+            Box::new(Rectangle {
+                width: 2.0,
+                height: 3.0,
+            }),
+            Box::new(Circle { radius: 2.0 }),
+        ];
+        let data = serialize_to_byte_vec(&shapes).unwrap();
+        let result = deserialize::<Vec<Box<dyn Shape>>>(&data).unwrap();
+        assert_eq!(
+            result.iter().map(|shape| shape.area()).collect::<Vec<_>>(),
+            vec![3.0, 6.0, 12.0]
+        );
+        assert_eq!(format!("{result:?}"), format!("{shapes:?}"));
+    }
+
+    #[test]
+    fn borrowed_slice_is_serialized_like_boxed_vec() {
+        let circle = Circle { radius: 1.0 };
+        let rectangle = Rectangle {
+            width: 2.0,
+            height: 3.0,
+        };
+        let borrowed: &[&dyn Shape] = &[&circle, &rectangle];
+        let boxed: Vec<Box<dyn Shape>> =
+            vec![Box::new(circle.clone()), Box::new(rectangle.clone())];
+
+        let data = serialize_to_byte_vec(&borrowed).unwrap();
+        assert_eq!(data, serialize_to_byte_vec(&boxed).unwrap());
+        let result = deserialize::<Vec<Box<dyn Shape>>>(&data).unwrap();
+        assert_eq!(format!("{result:?}"), format!("{boxed:?}"));
+    }
+
+    #[test]
+    fn concrete_values_keep_their_own_format() {
+        roundtrip(Circle { radius: 4.0 });
+    }
+
+    #[test]
+    fn serializing_unregistered_type_fails() {
+        let shape: Box<dyn Shape> = Box::new(Triangle);
+        assert!(matches!(
+            serialize_to_byte_vec(&shape),
+            Err(Error::UnregisteredType(type_name)) if type_name.ends_with("Triangle")
+        ));
+    }
+
+    #[test]
+    fn max_depth_counts_the_nesting_of_registered_values() {
+        let max_depth = Options {
+            max_depth: Some(16),
+            ..Default::default()
+        };
+        let shallow: Box<dyn Shape> = Box::new(Group::with_depth(4));
+        let data = serialize_to_byte_vec(&shallow).unwrap();
+        let result = deserialize_with_options::<Box<dyn Shape>>(&data, max_depth.clone()).unwrap();
+        assert_eq!(format!("{result:?}"), format!("{shallow:?}"));
+
+        let deep: Box<dyn Shape> = Box::new(Group::with_depth(32));
+        assert!(matches!(
+            serialize_with_options(&deep, Vec::new(), max_depth.clone()),
+            Err(Error::RecursionLimitExceeded { limit: 16 })
+        ));
+        let data = serialize_to_byte_vec(&deep).unwrap();
+        assert!(matches!(
+            deserialize_with_options::<Box<dyn Shape>>(&data, max_depth),
+            Err(Error::RecursionLimitExceeded { limit: 16 })
+        ));
+    }
+
+    #[test]
+    fn deserializing_unknown_tag_fails() {
+        let data = serialize_to_byte_vec(&"hexagon".to_string()).unwrap();
+        assert!(matches!(
+            deserialize::<Box<dyn Shape>>(&data),
+            Err(Error::UnknownTypeTag(tag)) if tag == "hexagon"
+        ));
+    }
+}
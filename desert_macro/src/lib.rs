@@ -1,15 +1,21 @@
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
-use quote::quote;
+use quote::{quote, quote_spanned};
 use std::collections::HashMap;
 use syn::punctuated::Punctuated;
-use syn::{Attribute, Data, DeriveInput, Expr, Fields, Lit, LitStr, Meta, Token, Type};
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Expr, ExprLit, Fields, GenericParam, Generics, Lit, LitInt,
+    LitStr, Meta, MetaNameValue, Token, Type,
+};
 
 fn evolution_steps_from_attributes(
     attrs: &[Attribute],
+    schema: &mut String,
 ) -> (Vec<proc_macro2::TokenStream>, HashMap<String, Expr>) {
     let mut evolution_steps = Vec::new();
     let mut field_defaults = HashMap::new();
+    schema.push('[');
     for attr in attrs {
         if attr.path().is_ident("evolution") {
             let nested = attr
@@ -25,7 +31,7 @@ fn evolution_steps_from_attributes(
                             let args = list
                                 .parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)
                                 .expect("FieldAdded arguments");
-                            if args.len() != 2 {
+                            if args.is_empty() || args.len() > 2 {
                                 panic!("Invalid number of arguments for FieldAdded");
                             }
                             let field_name = match &args[0] {
@@ -38,9 +44,14 @@ fn evolution_steps_from_attributes(
                                                 expr_type(other)
                                 ),
                             };
-                            let field_default = &args[1];
+                            // Without an explicit default value the field's type has to implement Default
+                            let field_default: Expr = match args.get(1) {
+                                Some(field_default) => field_default.clone(),
+                                None => syn::parse_quote! { ::core::default::Default::default() },
+                            };
 
-                            field_defaults.insert(field_name.clone(), field_default.clone());
+                            field_defaults.insert(field_name.clone(), field_default);
+                            schema.push_str(&format!("FieldAdded({field_name});"));
                             evolution_steps.push(quote! {
                                 desert_rust::Evolution::FieldAdded {
                                     name: #field_name.to_string(),
@@ -51,6 +62,7 @@ fn evolution_steps_from_attributes(
                                 list.parse_args().expect("FieldMadeOptional argument");
                             let field_name = field_name_lit.value();
 
+                            schema.push_str(&format!("FieldMadeOptional({field_name});"));
                             evolution_steps.push(quote! {
                                 desert_rust::Evolution::FieldMadeOptional {
                                     name: #field_name.to_string(),
@@ -61,6 +73,7 @@ fn evolution_steps_from_attributes(
                                 list.parse_args().expect("FieldMadeOptional argument");
                             let field_name = field_name_lit.value();
 
+                            schema.push_str(&format!("FieldRemoved({field_name});"));
                             evolution_steps.push(quote! {
                                 desert_rust::Evolution::FieldRemoved {
                                     name: #field_name.to_string(),
@@ -71,6 +84,7 @@ fn evolution_steps_from_attributes(
                                 list.parse_args().expect("FieldMadeOptional argument");
                             let field_name = field_name_lit.value();
 
+                            schema.push_str(&format!("FieldMadeTransient({field_name});"));
                             evolution_steps.push(quote! {
                                 desert_rust::Evolution::FieldMadeTransient {
                                     name: #field_name.to_string(),
@@ -87,12 +101,37 @@ fn evolution_steps_from_attributes(
             }
         }
     }
+    schema.push(']');
     (evolution_steps, field_defaults)
 }
 
-// TODO: attribute to force/disable option field detection for a field (because it's based on names only)
-// TODO: attribute to use different field names (for Scala compatibility)
-#[proc_macro_derive(BinaryCodec, attributes(evolution, transient, sorted_constructors))]
+/// Derives `BinarySerializer` and `BinaryDeserializer` for a struct or enum.
+///
+/// # Type attributes
+///
+/// - `#[desert(no_evolution)]` drops the version byte and the evolution header of the type and its
+///   constructors, saving a byte per value for small types that will never change. It cannot be combined
+///   with evolution steps. **The format is incompatible with the default one**: adding it to or removing
+///   it from a type makes all the data serialized before unreadable.
+/// - `#[desert(compact)]` serializes an enum whose variants have at most one field as a single byte
+///   constructor id followed by that field, the same way as the built-in `Option` and `Result` codecs.
+///   **Compact enums cannot be evolved**, and the format is incompatible with the default one, so the
+///   attribute cannot be added to or removed from a type with existing data either.
+/// - `#[desert(schema_hash)]` generates an inherent `SCHEMA_HASH: u64` constant, a hash of the type's
+///   fields and evolution steps for comparing the schemas of two builds before exchanging data. Its
+///   documentation describes how it is computed.
+///
+/// # Variant attributes
+///
+/// - `#[desert(fallback)]` marks the variant to be deserialized in place of constructors that are unknown
+///   to the current version of the enum, for example ones that have been removed. Its fields get their
+///   default values. The data of an unknown constructor can only be skipped if it was serialized with
+///   at least one evolution step, so the enum must have an `#[evolution(...)]` step, and data written
+///   before the first step was added still fails with `InvalidConstructorId`.
+#[proc_macro_derive(
+    BinaryCodec,
+    attributes(evolution, transient, sorted_constructors, desert)
+)]
 pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).expect("derive input");
 
@@ -100,8 +139,98 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
         .attrs
         .iter()
         .any(|attr| attr.path().is_ident("sorted_constructors"));
-    let (evolution_steps, field_defaults) = evolution_steps_from_attributes(&ast.attrs);
+
+    let mut no_evolution = false;
+    let mut compact = false;
+    let mut builder = false;
+    let mut repr_discriminant = false;
+    let mut tuple = false;
+    let mut use_serde_rename = false;
+    let mut emit_schema_hash = false;
+    for attr in &ast.attrs {
+        if attr.path().is_ident("desert") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("no_evolution") {
+                    no_evolution = true;
+                    Ok(())
+                } else if meta.path.is_ident("compact") {
+                    compact = true;
+                    Ok(())
+                } else if meta.path.is_ident("builder") {
+                    builder = true;
+                    Ok(())
+                } else if meta.path.is_ident("repr_discriminant") {
+                    repr_discriminant = true;
+                    Ok(())
+                } else if meta.path.is_ident("tuple") {
+                    tuple = true;
+                    Ok(())
+                } else if meta.path.is_ident("use_serde_rename") {
+                    use_serde_rename = true;
+                    Ok(())
+                } else if meta.path.is_ident("schema_hash") {
+                    emit_schema_hash = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported desert type attribute"))
+                }
+            })
+            .expect("desert type attributes");
+        }
+    }
+    let name = &ast.ident;
+    if no_evolution && has_evolution_attribute(&ast.attrs) {
+        panic!("{name} cannot have both #[desert(no_evolution)] and #[evolution(...)]");
+    }
+    // #[desert(tuple)] guarantees that a struct is serialized exactly like the tuple of its non-transient
+    // fields, so the two can be read as each other. This is the default version 0 layout, the attribute
+    // only rejects everything that would change it.
+    if tuple {
+        if !matches!(ast.data, Data::Struct(_)) {
+            panic!("#[desert(tuple)] can only be used on structs, {name} is not a struct");
+        }
+        if no_evolution {
+            panic!("{name} cannot have both #[desert(tuple)] and #[desert(no_evolution)]");
+        }
+    }
+    // #[desert(builder)] generates a NameBuilder with setters for the non-transient fields, which is also
+    // used to construct the value when deserializing
+    let builder_impl = if builder {
+        derive_builder(&ast)
+    } else {
+        quote! {}
+    };
+    // #[desert(repr_discriminant)] uses the discriminants of a fieldless enum's variants as their constructor
+    // ids, so they match the values used for C interop
+    let discriminants = if repr_discriminant {
+        repr_discriminants(&ast)
+    } else {
+        HashMap::new()
+    };
+    if compact {
+        if no_evolution {
+            panic!("{name} cannot have both #[desert(compact)] and #[desert(no_evolution)]");
+        }
+        return derive_compact_enum(
+            &ast,
+            use_sorted_constructors,
+            &discriminants,
+            emit_schema_hash,
+        );
+    }
+
+    // A textual description of everything that affects the binary format, hashed into SCHEMA_HASH
+    let mut schema = String::new();
+    if no_evolution {
+        schema.push_str("no_evolution;");
+    }
+
+    let (evolution_steps, field_defaults) =
+        evolution_steps_from_attributes(&ast.attrs, &mut schema);
     let version = evolution_steps.len();
+    if tuple && version > 0 {
+        panic!("{name} cannot have both #[desert(tuple)] and evolution steps");
+    }
     let mut push_evolution_steps = Vec::new();
     for evolution_step in evolution_steps {
         push_evolution_steps.push(quote! {
@@ -109,7 +238,6 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
         });
     }
 
-    let name = &ast.ident;
     let metadata_name = Ident::new(
         &format!("{name}_metadata").to_uppercase(),
         Span::call_site(),
@@ -118,30 +246,56 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
     let mut metadata = Vec::new();
     let mut serialization_commands = Vec::new();
     let mut deserialization_commands = Vec::new();
+    let mut fallback_case: Option<(String, proc_macro2::TokenStream)> = None;
     let is_record;
+    let mut is_tuple_struct = false;
+    let mut is_unit_struct = false;
 
     match ast.data {
         Data::Struct(struct_data) => {
             is_record = true;
-            let mut field_patterns = Vec::new();
-            for field in struct_data.fields.iter() {
-                let field_ident = field.ident.as_ref().unwrap();
-                field_patterns.push(quote! { #field_ident });
+            match &struct_data.fields {
+                Fields::Unnamed(unnamed_fields) => {
+                    is_tuple_struct = true;
+                    let mut field_patterns = Vec::new();
+                    for n in 0..unnamed_fields.unnamed.len() {
+                        let field_ident = Ident::new(&format!("field{}", n), Span::call_site());
+                        field_patterns.push(quote! { #field_ident });
+                    }
+                    serialization_commands.push(quote! {
+                       let #name(#(#field_patterns),*) = self;
+                    });
+                }
+                Fields::Unit => {
+                    is_unit_struct = true;
+                }
+                Fields::Named(_) => {
+                    let mut field_patterns = Vec::new();
+                    for field in struct_data.fields.iter() {
+                        let field_ident = field.ident.as_ref().unwrap();
+                        field_patterns.push(quote! { #field_ident });
+                    }
+                    serialization_commands.push(quote! {
+                       let #name { #(#field_patterns),* } = self;
+                    });
+                }
             }
-            serialization_commands.push(quote! {
-               let #name { #(#field_patterns),* } = self;
-            });
+            schema.push_str("struct");
             derive_field_serialization(
                 field_defaults,
                 &mut serialization_commands,
                 &mut deserialization_commands,
                 &struct_data.fields,
+                &mut schema,
+                builder,
+                use_serde_rename,
             );
         }
         Data::Enum(enum_data) => {
             is_record = false;
 
             let mut cases = Vec::new();
+            schema.push_str("enum{");
 
             let mut variants = enum_data.variants.iter().cloned().collect::<Vec<_>>();
             if use_sorted_constructors {
@@ -149,6 +303,7 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
             }
 
             let mut effective_case_idx = 0;
+            let mut used_case_ids = HashMap::new();
             for variant in variants {
                 let is_transient = variant
                     .attrs
@@ -156,6 +311,71 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
                     .any(|attr| attr.path().is_ident("transient"));
                 let case_name = &variant.ident;
 
+                // #[desert(id = N)] pins the constructor id written to the stream, so the variant can be
+                // decoded even if other variants are inserted before it
+                let mut explicit_case_id = None;
+                let mut is_fallback = false;
+                for attr in &variant.attrs {
+                    if attr.path().is_ident("desert") {
+                        attr.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("id") {
+                                let id: LitInt = meta.value()?.parse()?;
+                                explicit_case_id = Some(id.base10_parse::<u32>()?);
+                                Ok(())
+                            } else if meta.path.is_ident("fallback") {
+                                is_fallback = true;
+                                Ok(())
+                            } else {
+                                Err(meta.error("unsupported desert variant attribute"))
+                            }
+                        })
+                        .expect("desert variant attributes");
+                    }
+                }
+                let explicit_case_id =
+                    case_id_from_discriminant(name, case_name, explicit_case_id, &discriminants);
+                let case_id = explicit_case_id.unwrap_or(effective_case_idx);
+
+                // #[desert(fallback)] marks the variant to be constructed when an unknown constructor id is read,
+                // for example one belonging to a variant that has been removed since the data was written
+                if is_fallback {
+                    if no_evolution {
+                        panic!(
+                            "{case_name} of {name} cannot be a fallback, because {name} has #[desert(no_evolution)]"
+                        );
+                    }
+                    if version == 0 {
+                        panic!(
+                            "{case_name} of {name} cannot be a fallback, because {name} has no evolution steps and unknown constructors can only be skipped in evolved data"
+                        );
+                    }
+                    if let Some((other_case_name, _)) = &fallback_case {
+                        panic!(
+                            "Both {other_case_name} and {case_name} of {name} are marked as fallback"
+                        );
+                    }
+                    let construct_fallback = match &variant.fields {
+                        Fields::Unit => {
+                            quote! { #name::#case_name }
+                        }
+                        Fields::Named(named_fields) => {
+                            let field_idents = named_fields
+                                .named
+                                .iter()
+                                .map(|field| field.ident.as_ref().unwrap());
+                            quote! { #name::#case_name { #(#field_idents: Default::default()),* } }
+                        }
+                        Fields::Unnamed(unnamed_fields) => {
+                            let defaults = unnamed_fields
+                                .unnamed
+                                .iter()
+                                .map(|_| quote! { Default::default() });
+                            quote! { #name::#case_name(#(#defaults),*) }
+                        }
+                    };
+                    fallback_case = Some((case_name.to_string(), construct_fallback));
+                }
+
                 let pattern = match &variant.fields {
                     Fields::Unit => {
                         quote! { #name::#case_name }
@@ -179,8 +399,22 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
                 };
 
                 if !is_transient {
+                    if no_evolution && has_evolution_attribute(&variant.attrs) {
+                        panic!(
+                            "{case_name} of {name} cannot have #[evolution(...)], because {name} has #[desert(no_evolution)]"
+                        );
+                    }
+                    if let Some(other_case_name) =
+                        used_case_ids.insert(case_id, case_name.to_string())
+                    {
+                        panic!(
+                            "Constructors {other_case_name} and {case_name} of {name} have the same id {case_id}"
+                        );
+                    }
+
+                    schema.push_str(&format!("{case_name}#{case_id}"));
                     let (case_evolution_steps, case_field_defaults) =
-                        evolution_steps_from_attributes(&variant.attrs);
+                        evolution_steps_from_attributes(&variant.attrs, &mut schema);
                     let version = case_evolution_steps.len();
                     let mut case_push_evolution_steps = Vec::new();
                     for evolution_step in case_evolution_steps {
@@ -191,7 +425,9 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
                     let mut case_serialization_commands = Vec::new();
                     let mut case_deserialization_commands = Vec::new();
 
-                    let new_v = if version == 0 {
+                    let new_v = if no_evolution {
+                        quote! { new_flat }
+                    } else if version == 0 {
                         quote! { new_v0 }
                     } else {
                         quote! { new }
@@ -221,13 +457,19 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
                         &mut case_serialization_commands,
                         &mut case_deserialization_commands,
                         &variant.fields,
+                        &mut schema,
+                        false,
+                        use_serde_rename,
                     );
+                    schema.push(';');
 
+                    let case_name_string = case_name.to_string();
                     cases.push(
                         quote! {
                         #pattern => {
-                            serializer.write_constructor(
-                                #effective_case_idx as u32,
+                            serializer.write_named_constructor(
+                                #case_id,
+                                #case_name_string,
                                 |context| {
                                     let mut serializer = desert_rust::adt::AdtSerializer::#new_v(&#case_metadata_name, context);
                                     #(#case_serialization_commands)*
@@ -253,24 +495,33 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
                         }
                     };
 
-                    deserialization_commands.push(
+                    let deserialize_case = if no_evolution {
                         quote! {
-                            if let Some(result) = deserializer.read_constructor(#effective_case_idx as u32,
-                                |context| {
-                                    let stored_version = context.read_u8()?;
-                                    if stored_version == 0 {
-                                        let mut deserializer = desert_rust::adt::AdtDeserializer::new_v0(&#case_metadata_name, context)?;
-                                        Ok(#construct_case)
-                                    } else {
-                                        let mut deserializer = desert_rust::adt::AdtDeserializer::new(&#case_metadata_name, context, stored_version)?;
-                                        Ok(#construct_case)
-                                    }
-                                }
-                            )? {
-                                return Ok(result)
+                            let mut deserializer = desert_rust::adt::AdtDeserializer::new_v0(&#case_metadata_name, context)?;
+                            Ok(#construct_case)
+                        }
+                    } else {
+                        quote! {
+                            let stored_version = context.read_u8()?;
+                            if stored_version == 0 {
+                                let mut deserializer = desert_rust::adt::AdtDeserializer::new_v0(&#case_metadata_name, context)?;
+                                Ok(#construct_case)
+                            } else {
+                                let mut deserializer = desert_rust::adt::AdtDeserializer::new(&#case_metadata_name, context, stored_version)?;
+                                Ok(#construct_case)
                             }
-                       }
-                    );
+                        }
+                    };
+
+                    deserialization_commands.push(quote! {
+                         if let Some(result) = deserializer.read_named_constructor(#case_id, #case_name_string,
+                             |context| {
+                                 #deserialize_case
+                             }
+                         )? {
+                             return Ok(result)
+                         }
+                    });
 
                     effective_case_idx += 1;
                 } else {
@@ -287,6 +538,7 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
                 }
             }
 
+            schema.push('}');
             serialization_commands.push(quote! {
                 match self {
                     #(#cases),*
@@ -312,13 +564,27 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
         }
     });
 
-    let new_v = if version == 0 {
+    let new_v = if no_evolution {
+        quote! { new_flat }
+    } else if version == 0 {
         quote! { new_v0 }
     } else {
         quote! { new }
     };
 
-    let deserialization = if is_record {
+    let deserialization = if builder {
+        quote! {
+            Ok(Self::builder() #(#deserialization_commands)* .build())
+        }
+    } else if is_unit_struct {
+        quote! {
+            Ok(Self)
+        }
+    } else if is_tuple_struct {
+        quote! {
+            Ok(Self(#(#deserialization_commands)*))
+        }
+    } else if is_record {
         quote! {
             Ok(Self {
                     #(#deserialization_commands)*
@@ -327,18 +593,65 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
     } else {
         quote! {
             #(#deserialization_commands)*
-            Err(desert_rust::Error::InvalidConstructorId {
-                type_name: stringify!(#name).to_string(),
-                constructor_id: deserializer.read_or_get_constructor_idx().unwrap_or(u32::MAX),
-            })
+            Err(deserializer.invalid_constructor(stringify!(#name)))
+        }
+    };
+
+    // The data of an unknown constructor can only be skipped if it was stored in a length-prefixed chunk, so the
+    // fallback is not used when reading version 0 data
+    let evolved_deserialization = match &fallback_case {
+        Some((_, construct_fallback)) => quote! {
+            #(#deserialization_commands)*
+            Ok(#construct_fallback)
+        },
+        None => deserialization.clone(),
+    };
+
+    let deserialize = if no_evolution {
+        quote! {
+            let mut deserializer = desert_rust::adt::AdtDeserializer::new_v0(&#metadata_name, context)?;
+            #deserialization
+        }
+    } else {
+        quote! {
+            use desert_rust::BinaryInput;
+
+            let stored_version = context.read_u8()?;
+            if stored_version == 0 {
+                let mut deserializer = desert_rust::adt::AdtDeserializer::new_v0(&#metadata_name, context)?;
+                #deserialization
+            } else {
+                let mut deserializer = desert_rust::adt::AdtDeserializer::new(&#metadata_name, context, stored_version)?;
+                #evolved_deserialization
+            }
         }
     };
 
+    // Lifetime and const parameters are passed through as they are, only the type parameters need codec bounds
+    let serializer_generics =
+        with_type_param_bounds(&ast.generics, quote! { desert_rust::BinarySerializer });
+    let (serializer_impl_generics, ty_generics, serializer_where_clause) =
+        serializer_generics.split_for_impl();
+    let deserializer_generics =
+        with_type_param_bounds(&ast.generics, quote! { desert_rust::BinaryDeserializer });
+    let (deserializer_impl_generics, _, deserializer_where_clause) =
+        deserializer_generics.split_for_impl();
+
+    let schema_hash = if emit_schema_hash {
+        derive_schema_hash(name, &ast.generics, &schema)
+    } else {
+        quote! {}
+    };
+
     let gen = quote! {
         #(#metadata)*
 
+        #schema_hash
+
+        #builder_impl
+
         #[allow(unused_variables)]
-        impl desert_rust::BinarySerializer for #name {
+        impl #serializer_impl_generics desert_rust::BinarySerializer for #name #ty_generics #serializer_where_clause {
             fn serialize<Output: desert_rust::BinaryOutput>(&self, context: &mut desert_rust::SerializationContext<Output>) -> desert_rust::Result<()> {
                 let mut serializer = desert_rust::adt::AdtSerializer::#new_v(&#metadata_name, context);
                 #(#serialization_commands)*
@@ -346,18 +659,9 @@ pub fn derive_binary_codec(input: TokenStream) -> TokenStream {
             }
         }
 
-        impl desert_rust::BinaryDeserializer for #name {
-            fn deserialize<'a, 'b>(context: &'a mut desert_rust::DeserializationContext<'b>) -> desert_rust::Result<Self> {
-                use desert_rust::BinaryInput;
-
-                let stored_version = context.read_u8()?;
-                if stored_version == 0 {
-                    let mut deserializer = desert_rust::adt::AdtDeserializer::new_v0(&#metadata_name, context)?;
-                    #deserialization
-                } else {
-                    let mut deserializer = desert_rust::adt::AdtDeserializer::new(&#metadata_name, context, stored_version)?;
-                    #deserialization
-                }
+        impl #deserializer_impl_generics desert_rust::BinaryDeserializer for #name #ty_generics #deserializer_where_clause {
+            fn deserialize(context: &mut desert_rust::DeserializationContext<'_>) -> desert_rust::Result<Self> {
+                #deserialize
             }
         }
     };
@@ -370,13 +674,19 @@ fn derive_field_serialization(
     serialization_commands: &mut Vec<proc_macro2::TokenStream>,
     deserialization_commands: &mut Vec<proc_macro2::TokenStream>,
     fields: &Fields,
+    schema: &mut String,
+    builder: bool,
+    use_serde_rename: bool,
 ) {
+    schema.push('{');
     for (n, field) in fields.iter().enumerate() {
         let n_ident = Ident::new(&format!("field{n}"), Span::call_site());
         let field_ident = field.ident.as_ref().unwrap_or(&n_ident);
-        let field_name = field_ident.to_string();
+        let mut field_name = field_ident.to_string();
+        let mut renamed = false;
 
         let mut transient = None;
+        let mut optional = None;
         for attr in &field.attrs {
             if attr.path().is_ident("transient") {
                 let args = attr
@@ -387,67 +697,79 @@ fn derive_field_serialization(
                 }
                 let field_default = args[0].clone();
                 transient = Some(field_default);
+            } else if attr.path().is_ident("desert") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("optional") || meta.path.is_ident("not_optional") {
+                        let is_optional = meta.path.is_ident("optional");
+                        if optional == Some(!is_optional) {
+                            return Err(meta.error(
+                                "a field cannot be both #[desert(optional)] and #[desert(not_optional)]",
+                            ));
+                        }
+                        optional = Some(is_optional);
+                        Ok(())
+                    } else if meta.path.is_ident("rename") {
+                        // The serialized field name is used in evolution steps, and it has to match the
+                        // field names used by other (for example Scala) implementations
+                        let new_name: LitStr = meta.value()?.parse()?;
+                        field_name = new_name.value();
+                        renamed = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported desert field attribute"))
+                    }
+                })
+                .expect("desert field attributes");
             }
         }
+        // #[desert(use_serde_rename)] on the type takes the field names from #[serde(rename = "...")] when
+        // there is no #[desert(rename)] for the field
+        if use_serde_rename && !renamed {
+            if let Some(serde_name) = serde_rename(&field.attrs) {
+                field_name = serde_name;
+            }
+        }
+        // Option fields are detected by the type's name, which can be overridden by #[desert(optional)] and
+        // #[desert(not_optional)] when it is not accurate (for example for type aliases)
+        let optional = optional.unwrap_or_else(|| is_option(&field.ty));
+        // Generated code using the field is spanned to the field's type, so if it does not implement the
+        // codec traits the error is reported at the field instead of at the derive
+        let span = field.ty.span();
 
         match transient {
             None => {
-                serialization_commands.push(quote! {
+                let ty = &field.ty;
+                schema.push_str(&format!("{field_name}:{};", normalized_type(ty)));
+                serialization_commands.push(quote_spanned! {span=>
                     serializer.write_field(#field_name, &#field_ident)?;
                 });
 
-                if is_option(&field.ty) {
-                    match field_defaults.get(&field_name) {
-                        Some(field_default) => {
-                            if field.ident.is_some() {
-                                deserialization_commands.push(quote! {
-                                    #field_ident: deserializer.read_optional_field(#field_name, Some(#field_default))?,
-                                });
-                            } else {
-                                deserialization_commands.push(quote! {
-                                    deserializer.read_optional_field(#field_name, Some(#field_default))?,
-                                });
-                            }
-                        }
-                        None => {
-                            if field.ident.is_some() {
-                                deserialization_commands.push(quote! {
-                                   #field_ident: deserializer.read_optional_field(#field_name, None)?,
-                                });
-                            } else {
-                                deserialization_commands.push(quote! {
-                                   deserializer.read_optional_field(#field_name, None)?,
-                                });
-                            }
-                        }
-                    }
+                let default = match field_defaults.get(&field_name) {
+                    Some(field_default) => quote_spanned! {span=> Some(#field_default) },
+                    None => quote_spanned! {span=> None },
+                };
+                let read = if optional {
+                    quote_spanned! {span=> deserializer.read_optional_field(#field_name, #default)? }
                 } else {
-                    match field_defaults.get(&field_name) {
-                        Some(field_default) => {
-                            if field.ident.is_some() {
-                                deserialization_commands.push(quote! {
-                                    #field_ident: deserializer.read_field(#field_name, Some(#field_default))?,
-                                });
-                            } else {
-                                deserialization_commands.push(quote! {
-                                    deserializer.read_field(#field_name, Some(#field_default))?,
-                                });
-                            }
-                        }
-                        None => {
-                            if field.ident.is_some() {
-                                deserialization_commands.push(quote! {
-                                   #field_ident: deserializer.read_field(#field_name, None)?,
-                                });
-                            } else {
-                                deserialization_commands.push(quote! {
-                                   deserializer.read_field(#field_name, None)?,
-                                });
-                            }
-                        }
-                    }
+                    quote_spanned! {span=> deserializer.read_field(#field_name, #default)? }
+                };
+                if builder {
+                    deserialization_commands.push(quote_spanned! {span=>
+                        .#field_ident(#read)
+                    });
+                } else if field.ident.is_some() {
+                    deserialization_commands.push(quote_spanned! {span=>
+                        #field_ident: #read,
+                    });
+                } else {
+                    deserialization_commands.push(quote_spanned! {span=>
+                        #read,
+                    });
                 }
             }
+            Some(_) if builder => {
+                // The builder fills in the transient fields
+            }
             Some(transient_default_value) => {
                 if field.ident.is_some() {
                     deserialization_commands.push(quote! {
@@ -461,26 +783,466 @@ fn derive_field_serialization(
             }
         }
     }
+    schema.push('}');
+}
+
+fn derive_builder(ast: &DeriveInput) -> proc_macro2::TokenStream {
+    let name = &ast.ident;
+    let fields = match &ast.data {
+        Data::Struct(struct_data) => match &struct_data.fields {
+            Fields::Named(named_fields) => &named_fields.named,
+            _ => panic!("#[desert(builder)] can only be used on structs with named fields, but {name} has none"),
+        },
+        _ => panic!("#[desert(builder)] can only be used on structs, but {name} is not a struct"),
+    };
+    let vis = &ast.vis;
+    let builder_name = Ident::new(&format!("{name}Builder"), Span::call_site());
+    let generics = &ast.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut builder_fields = Vec::new();
+    let mut empty_fields = Vec::new();
+    let mut setters = Vec::new();
+    let mut build_fields = Vec::new();
+    for field in fields {
+        let field_ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let transient_default = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("transient"))
+            .map(|attr| {
+                attr.parse_args::<Expr>()
+                    .expect("#[transient(default)] on fields needs a default value")
+            });
+        match transient_default {
+            Some(default) => {
+                build_fields.push(quote! { #field_ident: #default, });
+            }
+            None => {
+                let message = format!("Field {field_ident} of {name} has not been set");
+                builder_fields.push(quote! { #field_ident: Option<#ty>, });
+                empty_fields.push(quote! { #field_ident: None, });
+                setters.push(quote! {
+                    pub fn #field_ident(mut self, value: #ty) -> Self {
+                        self.#field_ident = Some(value);
+                        self
+                    }
+                });
+                build_fields.push(quote! { #field_ident: self.#field_ident.expect(#message), });
+            }
+        }
+    }
+
+    let builder_doc = format!(
+        "Builder for [{name}], with a setter for each non-transient field. Transient fields get their \
+        `#[transient(...)]` default value."
+    );
+    quote! {
+        #[doc = #builder_doc]
+        #vis struct #builder_name #generics #where_clause {
+            #(#builder_fields)*
+            _marker: ::core::marker::PhantomData<fn() -> #name #ty_generics>,
+        }
+
+        impl #impl_generics ::core::default::Default for #builder_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#empty_fields)*
+                    _marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            #(#setters)*
+
+            /// Constructs the value. Panics if a non-transient field has not been set.
+            pub fn build(self) -> #name #ty_generics {
+                #name {
+                    #(#build_fields)*
+                }
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn builder() -> #builder_name #ty_generics {
+                ::core::default::Default::default()
+            }
+        }
+    }
+}
+
+// #[desert(compact)] enums are written as a single byte constructor id followed by the only field, if any
+fn derive_compact_enum(
+    ast: &DeriveInput,
+    use_sorted_constructors: bool,
+    discriminants: &HashMap<String, u32>,
+    emit_schema_hash: bool,
+) -> TokenStream {
+    let name = &ast.ident;
+    let enum_data = match &ast.data {
+        Data::Enum(enum_data) => enum_data,
+        _ => panic!("#[desert(compact)] can only be used on enums, but {name} is not an enum"),
+    };
+    if has_evolution_attribute(&ast.attrs) {
+        panic!("{name} cannot have both #[desert(compact)] and #[evolution(...)]");
+    }
+
+    let mut variants = enum_data.variants.iter().collect::<Vec<_>>();
+    if use_sorted_constructors {
+        variants.sort_by_key(|variant| variant.ident.to_string());
+    }
+
+    let mut schema = String::from("compact;enum{");
+    let mut serialization_cases = Vec::new();
+    let mut deserialization_cases = Vec::new();
+    let mut effective_case_idx: u32 = 0;
+    let mut used_case_ids = HashMap::new();
+    for variant in variants {
+        let case_name = &variant.ident;
+        if has_evolution_attribute(&variant.attrs) {
+            panic!(
+                "{case_name} of {name} cannot have #[evolution(...)], because {name} has #[desert(compact)]"
+            );
+        }
+        if variant.fields.len() > 1 {
+            panic!(
+                "{case_name} of {name} has more than one field, which is not supported by #[desert(compact)]"
+            );
+        }
+        let field = variant.fields.iter().next();
+        if let Some(field) = field {
+            if field
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("transient") || attr.path().is_ident("desert"))
+            {
+                panic!("The field of {case_name} of {name} cannot have attributes, because {name} has #[desert(compact)]");
+            }
+        }
+
+        let is_transient = variant
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("transient"));
+        let mut explicit_case_id = None;
+        for attr in &variant.attrs {
+            if attr.path().is_ident("desert") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("id") {
+                        let id: LitInt = meta.value()?.parse()?;
+                        explicit_case_id = Some(id.base10_parse::<u32>()?);
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported desert variant attribute for compact enums"))
+                    }
+                })
+                .expect("desert variant attributes");
+            }
+        }
+        let explicit_case_id =
+            case_id_from_discriminant(name, case_name, explicit_case_id, discriminants);
+
+        let (pattern, construct) = match (&variant.fields, field) {
+            (Fields::Named(_), Some(field)) => {
+                let field_ident = field.ident.as_ref().unwrap();
+                (
+                    quote! { #name::#case_name { #field_ident: value } },
+                    quote! { #name::#case_name { #field_ident: desert_rust::BinaryDeserializer::deserialize(context)? } },
+                )
+            }
+            (Fields::Unnamed(_), Some(_)) => (
+                quote! { #name::#case_name(value) },
+                quote! { #name::#case_name(desert_rust::BinaryDeserializer::deserialize(context)?) },
+            ),
+            (Fields::Named(_), None) => (
+                quote! { #name::#case_name {} },
+                quote! { #name::#case_name {} },
+            ),
+            (Fields::Unnamed(_), None) => (
+                quote! { #name::#case_name() },
+                quote! { #name::#case_name() },
+            ),
+            (Fields::Unit, _) => (quote! { #name::#case_name }, quote! { #name::#case_name }),
+        };
+
+        if is_transient {
+            let name_string = name.to_string();
+            let case_name_string = case_name.to_string();
+            serialization_cases.push(quote! {
+                #pattern => Err(desert_rust::Error::SerializingTransientConstructor {
+                    type_name: #name_string.to_string(),
+                    constructor_name: #case_name_string.to_string(),
+                })
+            });
+            continue;
+        }
+
+        let case_id = explicit_case_id.unwrap_or(effective_case_idx);
+        let case_id = u8::try_from(case_id).unwrap_or_else(|_| {
+            panic!("{case_name} of {name} has id {case_id}, but #[desert(compact)] only supports ids up to 255")
+        });
+        if let Some(other_case_name) = used_case_ids.insert(case_id, case_name.to_string()) {
+            panic!("Constructors {other_case_name} and {case_name} of {name} have the same id {case_id}");
+        }
+        effective_case_idx += 1;
+
+        schema.push_str(&format!("{case_name}#{case_id}{{"));
+        let serialize_field = match field {
+            Some(field) => {
+                let ty = &field.ty;
+                let field_name = match &field.ident {
+                    Some(ident) => ident.to_string(),
+                    None => String::from("field0"),
+                };
+                schema.push_str(&format!("{field_name}:{};", normalized_type(ty)));
+                // Spanned to the field's type, so a missing codec implementation is reported at the field
+                quote_spanned! {ty.span()=>
+                    desert_rust::BinarySerializer::serialize(value, context)
+                }
+            }
+            None => quote! { Ok(()) },
+        };
+        schema.push_str("};");
+
+        serialization_cases.push(quote! {
+            #pattern => {
+                context.write_u8(#case_id);
+                #serialize_field
+            }
+        });
+        deserialization_cases.push(quote! {
+            #case_id => Ok(#construct),
+        });
+    }
+    schema.push('}');
+
+    let serializer_generics =
+        with_type_param_bounds(&ast.generics, quote! { desert_rust::BinarySerializer });
+    let (serializer_impl_generics, ty_generics, serializer_where_clause) =
+        serializer_generics.split_for_impl();
+    let deserializer_generics =
+        with_type_param_bounds(&ast.generics, quote! { desert_rust::BinaryDeserializer });
+    let (deserializer_impl_generics, _, deserializer_where_clause) =
+        deserializer_generics.split_for_impl();
+    let schema_hash = if emit_schema_hash {
+        derive_schema_hash(name, &ast.generics, &schema)
+    } else {
+        quote! {}
+    };
+
+    let gen = quote! {
+        #schema_hash
+
+        impl #serializer_impl_generics desert_rust::BinarySerializer for #name #ty_generics #serializer_where_clause {
+            fn serialize<Output: desert_rust::BinaryOutput>(&self, context: &mut desert_rust::SerializationContext<Output>) -> desert_rust::Result<()> {
+                use desert_rust::BinaryOutput;
+
+                match self {
+                    #(#serialization_cases),*
+                }
+            }
+        }
+
+        impl #deserializer_impl_generics desert_rust::BinaryDeserializer for #name #ty_generics #deserializer_where_clause {
+            fn deserialize(context: &mut desert_rust::DeserializationContext<'_>) -> desert_rust::Result<Self> {
+                use desert_rust::BinaryInput;
+
+                match context.read_u8()? {
+                    #(#deserialization_cases)*
+                    constructor_id => Err(desert_rust::Error::InvalidConstructorId {
+                        type_name: stringify!(#name).to_string(),
+                        constructor_id: constructor_id as u32,
+                    }),
+                }
+            }
+        }
+    };
+
+    gen.into()
+}
+
+fn derive_schema_hash(name: &Ident, generics: &Generics, schema: &str) -> proc_macro2::TokenStream {
+    let schema_hash = fnv1a_64(schema.as_bytes());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Hash of the binary schema of this type, for checking the compatibility of two builds before
+            /// exchanging data.
+            ///
+            /// It is the 64-bit FNV-1a hash of the UTF-8 bytes of a textual description of the type. The
+            /// description lists the evolution steps as `[FieldAdded(name);...]`, followed by
+            /// `struct{name:type;...}` for structs or `enum{Name#id[...]{...};...}` for enums. Only non-transient
+            /// fields and constructors are listed, fields by their serialized name. Types with
+            /// `#[desert(no_evolution)]` are prefixed by `no_evolution;`, types with `#[desert(compact)]` are
+            /// described as `compact;enum{Name#id{...};...}`, and the name of the type itself is not part of the
+            /// description.
+            ///
+            /// Field types are the tokens as written in the source, without whitespace except for a single space
+            /// between two adjacent identifiers, keywords, lifetimes or literals, for example `Vec<String>`,
+            /// `(u32,Option<String>)` or `Box<dyn Shape>`. Paths and type aliases are not resolved, so the hash
+            /// reflects how the field types are spelled rather than their binary format: spelling `Vec<u8>` as
+            /// `std::vec::Vec<u8>` or through an alias changes the hash without changing the serialized data.
+            pub const SCHEMA_HASH: u64 = #schema_hash;
+        }
+    }
+}
+
+/// The tokens of a type without whitespace, except for a single space separating two identifiers, keywords,
+/// lifetimes or literals, so the description of a type in SCHEMA_HASH does not depend on how proc_macro2
+/// prints tokens
+fn normalized_type(ty: &Type) -> String {
+    fn push_tokens(tokens: proc_macro2::TokenStream, result: &mut String) {
+        for token in tokens {
+            match token {
+                proc_macro2::TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        proc_macro2::Delimiter::Parenthesis => ("(", ")"),
+                        proc_macro2::Delimiter::Brace => ("{", "}"),
+                        proc_macro2::Delimiter::Bracket => ("[", "]"),
+                        proc_macro2::Delimiter::None => ("", ""),
+                    };
+                    result.push_str(open);
+                    push_tokens(group.stream(), result);
+                    result.push_str(close);
+                }
+                proc_macro2::TokenTree::Punct(punct) => result.push(punct.as_char()),
+                proc_macro2::TokenTree::Ident(_) | proc_macro2::TokenTree::Literal(_) => {
+                    let word = token.to_string();
+                    if result
+                        .chars()
+                        .last()
+                        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+                    {
+                        result.push(' ');
+                    }
+                    result.push_str(&word);
+                }
+            }
+        }
+    }
+
+    let mut result = String::new();
+    push_tokens(quote!(#ty), &mut result);
+    result
+}
+
+/// 64-bit FNV-1a, used for SCHEMA_HASH because it is trivial to reproduce in other implementations
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn with_type_param_bounds(generics: &Generics, bound: proc_macro2::TokenStream) -> Generics {
+    let mut generics = generics.clone();
+    for param in &mut generics.params {
+        if let GenericParam::Type(type_param) = param {
+            type_param
+                .bounds
+                .push(syn::parse2(bound.clone()).expect("codec trait bound"));
+        }
+    }
+    generics
+}
+
+/// The discriminant of each variant of a fieldless enum by the variant's name, following the implicit
+/// numbering of the variants without an explicit discriminant
+fn repr_discriminants(ast: &DeriveInput) -> HashMap<String, u32> {
+    let name = &ast.ident;
+    let enum_data = match &ast.data {
+        Data::Enum(enum_data) => enum_data,
+        _ => panic!(
+            "#[desert(repr_discriminant)] can only be used on enums, but {name} is not an enum"
+        ),
+    };
+    let mut discriminants = HashMap::new();
+    let mut next_discriminant = 0u32;
+    for variant in &enum_data.variants {
+        let case_name = &variant.ident;
+        if !matches!(variant.fields, Fields::Unit) {
+            panic!("{case_name} of {name} has fields, but #[desert(repr_discriminant)] can only be used on fieldless enums");
+        }
+        let discriminant = match &variant.discriminant {
+            Some((_, Expr::Lit(expr))) => match &expr.lit {
+                Lit::Int(lit) => lit.base10_parse::<u32>().ok(),
+                _ => None,
+            },
+            Some(_) => None,
+            None => Some(next_discriminant),
+        }
+        .unwrap_or_else(|| {
+            panic!("The discriminant of {case_name} of {name} must be a non-negative integer literal to be used by #[desert(repr_discriminant)]")
+        });
+        discriminants.insert(case_name.to_string(), discriminant);
+        next_discriminant = discriminant.wrapping_add(1);
+    }
+    discriminants
+}
+
+/// The id of a constructor given by its discriminant with #[desert(repr_discriminant)], or its #[desert(id = N)]
+fn case_id_from_discriminant(
+    name: &Ident,
+    case_name: &Ident,
+    explicit_case_id: Option<u32>,
+    discriminants: &HashMap<String, u32>,
+) -> Option<u32> {
+    match discriminants.get(&case_name.to_string()) {
+        Some(_) if explicit_case_id.is_some() => {
+            panic!("{case_name} of {name} cannot have #[desert(id = N)], because {name} has #[desert(repr_discriminant)]")
+        }
+        Some(discriminant) => Some(*discriminant),
+        None => explicit_case_id,
+    }
+}
+
+/// The name given by `#[serde(rename = "...")]`, ignoring every other serde attribute, and the
+/// `rename(serialize = "...", deserialize = "...")` form which does not give a single name
+fn serde_rename(attrs: &[Attribute]) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .find_map(|meta| match meta {
+            Meta::NameValue(MetaNameValue {
+                path,
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(name),
+                        ..
+                    }),
+                ..
+            }) if path.is_ident("rename") => Some(name.value()),
+            _ => None,
+        })
+}
+
+fn has_evolution_attribute(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("evolution"))
 }
 
 fn is_option(ty: &Type) -> bool {
     match ty {
         Type::Group(group) => is_option(&group.elem),
         Type::Paren(paren) => is_option(&paren.elem),
-        Type::Path(type_path) => {
-            if type_path.qself.is_none() {
-                let idents = type_path
-                    .path
-                    .segments
-                    .iter()
-                    .map(|segment| segment.ident.to_string())
-                    .collect::<Vec<_>>();
-                idents == vec!["Option"]
-                    || idents == vec!["std", "option", "Option"]
-                    || idents == vec!["core", "option", "Option"]
-            } else {
-                false
-            }
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            let idents = type_path
+                .path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>();
+            idents == vec!["Option"]
+                || idents == vec!["std", "option", "Option"]
+                || idents == vec!["core", "option", "Option"]
         }
         _ => false,
     }
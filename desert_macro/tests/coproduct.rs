@@ -55,3 +55,477 @@ fn can_read_new_data_after_adding_new_constructor_if_it_is_not_the_new_construct
 fn cannot_read_new_constructor_as_old_data() {
     incompatibility_test::<TypeV2, TypeV1>(TypeV2::Cons2V3 { value: 42 });
 }
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+enum PinnedV1 {
+    #[desert(id = 0)]
+    First,
+    #[desert(id = 1)]
+    Second(String),
+    #[desert(id = 2)]
+    Third { value: i64 },
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+enum PinnedV2 {
+    #[desert(id = 0)]
+    First,
+    #[desert(id = 3)]
+    Inserted(bool),
+    #[desert(id = 1)]
+    Second(String),
+    #[desert(id = 2)]
+    Third { value: i64 },
+}
+
+proptest! {
+    #[test]
+    fn serialization_works_with_pinned_constructor_ids(v2 in arb::<PinnedV2>()) {
+        roundtrip(v2);
+    }
+}
+
+#[test]
+fn pinned_constructor_ids_survive_inserting_a_constructor() {
+    compatibility_test(PinnedV1::First, PinnedV2::First);
+    compatibility_test(
+        PinnedV1::Second("x".to_string()),
+        PinnedV2::Second("x".to_string()),
+    );
+    compatibility_test(PinnedV1::Third { value: 42 }, PinnedV2::Third { value: 42 });
+    compatibility_test(PinnedV2::Third { value: 42 }, PinnedV1::Third { value: 42 });
+}
+
+#[test]
+fn cannot_read_inserted_pinned_constructor_as_old_data() {
+    incompatibility_test::<PinnedV2, PinnedV1>(PinnedV2::Inserted(true));
+}
+
+#[test]
+fn duplicate_constructor_ids_are_rejected() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/duplicate_constructor_id.rs");
+}
+
+// Unknown constructors can only be skipped if the enum is serialized in the evolved format, which requires at
+// least one evolution step
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+#[evolution(FieldRemoved("unused"))]
+enum FallbackV1 {
+    Known(String),
+    Removed { value: i64 },
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+#[evolution(FieldRemoved("unused"))]
+enum FallbackV2 {
+    Known(String),
+    #[desert(id = 2)]
+    #[desert(fallback)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+#[evolution(FieldRemoved("unused"))]
+enum FallbackWithFieldsV2 {
+    Known(String),
+    #[desert(id = 2)]
+    #[desert(fallback)]
+    Unknown {
+        reason: String,
+        code: Option<i32>,
+    },
+}
+
+#[test]
+fn removed_constructor_is_read_as_fallback() {
+    compatibility_test(
+        FallbackV1::Known("x".to_string()),
+        FallbackV2::Known("x".to_string()),
+    );
+    compatibility_test(FallbackV1::Removed { value: 42 }, FallbackV2::Unknown);
+    compatibility_test(FallbackV1::Unknown, FallbackV2::Unknown);
+    compatibility_test(FallbackV2::Unknown, FallbackV1::Unknown);
+}
+
+#[test]
+fn removed_constructor_is_read_as_fallback_with_default_fields() {
+    compatibility_test(
+        FallbackV1::Removed { value: 42 },
+        FallbackWithFieldsV2::Unknown {
+            reason: String::new(),
+            code: None,
+        },
+    );
+}
+
+#[test]
+fn removed_constructor_is_read_as_fallback_within_other_data() {
+    compatibility_test(
+        vec![
+            FallbackV1::Removed { value: 1 },
+            FallbackV1::Known("x".to_string()),
+        ],
+        vec![FallbackV2::Unknown, FallbackV2::Known("x".to_string())],
+    );
+}
+
+#[test]
+fn fallback_requires_evolution_steps() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/fallback_without_evolution.rs");
+}
+
+#[test]
+fn multiple_fallbacks_are_rejected() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/duplicate_fallback.rs");
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+enum FunctionType {
+    ReadLocal,
+    WriteLocal,
+    ReadRemote,
+    WriteRemote,
+    WriteRemoteBatched(Option<u64>),
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+#[desert(no_evolution)]
+enum FrozenFunctionType {
+    ReadLocal,
+    WriteLocal,
+    ReadRemote,
+    WriteRemote,
+    WriteRemoteBatched(Option<u64>),
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+#[desert(no_evolution)]
+struct FrozenEntry {
+    function_type: FrozenFunctionType,
+    name: String,
+}
+
+proptest! {
+    #[test]
+    fn no_evolution_enum_roundtrip(value in arb::<FrozenFunctionType>()) {
+        roundtrip(value);
+    }
+
+    #[test]
+    fn no_evolution_struct_roundtrip(value in arb::<FrozenEntry>()) {
+        roundtrip(value);
+    }
+}
+
+#[test]
+fn no_evolution_enum_has_no_version_bytes() {
+    assert_eq!(
+        desert_core::serialize_to_byte_vec(&FunctionType::WriteLocal).unwrap(),
+        vec![0, 1, 0]
+    );
+    assert_eq!(
+        desert_core::serialize_to_byte_vec(&FrozenFunctionType::WriteLocal).unwrap(),
+        vec![1]
+    );
+    assert_eq!(
+        desert_core::serialized_size(&FunctionType::WriteRemoteBatched(Some(1))).unwrap(),
+        desert_core::serialized_size(&FrozenFunctionType::WriteRemoteBatched(Some(1))).unwrap() + 2
+    );
+}
+
+#[test]
+fn no_evolution_cannot_be_combined_with_evolution_steps() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/no_evolution_with_evolution.rs");
+    t.compile_fail("tests/ui/no_evolution_with_fallback.rs");
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, BinaryCodec, Arbitrary)]
+#[desert(repr_discriminant)]
+#[repr(u8)]
+enum Color {
+    Red = 1,
+    Green = 5,
+    Blue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, BinaryCodec, Arbitrary)]
+#[desert(compact, repr_discriminant)]
+#[repr(u8)]
+enum CompactColor {
+    Red = 1,
+    Green = 5,
+    Blue,
+}
+
+proptest! {
+    #[test]
+    fn repr_discriminant_roundtrip(color in arb::<Color>(), compact_color in arb::<CompactColor>()) {
+        roundtrip(color);
+        roundtrip(compact_color);
+    }
+}
+
+#[test]
+fn repr_discriminant_is_the_constructor_id() {
+    for (color, compact_color) in [
+        (Color::Red, CompactColor::Red),
+        (Color::Green, CompactColor::Green),
+        (Color::Blue, CompactColor::Blue),
+    ] {
+        assert_eq!(
+            desert_core::serialize_to_byte_vec(&color).unwrap(),
+            vec![0, color as u8, 0]
+        );
+        assert_eq!(
+            desert_core::serialize_to_byte_vec(&compact_color).unwrap(),
+            vec![compact_color as u8]
+        );
+    }
+    assert_eq!(CompactColor::Blue as u8, 6);
+}
+
+#[test]
+fn repr_discriminant_requires_fieldless_enums() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/repr_discriminant_with_fields.rs");
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+#[evolution(FieldRemoved("unused"))]
+enum EvolvedCases {
+    First,
+    #[evolution(FieldAdded("y", 0))]
+    Second {
+        x: i32,
+        y: i32,
+    },
+}
+
+#[test]
+fn debug_dump_shows_constructors_of_evolved_cases() {
+    let data = desert_core::serialize_to_byte_vec(&EvolvedCases::Second { x: 1, y: 2 }).unwrap();
+    let dump = desert_core::debug_dump(&data).unwrap();
+    assert!(dump.contains("[1] field unused removed"));
+    assert!(dump.contains("constructor 1"));
+    assert!(dump.contains("chunk 0: 4 bytes"));
+    assert!(dump.contains("chunk 1: 4 bytes"));
+}
+
+proptest! {
+    #[test]
+    fn evolved_enum_without_fallback_roundtrip(value in arb::<EvolvedCases>()) {
+        roundtrip(value);
+    }
+}
+
+#[test]
+fn evolved_enum_writes_the_constructor_into_the_first_chunk() {
+    // The constructor id and the case follow the evolution header, as part of chunk 0
+    assert_eq!(
+        desert_core::serialize_to_byte_vec(&EvolvedCases::First).unwrap(),
+        vec![1, 4, 3, 12, b'u', b'n', b'u', b's', b'e', b'd', 0, 0]
+    );
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+enum Outcome {
+    Success(u64),
+    Failure { reason: String },
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+#[desert(compact)]
+enum CompactOutcome {
+    Success(u64),
+    Failure { reason: String },
+    Cancelled,
+}
+
+proptest! {
+    #[test]
+    fn compact_enum_roundtrip(value in arb::<CompactOutcome>()) {
+        roundtrip(value);
+    }
+}
+
+#[test]
+fn compact_enum_is_a_tag_byte_followed_by_the_field() {
+    let data = desert_core::serialize_to_byte_vec(&CompactOutcome::Failure {
+        reason: "timeout".to_string(),
+    })
+    .unwrap();
+    let result: Result<u64, String> = Err("timeout".to_string());
+    let mut expected = vec![1];
+    expected.extend(&desert_core::serialize_to_byte_vec(&result).unwrap()[1..]);
+    assert_eq!(data, expected);
+}
+
+#[test]
+fn compact_enum_is_smaller_than_the_default_layout() {
+    let compact = desert_core::serialized_size(&CompactOutcome::Success(1)).unwrap();
+    let default = desert_core::serialized_size(&Outcome::Success(1)).unwrap();
+    assert_eq!(compact, 9);
+    assert_eq!(default, compact + 2);
+    assert_eq!(
+        desert_core::serialized_size(&CompactOutcome::Cancelled).unwrap(),
+        1
+    );
+}
+
+#[test]
+fn compact_enum_variants_can_have_at_most_one_field() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/compact_with_multiple_fields.rs");
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+enum ShapeV1 {
+    Point,
+    Circle(f64),
+    Rectangle { width: f64, height: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+enum ShapeV2 {
+    Rectangle { width: f64, height: f64 },
+    Triangle,
+    Circle(f64),
+    Point,
+}
+
+fn named_constructors() -> desert_core::Options {
+    desert_core::Options {
+        named_constructors: true,
+        ..Default::default()
+    }
+}
+
+fn with_named_constructors<T: desert_core::BinarySerializer>(value: &T) -> Vec<u8> {
+    desert_core::serialize_with_options(value, Vec::new(), named_constructors()).unwrap()
+}
+
+#[test]
+fn named_constructors_roundtrip() {
+    let values = vec![
+        ShapeV1::Circle(1.5),
+        ShapeV1::Point,
+        ShapeV1::Rectangle {
+            width: 2.0,
+            height: 3.0,
+        },
+        ShapeV1::Circle(2.5),
+    ];
+    let data = with_named_constructors(&values);
+    let result =
+        desert_core::deserialize_with_options::<Vec<ShapeV1>>(&data, named_constructors()).unwrap();
+    assert_eq!(result, values);
+}
+
+#[test]
+fn named_constructors_survive_reordering_variants() {
+    let data = with_named_constructors(&vec![
+        ShapeV1::Point,
+        ShapeV1::Circle(1.0),
+        ShapeV1::Rectangle {
+            width: 2.0,
+            height: 3.0,
+        },
+    ]);
+    let result =
+        desert_core::deserialize_with_options::<Vec<ShapeV2>>(&data, named_constructors()).unwrap();
+    assert_eq!(
+        result,
+        vec![
+            ShapeV2::Point,
+            ShapeV2::Circle(1.0),
+            ShapeV2::Rectangle {
+                width: 2.0,
+                height: 3.0
+            }
+        ]
+    );
+}
+
+#[test]
+fn unknown_constructor_name_fails() {
+    let data = with_named_constructors(&ShapeV2::Triangle);
+    let result = desert_core::deserialize_with_options::<ShapeV1>(&data, named_constructors());
+    assert!(matches!(
+        result,
+        Err(desert_core::Error::InvalidConstructorName { constructor_name, type_name })
+            if constructor_name == "Triangle" && type_name == "ShapeV1"
+    ));
+}
+
+/// Byte buffer counting how many times it has been cloned
+#[derive(Debug, PartialEq)]
+struct Blob(Vec<u8>);
+
+static BLOB_CLONES: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+impl Clone for Blob {
+    fn clone(&self) -> Self {
+        BLOB_CLONES.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Blob(self.0.clone())
+    }
+}
+
+impl desert_core::BinarySerializer for Blob {
+    fn serialize<Output: desert_core::BinaryOutput>(
+        &self,
+        context: &mut desert_core::SerializationContext<Output>,
+    ) -> desert_core::Result<()> {
+        self.0.serialize(context)
+    }
+}
+
+impl desert_core::BinaryDeserializer for Blob {
+    fn deserialize(
+        context: &mut desert_core::DeserializationContext<'_>,
+    ) -> desert_core::Result<Self> {
+        Ok(Blob(Vec::deserialize(context)?))
+    }
+}
+
+// Neither enum implements Clone, so serializing their single-field variants has to borrow the field
+#[derive(Debug, PartialEq, BinaryCodec)]
+enum Payload {
+    Empty,
+    Bytes(Blob),
+    Named { data: Blob },
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+#[desert(compact)]
+enum CompactPayload {
+    Empty,
+    Bytes(Blob),
+    Named { data: Blob },
+}
+
+#[test]
+fn single_field_variants_serialize_owned_fields_without_cloning() {
+    use desert_core::{deserialize, serialize_to_byte_vec};
+
+    let large = || Blob((0..1_000_000).map(|i| i as u8).collect());
+    for value in [Payload::Bytes(large()), Payload::Named { data: large() }] {
+        let data = serialize_to_byte_vec(&value).unwrap();
+        assert_eq!(deserialize::<Payload>(&data).unwrap(), value);
+    }
+    for value in [
+        CompactPayload::Bytes(large()),
+        CompactPayload::Named { data: large() },
+    ] {
+        let data = serialize_to_byte_vec(&value).unwrap();
+        // constructor id, length and the bytes, without any header around the field
+        assert_eq!(data.len(), 1 + 3 + 1_000_000);
+        assert_eq!(deserialize::<CompactPayload>(&data).unwrap(), value);
+    }
+    assert_eq!(BLOB_CLONES.load(std::sync::atomic::Ordering::SeqCst), 0);
+}
@@ -1,6 +1,7 @@
 use assert2::check;
 use desert_core::*;
 use desert_macro::BinaryCodec;
+use std::borrow::Cow;
 use test_r::test;
 
 test_r::enable!();
@@ -35,6 +36,58 @@ enum Choices {
     C { pt: Option<Point>, z: u64 },
 }
 
+#[derive(Debug, PartialEq, BinaryCodec)]
+struct Wrapper<'a, T> {
+    name: Cow<'a, str>,
+    value: T,
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+enum Either<'a, L, R> {
+    Left(L),
+    Right { value: R, note: Cow<'a, str> },
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+struct FixedBuf<const N: usize> {
+    data: [u8; N],
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+#[evolution(FieldAdded("scale", 1))]
+struct Samples<T, const N: usize> {
+    values: [T; N],
+    scale: i32,
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+#[desert(builder)]
+#[evolution(FieldAdded("tags", Vec::new()))]
+struct Session<T> {
+    user: String,
+    payload: T,
+    #[transient(0)]
+    hits: u32,
+    #[transient("cold".to_string())]
+    cache_state: String,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+struct Label {
+    text: String,
+    priority: u8,
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+struct Marker;
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+struct Empty {}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+struct EmptyTuple();
+
 #[test]
 fn debug() {
     let pt = Point {
@@ -74,3 +127,211 @@ fn debug() {
     let choices2 = deserialize(&bytes3).unwrap();
     check!(choices == choices2);
 }
+
+#[test]
+fn non_codec_fields_are_reported_at_the_field() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/non_codec_field.rs");
+}
+
+#[test]
+fn generic_types_with_lifetimes() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/generic_lifetime.rs");
+}
+
+#[test]
+fn owned_generic_instantiations_roundtrip() {
+    let wrapper: Wrapper<'static, Vec<u32>> = Wrapper {
+        name: Cow::Owned("numbers".to_string()),
+        value: vec![1, 2, 3],
+    };
+    let bytes = serialize_to_bytes(&wrapper).unwrap();
+    check!(deserialize::<Wrapper<Vec<u32>>>(&bytes).unwrap() == wrapper);
+
+    let either: Either<'static, u8, Wrapper<'static, String>> = Either::Right {
+        value: Wrapper {
+            name: Cow::Borrowed("static"),
+            value: "inner".to_string(),
+        },
+        note: Cow::Owned("note".to_string()),
+    };
+    let bytes = serialize_to_bytes(&either).unwrap();
+    check!(deserialize::<Either<u8, Wrapper<String>>>(&bytes).unwrap() == either);
+}
+
+#[test]
+fn const_generic_instantiations_roundtrip() {
+    let small = FixedBuf {
+        data: [1u8, 2, 3, 4],
+    };
+    let bytes = serialize_to_bytes(&small).unwrap();
+    check!(deserialize::<FixedBuf<4>>(&bytes).unwrap() == small);
+
+    let large = FixedBuf { data: [0xAB; 64] };
+    let bytes = serialize_to_bytes(&large).unwrap();
+    check!(deserialize::<FixedBuf<64>>(&bytes).unwrap() == large);
+    check!(deserialize::<FixedBuf<4>>(&bytes).is_err());
+
+    let samples = Samples {
+        values: [1.5f64, -2.0, 0.25],
+        scale: 10,
+    };
+    let bytes = serialize_to_bytes(&samples).unwrap();
+    check!(deserialize::<Samples<f64, 3>>(&bytes).unwrap() == samples);
+}
+
+#[test]
+fn fieldless_structs_are_serialized_as_the_version_byte() {
+    let bytes = serialize_to_bytes(&Marker).unwrap();
+    check!(bytes.to_vec() == vec![0]);
+    check!(deserialize::<Marker>(&bytes).unwrap() == Marker);
+
+    let bytes = serialize_to_bytes(&Empty {}).unwrap();
+    check!(bytes.to_vec() == vec![0]);
+    check!(deserialize::<Empty>(&bytes).unwrap() == Empty {});
+
+    let bytes = serialize_to_bytes(&EmptyTuple()).unwrap();
+    check!(bytes.to_vec() == vec![0]);
+    check!(deserialize::<EmptyTuple>(&bytes).unwrap() == EmptyTuple());
+
+    check!(serialized_size(&(Marker, Empty {})).unwrap() == 3);
+}
+
+#[test]
+fn builder_sets_transient_fields_to_their_defaults() {
+    let session = Session::builder()
+        .user("alice".to_string())
+        .payload(42u64)
+        .tags(vec!["admin".to_string()])
+        .build();
+    check!(session.hits == 0);
+    check!(session.cache_state == "cold");
+    check!(session.payload == 42);
+}
+
+#[test]
+fn builder_is_used_when_deserializing() {
+    let mut session = Session::builder()
+        .user("bob".to_string())
+        .payload(vec![1u8, 2, 3])
+        .tags(vec![])
+        .build();
+    session.hits = 12;
+    session.cache_state = "warm".to_string();
+
+    let bytes = serialize_to_bytes(&session).unwrap();
+    let result = deserialize::<Session<Vec<u8>>>(&bytes).unwrap();
+    check!(result.user == "bob");
+    check!(result.payload == vec![1, 2, 3]);
+    check!(result.hits == 0);
+    check!(result.cache_state == "cold");
+}
+
+#[test]
+#[should_panic(expected = "Field payload of Session has not been set")]
+fn builder_panics_on_missing_field() {
+    Session::<u8>::builder().user("carol".to_string()).build();
+}
+
+#[test]
+fn cow_of_custom_type_roundtrips() {
+    let label = Label {
+        text: "urgent".to_string(),
+        priority: 1,
+    };
+    let owned: Cow<'static, Label> = Cow::Owned(label.clone());
+    let bytes = serialize_to_bytes(&owned).unwrap();
+    let result = deserialize::<Cow<'static, Label>>(&bytes).unwrap();
+    check!(matches!(result, Cow::Owned(_)));
+    check!(result.as_ref() == &label);
+
+    let borrowed: Cow<Label> = Cow::Borrowed(&label);
+    check!(serialize_to_bytes(&borrowed).unwrap() == bytes);
+}
+
+#[test]
+fn custom_type_can_be_read_as_cow() {
+    let label = Label {
+        text: "low".to_string(),
+        priority: 9,
+    };
+    let bytes = serialize_to_bytes(&label).unwrap();
+    let result = deserialize::<Cow<'static, Label>>(&bytes).unwrap();
+    check!(result.into_owned() == label);
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+struct Tree {
+    label: u32,
+    children: Vec<Tree>,
+}
+
+#[test]
+fn max_depth_rejects_deeply_nested_derived_values() {
+    let mut tree = Tree {
+        label: 0,
+        children: vec![],
+    };
+    for label in 1..50 {
+        tree = Tree {
+            label,
+            children: vec![tree],
+        };
+    }
+    let data = serialize_to_byte_vec(&tree).unwrap();
+    let limited = |max_depth| Options {
+        max_depth: Some(max_depth),
+        ..Default::default()
+    };
+    check!(deserialize_with_options::<Tree>(&data, limited(100)).unwrap() == tree);
+    check!(matches!(
+        deserialize_with_options::<Tree>(&data, limited(40)),
+        Err(Error::RecursionLimitExceeded { limit: 40 })
+    ));
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+struct WorkerIdV1 {
+    component_id: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, serde::Serialize)]
+#[desert(use_serde_rename)]
+#[evolution(FieldAdded("workerName", String::new()))]
+struct WorkerIdV2 {
+    #[serde(default)]
+    component_id: u64,
+    #[serde(rename = "workerName", skip_serializing_if = "String::is_empty")]
+    worker_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+#[evolution(FieldAdded("workerName", String::new()))]
+struct WorkerIdV2DesertRename {
+    component_id: u64,
+    #[desert(rename = "workerName")]
+    worker_name: String,
+}
+
+#[test]
+fn use_serde_rename_takes_field_names_from_serde() {
+    let value = WorkerIdV2 {
+        component_id: 1,
+        worker_name: "worker-1".to_string(),
+    };
+    let renamed = WorkerIdV2DesertRename {
+        component_id: 1,
+        worker_name: "worker-1".to_string(),
+    };
+    check!(serialize_to_byte_vec(&value).unwrap() == serialize_to_byte_vec(&renamed).unwrap());
+
+    let old = serialize_to_byte_vec(&WorkerIdV1 { component_id: 2 }).unwrap();
+    check!(
+        deserialize::<WorkerIdV2>(&old).unwrap()
+            == WorkerIdV2 {
+                component_id: 2,
+                worker_name: String::new(),
+            }
+    );
+}
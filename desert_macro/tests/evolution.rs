@@ -29,6 +29,15 @@ struct ProdV2 {
     field_b: i32,
 }
 
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+#[evolution(FieldAdded("tags"), FieldAdded("count"))]
+struct ProdWithDefaultFields {
+    field_a: String,
+    field_b: i32,
+    tags: Vec<String>,
+    count: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
 #[evolution(FieldAdded("new_field_1", true), FieldMadeOptional("field_b"))]
 struct ProdV3 {
@@ -37,6 +46,23 @@ struct ProdV3 {
     field_b: Option<i32>,
 }
 
+type Maybe<T> = Option<T>;
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+#[evolution(FieldAdded("new_field_1", true), FieldMadeOptional("field_b"))]
+struct ProdV3Aliased {
+    field_a: String,
+    new_field_1: bool,
+    #[desert(optional)]
+    field_b: Maybe<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+struct NotOptional {
+    #[desert(not_optional)]
+    field_a: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
 #[evolution(
     FieldAdded("new_field_1", true),
@@ -61,6 +87,32 @@ struct ProdV5 {
     new_field_1: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+#[evolution(FieldMadeOptional("field_b"), FieldRemoved("field_c"))]
+struct ProdWithoutAddedFields {
+    field_a: String,
+    field_b: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+struct PointV1(i32, i32);
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+#[evolution(FieldAdded("field2", 0))]
+struct PointV2(i32, i32, i32);
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
+struct Labelled(String, u64, Option<bool>);
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+#[evolution(FieldAdded("points", Vec::new()), FieldAdded("last", None))]
+struct Nested {
+    first: ProdV2,
+    items: Vec<ProdV3>,
+    points: Vec<PointV2>,
+    last: Option<Box<Nested>>,
+}
+
 #[derive(Debug, Clone, PartialEq, BinaryCodec, Arbitrary)]
 #[evolution()]
 enum Coprod1 {
@@ -80,10 +132,22 @@ enum Coprod2 {
 }
 
 mod tuples_vs_products {
+    use crate::desert_rust;
     use crate::serialization_properties::compatibility_test;
     use crate::ProdV1;
+    use desert_core::serialize_to_byte_vec;
+    use desert_macro::BinaryCodec;
     use test_r::test;
 
+    #[derive(Debug, Clone, PartialEq, BinaryCodec)]
+    #[desert(tuple)]
+    struct Flat {
+        name: String,
+        value: i32,
+        #[transient(0)]
+        cached: u64,
+    }
+
     #[test]
     fn tuple_can_be_read_as_struct() {
         compatibility_test(
@@ -105,6 +169,68 @@ mod tuples_vs_products {
             ("hello".to_string(), 42),
         );
     }
+
+    #[test]
+    fn tuple_struct_attribute_is_serialized_as_tuple() {
+        let value = Flat {
+            name: "hello".to_string(),
+            value: 42,
+            cached: 0,
+        };
+        let tuple = ("hello".to_string(), 42);
+        assert_eq!(
+            serialize_to_byte_vec(&value).unwrap(),
+            serialize_to_byte_vec(&tuple).unwrap()
+        );
+        compatibility_test(value.clone(), tuple.clone());
+        compatibility_test(tuple, value);
+    }
+
+    #[test]
+    fn tuple_struct_attribute_cannot_be_combined_with_evolution_steps() {
+        let t = trybuild::TestCases::new();
+        t.compile_fail("tests/ui/tuple_with_evolution.rs");
+    }
+}
+
+mod tuple_structs {
+    use crate::serialization_properties::{compatibility_test, roundtrip};
+    use crate::{Labelled, PointV1, PointV2};
+    use proptest::proptest;
+    use proptest_arbitrary_interop::arb;
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn two_element_tuple_struct_is_serializable(value in arb::<PointV1>()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn three_element_tuple_struct_is_serializable(value in arb::<Labelled>()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn tuple_struct_with_added_field_is_serializable(value in arb::<PointV2>()) {
+            roundtrip(value);
+        }
+    }
+
+    #[test]
+    fn tuple_struct_can_be_read_as_tuple() {
+        compatibility_test(PointV1(1, -2), (1, -2));
+    }
+
+    #[test]
+    fn new_version_can_read_old() {
+        compatibility_test(PointV1(1, -2), PointV2(1, -2, 0));
+    }
+
+    #[test]
+    fn old_version_can_read_new() {
+        compatibility_test(PointV2(1, -2, 3), PointV1(1, -2));
+    }
 }
 
 mod newtypes {
@@ -309,6 +435,62 @@ mod making_a_field_optional {
     }
 }
 
+mod overriding_option_detection {
+    use crate::serialization_properties::{compatibility_test, roundtrip};
+    use crate::{NotOptional, ProdV1, ProdV3, ProdV3Aliased};
+    use proptest::proptest;
+    use proptest_arbitrary_interop::arb;
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn product_with_aliased_optional_field_is_serializable(value in arb::<ProdV3Aliased>()) {
+            roundtrip(value);
+        }
+
+        #[test]
+        fn product_with_not_optional_option_field_is_serializable(value in arb::<NotOptional>()) {
+            roundtrip(value);
+        }
+    }
+
+    #[test]
+    fn aliased_optional_field_can_read_v1() {
+        let serialized = ProdV1 {
+            field_a: "hello".to_string(),
+            field_b: 200,
+        };
+        let expected = ProdV3Aliased {
+            field_a: "hello".to_string(),
+            new_field_1: true,
+            field_b: Some(200),
+        };
+        compatibility_test(serialized, expected);
+    }
+
+    #[test]
+    fn aliased_optional_field_is_compatible_with_option_field() {
+        let serialized = ProdV3 {
+            field_a: "hello".to_string(),
+            new_field_1: false,
+            field_b: None,
+        };
+        let expected = ProdV3Aliased {
+            field_a: "hello".to_string(),
+            new_field_1: false,
+            field_b: None,
+        };
+        compatibility_test(serialized, expected);
+    }
+
+    #[test]
+    fn invalid_optional_attributes_are_rejected() {
+        let t = trybuild::TestCases::new();
+        t.compile_fail("tests/ui/optional_and_not_optional.rs");
+        t.compile_fail("tests/ui/unknown_field_attribute.rs");
+    }
+}
+
 mod removing_a_field {
     use crate::serialization_properties::{compatibility_test, incompatibility_test, roundtrip};
     use crate::{ProdV1, ProdV2, ProdV3, ProdV4};
@@ -507,3 +689,656 @@ mod adding_new_transient_constructors {
         );
     }
 }
+
+mod serialized_size {
+    use crate::{ProdV1, ProdV2, ProdV3, ProdV5};
+    use desert_core::{serialize_to_byte_vec, serialized_size, BinarySerializer};
+    use test_r::test;
+
+    fn assert_serialized_size<T: BinarySerializer>(value: T) {
+        assert_eq!(
+            serialized_size(&value).unwrap(),
+            serialize_to_byte_vec(&value).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn serialized_size_of_evolved_products_matches_serialized_length() {
+        assert_serialized_size(ProdV1 {
+            field_a: "hello".to_string(),
+            field_b: 42,
+        });
+        assert_serialized_size(ProdV2 {
+            field_a: "hello".to_string(),
+            new_field_1: true,
+            field_b: 42,
+        });
+        assert_serialized_size(ProdV3 {
+            field_a: "hello".to_string(),
+            new_field_1: true,
+            field_b: None,
+        });
+        assert_serialized_size(ProdV5 {
+            field_a: "hello".to_string(),
+            new_field_1: false,
+        });
+    }
+}
+
+mod consumed_length {
+    use crate::{ProdV2, ProdV3};
+    use desert_core::{deserialize_with_consumed, serialize_to_byte_vec};
+    use test_r::test;
+
+    #[test]
+    fn concatenated_evolved_values_can_be_read_using_the_consumed_length() {
+        let first = ProdV2 {
+            field_a: "hello".to_string(),
+            new_field_1: true,
+            field_b: 42,
+        };
+        let second = ProdV3 {
+            field_a: "world".to_string(),
+            new_field_1: false,
+            field_b: None,
+        };
+        let mut data = serialize_to_byte_vec(&first).unwrap();
+        data.extend(serialize_to_byte_vec(&second).unwrap());
+
+        let (result1, consumed) = deserialize_with_consumed::<ProdV2>(&data).unwrap();
+        assert_eq!(result1, first);
+        let (result2, rest) = deserialize_with_consumed::<ProdV3>(&data[consumed..]).unwrap();
+        assert_eq!(result2, second);
+        assert_eq!(consumed + rest, data.len());
+    }
+}
+
+mod buffer_pool {
+    use crate::{Nested, PointV2, ProdV2, ProdV3};
+    use desert_core::{deserialize, serialize_with_options, Options};
+    use test_r::test;
+
+    fn nested(depth: usize) -> Nested {
+        Nested {
+            first: ProdV2 {
+                field_a: format!("first {depth}"),
+                new_field_1: depth.is_multiple_of(2),
+                field_b: depth as i32,
+            },
+            items: (0..depth)
+                .map(|i| ProdV3 {
+                    field_a: format!("item {i}"),
+                    new_field_1: true,
+                    field_b: if i.is_multiple_of(2) {
+                        Some(i as i32)
+                    } else {
+                        None
+                    },
+                })
+                .collect(),
+            points: (0..depth as i32).map(|i| PointV2(i, -i, i * i)).collect(),
+            last: if depth == 0 {
+                None
+            } else {
+                Some(Box::new(nested(depth - 1)))
+            },
+        }
+    }
+
+    #[test]
+    fn reusing_buffers_does_not_change_nested_evolved_values() {
+        let value = nested(4);
+        let pooled = serialize_with_options(&value, Vec::new(), Options::default()).unwrap();
+        let unpooled = serialize_with_options(
+            &value,
+            Vec::new(),
+            Options {
+                disable_buffer_pool: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(pooled, unpooled);
+        assert_eq!(deserialize::<Nested>(&pooled).unwrap(), value);
+    }
+}
+
+mod without_added_fields {
+    use crate::serialization_properties::{compatibility_test, roundtrip};
+    use crate::{ProdV1, ProdWithoutAddedFields};
+    use desert_core::serialize_to_byte_vec;
+    use proptest::proptest;
+    use proptest_arbitrary_interop::arb;
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn product_without_added_fields_is_serializable(value in arb::<ProdWithoutAddedFields>()) {
+            roundtrip(value);
+        }
+    }
+
+    #[test]
+    fn v1_can_read_it_if_it_is_not_none() {
+        let serialized = ProdWithoutAddedFields {
+            field_a: "hello".to_string(),
+            field_b: Some(200),
+        };
+        let expected = ProdV1 {
+            field_a: "hello".to_string(),
+            field_b: 200,
+        };
+        compatibility_test(serialized, expected);
+    }
+
+    #[test]
+    fn matches_the_buffered_format() {
+        let value = ProdWithoutAddedFields {
+            field_a: "hello".to_string(),
+            field_b: Some(200),
+        };
+        let data = serialize_to_byte_vec(&value).unwrap();
+        // version, evolution header with the size of the only chunk, then the chunk itself
+        assert_eq!(
+            data,
+            vec![
+                2, 22, 1, 255, 3, 14, 102, 105, 101, 108, 100, 95, 99, 10, 104, 101, 108, 108, 111,
+                1, 0, 0, 0, 200
+            ]
+        );
+    }
+}
+
+mod chunk_boundaries {
+    use crate::desert_rust;
+    use desert_core::{
+        deserialize, serialize_to_byte_vec, BinaryDeserializer, BinaryInput, BinaryOutput,
+        BinarySerializer, DeserializationContext, Error, SerializationContext,
+    };
+    use desert_macro::BinaryCodec;
+    use test_r::test;
+
+    /// Reads more bytes than it writes
+    struct Greedy;
+
+    impl BinarySerializer for Greedy {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            context: &mut SerializationContext<Output>,
+        ) -> desert_core::Result<()> {
+            context.write_u8(1);
+            Ok(())
+        }
+    }
+
+    impl BinaryDeserializer for Greedy {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> desert_core::Result<Self> {
+            context.read_u16()?;
+            Ok(Greedy)
+        }
+    }
+
+    #[derive(BinaryCodec)]
+    #[evolution(FieldAdded("extra", 0))]
+    struct WithGreedy {
+        greedy: Greedy,
+        extra: u32,
+    }
+
+    #[test]
+    fn reading_past_the_end_of_a_chunk_fails_at_its_end() {
+        let data = serialize_to_byte_vec(&WithGreedy {
+            greedy: Greedy,
+            extra: 42,
+        })
+        .unwrap();
+        // version, two chunk sizes, then the one byte first chunk
+        let result = deserialize::<WithGreedy>(&data);
+        assert!(matches!(
+            result,
+            Err(Error::InputEndedUnexpectedly { offset: 4 })
+        ));
+    }
+}
+
+mod schema_hash {
+    use crate::desert_rust;
+    use desert_macro::BinaryCodec;
+    use test_r::test;
+
+    #[derive(BinaryCodec)]
+    #[desert(schema_hash)]
+    #[evolution()]
+    struct HashedV1 {
+        field_a: String,
+        field_b: i32,
+    }
+
+    #[derive(BinaryCodec)]
+    #[desert(schema_hash)]
+    #[evolution()]
+    struct SameAsHashedV1 {
+        field_a: String,
+        field_b: i32,
+    }
+
+    #[derive(BinaryCodec)]
+    #[desert(schema_hash)]
+    #[evolution(FieldAdded("new_field_1", true))]
+    struct HashedV2 {
+        field_a: String,
+        new_field_1: bool,
+        field_b: i32,
+    }
+
+    #[derive(BinaryCodec)]
+    #[desert(schema_hash)]
+    #[evolution(FieldAdded("new_field_1", true), FieldMadeOptional("field_b"))]
+    struct HashedV3 {
+        field_a: String,
+        new_field_1: bool,
+        field_b: Option<i32>,
+    }
+
+    #[derive(BinaryCodec)]
+    #[desert(schema_hash)]
+    struct WithGenericFields {
+        names: Vec<String>,
+        pair: Option<(u32, String)>,
+        bytes: [u8; 4],
+        map: std::collections::BTreeMap<String, u32>,
+    }
+
+    /// Defines its own SCHEMA_HASH, which does not collide with the derive without #[desert(schema_hash)]
+    #[derive(BinaryCodec)]
+    struct WithoutSchemaHash {
+        field_a: String,
+    }
+
+    impl WithoutSchemaHash {
+        const SCHEMA_HASH: &'static str = "user defined";
+    }
+
+    fn fnv1a_64(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    #[test]
+    fn is_the_fnv1a_hash_of_the_schema_description() {
+        assert_eq!(
+            HashedV1::SCHEMA_HASH,
+            fnv1a_64(b"[]struct{field_a:String;field_b:i32;}")
+        );
+        assert_eq!(HashedV1::SCHEMA_HASH, 0xeb16eacb54ad1370);
+    }
+
+    #[test]
+    fn field_types_are_described_without_whitespace() {
+        assert_eq!(
+            WithGenericFields::SCHEMA_HASH,
+            fnv1a_64(
+                b"[]struct{names:Vec<String>;pair:Option<(u32,String)>;bytes:[u8;4];map:std::collections::BTreeMap<String,u32>;}"
+            )
+        );
+    }
+
+    #[test]
+    fn is_stable_for_the_same_schema() {
+        assert_eq!(HashedV1::SCHEMA_HASH, SameAsHashedV1::SCHEMA_HASH);
+    }
+
+    #[test]
+    fn changes_with_evolution_steps() {
+        assert_ne!(HashedV1::SCHEMA_HASH, HashedV2::SCHEMA_HASH);
+        assert_ne!(HashedV2::SCHEMA_HASH, HashedV3::SCHEMA_HASH);
+    }
+
+    #[test]
+    fn is_only_generated_on_request() {
+        assert_eq!(WithoutSchemaHash::SCHEMA_HASH, "user defined");
+    }
+}
+
+mod reading_a_named_field {
+    use crate::{ProdV1, ProdV2, ProdV3};
+    use desert_core::adt::{read_named_field, AdtMetadata};
+    use desert_core::{serialize_to_byte_vec, Evolution};
+    use test_r::test;
+
+    fn prod_v2_metadata() -> AdtMetadata {
+        AdtMetadata::new(vec![
+            Evolution::InitialVersion,
+            Evolution::FieldAdded {
+                name: "new_field_1".to_string(),
+            },
+        ])
+    }
+
+    #[test]
+    fn added_field_can_be_read_alone() {
+        let data = serialize_to_byte_vec(&ProdV3 {
+            field_a: "hello".to_string(),
+            new_field_1: false,
+            field_b: Some(42),
+        })
+        .unwrap();
+        let value = read_named_field::<bool>(&data, &prod_v2_metadata(), "new_field_1").unwrap();
+        assert_eq!(value, Some(false));
+    }
+
+    #[test]
+    fn added_field_is_missing_from_old_data() {
+        let data = serialize_to_byte_vec(&ProdV1 {
+            field_a: "hello".to_string(),
+            field_b: 42,
+        })
+        .unwrap();
+        let value = read_named_field::<bool>(&data, &prod_v2_metadata(), "new_field_1").unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn original_field_cannot_be_read_alone() {
+        let data = serialize_to_byte_vec(&ProdV2 {
+            field_a: "hello".to_string(),
+            new_field_1: true,
+            field_b: 42,
+        })
+        .unwrap();
+        assert!(read_named_field::<i32>(&data, &prod_v2_metadata(), "field_b").is_err());
+    }
+}
+
+mod adding_new_field_with_default_value {
+    use crate::serialization_properties::{compatibility_test, roundtrip};
+    use crate::{ProdV1, ProdWithDefaultFields};
+    use proptest::proptest;
+    use proptest_arbitrary_interop::arb;
+    use test_r::test;
+
+    proptest! {
+        #[test]
+        fn product_with_default_fields_is_serializable(value in arb::<ProdWithDefaultFields>()) {
+            roundtrip(value);
+        }
+    }
+
+    #[test]
+    fn new_version_reads_old_with_default_values() {
+        let serialized = ProdV1 {
+            field_a: "hello".to_string(),
+            field_b: 42,
+        };
+        let expected = ProdWithDefaultFields {
+            field_a: "hello".to_string(),
+            field_b: 42,
+            tags: Vec::new(),
+            count: 0,
+        };
+        compatibility_test(serialized, expected);
+    }
+}
+
+mod reading_newer_versions {
+    use crate::desert_rust;
+    use crate::ProdV2;
+    use desert_core::{deserialize, serialize_to_byte_vec};
+    use desert_macro::BinaryCodec;
+    use std::collections::HashMap;
+    use test_r::test;
+
+    #[derive(Debug, Clone, PartialEq, BinaryCodec)]
+    #[evolution(
+        FieldAdded("new_field_1", true),
+        FieldAdded("extra_1", 0),
+        FieldAdded("extra_2", Vec::new()),
+        FieldAdded("extra_3", HashMap::new())
+    )]
+    struct ProdV2WithExtraFields {
+        field_a: String,
+        new_field_1: bool,
+        field_b: i32,
+        extra_1: u64,
+        extra_2: Vec<String>,
+        extra_3: HashMap<String, ProdV2>,
+    }
+
+    #[test]
+    fn old_version_skips_the_chunks_of_fields_added_later() {
+        let inner = ProdV2 {
+            field_a: "inner".to_string(),
+            new_field_1: false,
+            field_b: -1,
+        };
+        let newer = ProdV2WithExtraFields {
+            field_a: "hello".to_string(),
+            new_field_1: false,
+            field_b: 42,
+            extra_1: u64::MAX,
+            extra_2: vec!["a".to_string(), "b".to_string()],
+            extra_3: HashMap::from([("key".to_string(), inner)]),
+        };
+        assert_eq!(serialize_to_byte_vec(&newer).unwrap()[0], 4);
+
+        // Followed by another value, which can only be read if the unknown chunks were skipped exactly
+        let data = serialize_to_byte_vec(&(newer, "trailing".to_string())).unwrap();
+        let (older, trailing) = deserialize::<(ProdV2, String)>(&data).unwrap();
+
+        assert_eq!(
+            older,
+            ProdV2 {
+                field_a: "hello".to_string(),
+                new_field_1: false,
+                field_b: 42,
+            }
+        );
+        assert_eq!(trailing, "trailing");
+    }
+}
+
+mod rejecting_newer_versions {
+    use crate::{ProdV1, ProdV3};
+    use desert_core::{deserialize_with_options, serialize_to_byte_vec, Error, Options};
+    use test_r::test;
+
+    fn strict() -> Options {
+        Options {
+            reject_newer_versions: true,
+            ..Default::default()
+        }
+    }
+
+    fn prod_v3() -> ProdV3 {
+        ProdV3 {
+            field_a: "hello".to_string(),
+            new_field_1: true,
+            field_b: Some(42),
+        }
+    }
+
+    #[test]
+    fn old_version_fails_to_read_new_in_strict_mode() {
+        let data = serialize_to_byte_vec(&prod_v3()).unwrap();
+        let result = deserialize_with_options::<ProdV1>(&data, strict());
+        assert!(matches!(
+            result,
+            Err(Error::NewerVersion {
+                stored: 2,
+                supported: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn same_version_can_be_read_in_strict_mode() {
+        let data = serialize_to_byte_vec(&prod_v3()).unwrap();
+        let result = deserialize_with_options::<ProdV3>(&data, strict()).unwrap();
+        assert_eq!(result, prod_v3());
+    }
+}
+
+mod hand_written_codecs {
+    use crate::{Coprod1, ProdV1, ProdV3};
+    use desert_core::adt::{AdtDeserializer, AdtMetadata, AdtSerializer};
+    use desert_core::{
+        deserialize, serialize_to_byte_vec, BinaryDeserializer, BinaryOutput, BinarySerializer,
+        DeserializationContext, Error, Evolution, Result, SerializationContext,
+    };
+    use std::sync::LazyLock;
+    use test_r::test;
+
+    #[derive(Debug, PartialEq)]
+    struct ManualProdV3 {
+        field_a: String,
+        new_field_1: bool,
+        field_b: Option<i32>,
+    }
+
+    static MANUAL_PROD_V3_METADATA: LazyLock<AdtMetadata> = LazyLock::new(|| {
+        AdtMetadata::new(vec![
+            Evolution::InitialVersion,
+            Evolution::FieldAdded {
+                name: "new_field_1".to_string(),
+            },
+            Evolution::FieldMadeOptional {
+                name: "field_b".to_string(),
+            },
+        ])
+    });
+
+    impl BinarySerializer for ManualProdV3 {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            context: &mut SerializationContext<Output>,
+        ) -> Result<()> {
+            let mut serializer = AdtSerializer::start(&MANUAL_PROD_V3_METADATA, context);
+            serializer.write_field("field_a", &self.field_a)?;
+            serializer.write_field("new_field_1", &self.new_field_1)?;
+            serializer.write_field("field_b", &self.field_b)?;
+            serializer.finish()
+        }
+    }
+
+    impl BinaryDeserializer for ManualProdV3 {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+            let mut deserializer = AdtDeserializer::start(&MANUAL_PROD_V3_METADATA, context)?;
+            Ok(ManualProdV3 {
+                field_a: deserializer.read_field("field_a", None)?,
+                new_field_1: deserializer.read_field("new_field_1", Some(true))?,
+                field_b: deserializer.read_optional_field("field_b", None)?,
+            })
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum ManualCoprod1 {
+        Case11(i32),
+        Case21 { x: String },
+    }
+
+    static EMPTY_METADATA: LazyLock<AdtMetadata> =
+        LazyLock::new(|| AdtMetadata::new(vec![Evolution::InitialVersion]));
+
+    impl BinarySerializer for ManualCoprod1 {
+        fn serialize<Output: BinaryOutput>(
+            &self,
+            context: &mut SerializationContext<Output>,
+        ) -> Result<()> {
+            let mut serializer = AdtSerializer::start(&EMPTY_METADATA, context);
+            match self {
+                ManualCoprod1::Case11(value) => serializer.write_constructor(0, |context| {
+                    let mut serializer = AdtSerializer::start(&EMPTY_METADATA, context);
+                    serializer.write_field("field0", value)?;
+                    serializer.finish()
+                })?,
+                ManualCoprod1::Case21 { x } => serializer.write_constructor(1, |context| {
+                    let mut serializer = AdtSerializer::start(&EMPTY_METADATA, context);
+                    serializer.write_field("x", x)?;
+                    serializer.finish()
+                })?,
+            }
+            serializer.finish()
+        }
+    }
+
+    impl BinaryDeserializer for ManualCoprod1 {
+        fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+            let mut deserializer = AdtDeserializer::start(&EMPTY_METADATA, context)?;
+            if let Some(result) = deserializer.read_constructor(0, |context| {
+                let mut deserializer = AdtDeserializer::start(&EMPTY_METADATA, context)?;
+                Ok(ManualCoprod1::Case11(
+                    deserializer.read_field("field0", None)?,
+                ))
+            })? {
+                return Ok(result);
+            }
+            if let Some(result) = deserializer.read_constructor(1, |context| {
+                let mut deserializer = AdtDeserializer::start(&EMPTY_METADATA, context)?;
+                Ok(ManualCoprod1::Case21 {
+                    x: deserializer.read_field("x", None)?,
+                })
+            })? {
+                return Ok(result);
+            }
+            Err(Error::InvalidConstructorId {
+                type_name: "ManualCoprod1".to_string(),
+                constructor_id: deserializer.read_or_get_constructor_idx()?,
+            })
+        }
+    }
+
+    #[test]
+    fn hand_written_record_is_byte_compatible_with_derived() {
+        let manual = ManualProdV3 {
+            field_a: "hello".to_string(),
+            new_field_1: false,
+            field_b: Some(42),
+        };
+        let derived = ProdV3 {
+            field_a: "hello".to_string(),
+            new_field_1: false,
+            field_b: Some(42),
+        };
+        let data = serialize_to_byte_vec(&manual).unwrap();
+        assert_eq!(data, serialize_to_byte_vec(&derived).unwrap());
+        assert_eq!(deserialize::<ProdV3>(&data).unwrap(), derived);
+        assert_eq!(deserialize::<ManualProdV3>(&data).unwrap(), manual);
+    }
+
+    #[test]
+    fn hand_written_record_reads_older_versions() {
+        let old = ProdV1 {
+            field_a: "old".to_string(),
+            field_b: 7,
+        };
+        let data = serialize_to_byte_vec(&old).unwrap();
+        assert_eq!(
+            deserialize::<ManualProdV3>(&data).unwrap(),
+            ManualProdV3 {
+                field_a: "old".to_string(),
+                new_field_1: true,
+                field_b: Some(7),
+            }
+        );
+    }
+
+    #[test]
+    fn hand_written_enum_is_byte_compatible_with_derived() {
+        let cases = [
+            (ManualCoprod1::Case11(-5), Coprod1::Case11(-5)),
+            (
+                ManualCoprod1::Case21 { x: "x".to_string() },
+                Coprod1::Case21 { x: "x".to_string() },
+            ),
+        ];
+        for (manual, derived) in cases {
+            let data = serialize_to_byte_vec(&manual).unwrap();
+            assert_eq!(data, serialize_to_byte_vec(&derived).unwrap());
+            assert_eq!(deserialize::<Coprod1>(&data).unwrap(), derived);
+            assert_eq!(deserialize::<ManualCoprod1>(&data).unwrap(), manual);
+        }
+    }
+}
@@ -1,12 +1,18 @@
 // Tests deserialization of a binary from the original Scala desert library
 
 use assert2::check;
+use desert_core::adt::{read_named_field, AdtMetadata};
 use desert_core::{
-    deserialize, serialize_to_byte_vec, BinaryDeserializer, BinaryInput, BinaryOutput,
-    BinarySerializer, DeserializationContext, SerializationContext,
+    deserialize, deserialize_from_chain, deserialize_from_reader, deserialize_owned,
+    deserialize_with_options, serialize_into_writer, serialize_to_byte_vec, BinaryDeserializer,
+    BinaryInput, BinaryOutput, BinarySerializer, DeserializationContext, Error, Evolution, Options,
+    OwnedInput, SerializationContext,
 };
 use desert_macro::BinaryCodec;
-use std::collections::{HashMap, HashSet};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufReader, Cursor};
 use std::path::PathBuf;
 use test_r::test;
 use uuid::Uuid;
@@ -40,6 +46,58 @@ struct TestModel1 {
     option: Option<HashMap<String, ListElement2>>,
 }
 
+// Same as TestModel1 but some Rust field names differ from the ones used by the Scala model
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+#[evolution(FieldMadeOptional("option"), FieldAdded("string", "default string".to_string()), FieldAdded("set", HashSet::new()))]
+struct TestModel1Renamed {
+    byte: i8,
+    short: i16,
+    int: i32,
+    long: i64,
+    float: f32,
+    double: f64,
+    boolean: bool,
+    unit: (),
+    #[desert(rename = "string")]
+    text: String,
+    uuid: Uuid,
+    exception: Throwable,
+    list: Vec<ListElement1>,
+    array: Vec<i64>,
+    vector: Vec<ListElement1>,
+    #[desert(rename = "set")]
+    tags: HashSet<String>,
+    either: Result<bool, String>,
+    tried: Result<ListElement2, Throwable>,
+    #[desert(rename = "option")]
+    elements: Option<HashMap<String, ListElement2>>,
+}
+
+// Same as TestModel1 but reading the Scala Map into a BTreeMap. The dataset was written from a Scala Map, not a
+// SortedMap, so this only shows that BTreeMap uses the layout of the former
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+#[evolution(FieldMadeOptional("option"), FieldAdded("string", "default string".to_string()), FieldAdded("set", HashSet::new()))]
+struct TestModel1SortedMap {
+    byte: i8,
+    short: i16,
+    int: i32,
+    long: i64,
+    float: f32,
+    double: f64,
+    boolean: bool,
+    unit: (),
+    string: String,
+    uuid: Uuid,
+    exception: Throwable,
+    list: Vec<ListElement1>,
+    array: Vec<i64>,
+    vector: Vec<ListElement1>,
+    set: HashSet<String>,
+    either: Result<bool, String>,
+    tried: Result<ListElement2, Throwable>,
+    option: Option<BTreeMap<String, ListElement2>>,
+}
+
 #[derive(Debug, Clone, PartialEq, BinaryCodec)]
 struct ListElement1 {
     id: String,
@@ -99,7 +157,11 @@ impl BinarySerializer for StackTraceElement {
 impl BinaryDeserializer for StackTraceElement {
     fn deserialize(context: &mut DeserializationContext<'_>) -> desert_rust::Result<Self> {
         let hdr = context.read_u8()?;
-        assert_eq!(hdr, 0);
+        if hdr != 0 {
+            return Err(Error::DeserializationFailure(format!(
+                "Unsupported StackTraceElement version {hdr}"
+            )));
+        }
         let class_name = Option::<String>::deserialize(context)?;
         let method_name = Option::<String>::deserialize(context)?;
         let file_name = Option::<String>::deserialize(context)?;
@@ -407,3 +469,202 @@ fn golden_test_1() {
 
     assert_eq!(value, value2);
 }
+
+#[test]
+fn golden_test_1_from_reader() {
+    let bytes = include_bytes!("../golden/dataset1.bin");
+    let from_slice: TestModel1 = deserialize(bytes).unwrap();
+
+    let reader = BufReader::new(Cursor::new(bytes));
+    let from_reader: TestModel1 = deserialize_from_reader(reader).unwrap();
+
+    assert_eq!(from_slice, from_reader);
+}
+
+#[test]
+fn golden_test_1_from_owned_input() {
+    let bytes = include_bytes!("../golden/dataset1.bin");
+    let from_slice: TestModel1 = deserialize(bytes).unwrap();
+    let from_owned: TestModel1 = deserialize_owned(OwnedInput::new(bytes.to_vec())).unwrap();
+
+    assert_eq!(from_slice, from_owned);
+}
+
+#[test]
+fn golden_test_1_from_chain() {
+    let bytes = include_bytes!("../golden/dataset1.bin");
+    let from_slice: TestModel1 = deserialize(bytes).unwrap();
+
+    for split in [
+        0,
+        1,
+        7,
+        bytes.len() / 3,
+        bytes.len() / 2,
+        bytes.len() - 1,
+        bytes.len(),
+    ] {
+        let (header, body) = bytes.split_at(split);
+        let from_chain: TestModel1 = deserialize_from_chain(&[header, body]).unwrap();
+        assert_eq!(from_slice, from_chain);
+    }
+
+    let segments = bytes.chunks(5).collect::<Vec<_>>();
+    let from_chain: TestModel1 = deserialize_from_chain(&segments).unwrap();
+    assert_eq!(from_slice, from_chain);
+}
+
+#[test]
+fn golden_test_1_into_writer() {
+    let bytes = include_bytes!("../golden/dataset1.bin");
+    let value: TestModel1 = deserialize(bytes).unwrap();
+
+    let mut written = Vec::new();
+    serialize_into_writer(&value, &mut written).unwrap();
+
+    assert_eq!(written, serialize_to_byte_vec(&value).unwrap());
+}
+
+#[test]
+fn golden_test_1_with_renamed_fields() {
+    let bytes = include_bytes!("../golden/dataset1.bin");
+    let value: TestModel1 = deserialize(bytes).unwrap();
+    let renamed: TestModel1Renamed = deserialize(bytes).unwrap();
+
+    check!(renamed.byte == value.byte);
+    check!(renamed.long == value.long);
+    check!(renamed.text == value.string);
+    check!(renamed.uuid == value.uuid);
+    check!(renamed.exception == value.exception);
+    check!(renamed.array == value.array);
+    check!(renamed.tags == value.set);
+    check!(renamed.tried == value.tried);
+    check!(renamed.elements == value.option);
+
+    let serialized = serialize_to_byte_vec(&renamed).unwrap();
+    let value2: TestModel1 = deserialize(&serialized).unwrap();
+
+    assert_eq!(value, value2);
+}
+
+#[test]
+fn golden_test_1_map_as_btree_map() {
+    let bytes = include_bytes!("../golden/dataset1.bin");
+    let value: TestModel1 = deserialize(bytes).unwrap();
+    let sorted: TestModel1SortedMap = deserialize(bytes).unwrap();
+
+    let map = sorted.option.clone().unwrap();
+    check!(map.keys().collect::<Vec<_>>() == vec!["first", "second", "third"]);
+    check!(Some(map.into_iter().collect::<HashMap<_, _>>()) == value.option);
+
+    let serialized = serialize_to_byte_vec(&sorted).unwrap();
+    let value2: TestModel1 = deserialize(&serialized).unwrap();
+    assert_eq!(value, value2);
+}
+
+#[test]
+fn golden_test_1_debug_dump() {
+    let bytes = include_bytes!("../golden/dataset1.bin");
+    let dump = desert_core::debug_dump(bytes).unwrap();
+
+    check!(dump.starts_with("value at offset 0\n  version 3\n"));
+    check!(dump.contains("[0] chunk of 242502 bytes"));
+    check!(dump.contains("[1] field made optional at chunk 0 position 15"));
+    check!(dump.contains("chunk 0: 242502 bytes at offset 8"));
+    check!(dump.contains("chunk 2: 17 bytes at offset 242510"));
+    check!(dump.contains("chunk 3: 13 bytes at offset 242527"));
+}
+
+#[test]
+fn golden_test_1_read_named_field() {
+    let bytes = include_bytes!("../golden/dataset1.bin");
+    let metadata = AdtMetadata::new(vec![
+        Evolution::InitialVersion,
+        Evolution::FieldMadeOptional {
+            name: "option".to_string(),
+        },
+        Evolution::FieldAdded {
+            name: "string".to_string(),
+        },
+        Evolution::FieldAdded {
+            name: "set".to_string(),
+        },
+    ]);
+
+    let string: Option<String> = read_named_field(bytes, &metadata, "string").unwrap();
+    check!(string == Some("Example data set".to_string()));
+
+    let set: Option<HashSet<String>> = read_named_field(bytes, &metadata, "set").unwrap();
+    check!(
+        set == Some(HashSet::from_iter([
+            "hello".to_string(),
+            "world".to_string()
+        ]))
+    );
+
+    check!(read_named_field::<i32>(bytes, &metadata, "int").is_err());
+}
+
+#[test]
+fn deeply_nested_throwable_causes_hit_the_depth_limit() {
+    let leaf = Throwable {
+        class_name: "java.lang.StackOverflowError".to_string(),
+        message: String::new(),
+        stack_trace: vec![],
+        cause: None,
+    };
+    let parent = Throwable {
+        cause: Some(Box::new(leaf.clone())),
+        ..leaf.clone()
+    };
+    let leaf_bytes = serialize_to_byte_vec(&leaf).unwrap();
+    let parent_bytes = serialize_to_byte_vec(&parent).unwrap();
+    check!(parent_bytes.ends_with(&leaf_bytes));
+
+    // A million nested causes would overflow the stack without a limit
+    let cause_prefix = &parent_bytes[..parent_bytes.len() - leaf_bytes.len()];
+    let mut bytes = cause_prefix.repeat(1_000_000);
+    bytes.extend_from_slice(&leaf_bytes);
+
+    let options = Options {
+        max_depth: Some(256),
+        ..Default::default()
+    };
+    check!(matches!(
+        deserialize_with_options::<Throwable>(&bytes, options.clone()),
+        Err(Error::RecursionLimitExceeded { limit: 256 })
+    ));
+
+    let shallow = [cause_prefix.repeat(10), leaf_bytes].concat();
+    let result = deserialize_with_options::<Throwable>(&shallow, options).unwrap();
+    let mut depth = 0;
+    let mut current = &result;
+    while let Some(cause) = &current.cause {
+        depth += 1;
+        current = cause;
+    }
+    check!(depth == 10);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(4096))]
+
+    #[test]
+    fn deserializing_random_bytes_does_not_panic(bytes in vec(any::<u8>(), 0..512)) {
+        let _ = deserialize::<TestModel1>(&bytes);
+    }
+
+    #[test]
+    fn deserializing_corrupted_golden_data_does_not_panic(
+        changes in vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+        truncate_at in any::<prop::sample::Index>(),
+    ) {
+        let mut bytes = include_bytes!("../golden/dataset1.bin").to_vec();
+        for (idx, byte) in changes {
+            let idx = idx.index(bytes.len());
+            bytes[idx] = byte;
+        }
+        let _ = deserialize::<TestModel1>(&bytes);
+        let _ = deserialize::<TestModel1>(&bytes[..truncate_at.index(bytes.len())]);
+    }
+}
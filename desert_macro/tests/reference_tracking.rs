@@ -0,0 +1,110 @@
+use desert_core::{deserialize, serialize_to_byte_vec, SharedRef};
+use desert_macro::BinaryCodec;
+use std::rc::Rc;
+use std::sync::Arc;
+use test_r::test;
+
+test_r::enable!();
+
+mod desert_rust {
+    pub use desert_core::*;
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+struct Config {
+    name: String,
+    values: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+#[evolution(FieldAdded("priority", 0))]
+struct Task {
+    id: u64,
+    config: SharedRef<Rc<Config>>,
+    priority: i32,
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+struct Plan {
+    tasks: Vec<Task>,
+    default_config: SharedRef<Rc<Config>>,
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+enum Shared {
+    Single(SharedRef<Arc<Config>>),
+    Pair(SharedRef<Arc<Config>>, SharedRef<Arc<Config>>),
+}
+
+#[test]
+fn derived_struct_fields_share_references() {
+    let config = Rc::new(Config {
+        name: "default".to_string(),
+        values: vec![1, 2, 3],
+    });
+    let other = Rc::new(Config {
+        name: "other".to_string(),
+        values: vec![4],
+    });
+    let plan = Plan {
+        tasks: vec![
+            Task {
+                id: 1,
+                config: SharedRef(config.clone()),
+                priority: 10,
+            },
+            Task {
+                id: 2,
+                config: SharedRef(other.clone()),
+                priority: 20,
+            },
+            Task {
+                id: 3,
+                config: SharedRef(config.clone()),
+                priority: 30,
+            },
+        ],
+        default_config: SharedRef(config),
+    };
+
+    let data = serialize_to_byte_vec(&plan).unwrap();
+    let result: Plan = deserialize(&data).unwrap();
+
+    assert_eq!(result, plan);
+    assert!(Rc::ptr_eq(
+        &result.tasks[0].config.0,
+        &result.tasks[2].config.0
+    ));
+    assert!(Rc::ptr_eq(
+        &result.tasks[0].config.0,
+        &result.default_config.0
+    ));
+    assert!(!Rc::ptr_eq(
+        &result.tasks[0].config.0,
+        &result.tasks[1].config.0
+    ));
+}
+
+#[test]
+fn derived_enum_fields_share_references() {
+    let config = Arc::new(Config {
+        name: "shared".to_string(),
+        values: vec![],
+    });
+    let value = vec![
+        Shared::Single(SharedRef(config.clone())),
+        Shared::Pair(SharedRef(config.clone()), SharedRef(config)),
+    ];
+
+    let data = serialize_to_byte_vec(&value).unwrap();
+    let result: Vec<Shared> = deserialize(&data).unwrap();
+
+    assert_eq!(result, value);
+    match (&result[0], &result[1]) {
+        (Shared::Single(a), Shared::Pair(b, c)) => {
+            assert!(Arc::ptr_eq(&a.0, &b.0));
+            assert!(Arc::ptr_eq(&b.0, &c.0));
+        }
+        _ => panic!("unexpected result: {result:?}"),
+    }
+}
@@ -0,0 +1,138 @@
+use desert_core::{
+    deserialize_stream, deserialize_stream_with_options, serialize_to_byte_vec, BinarySerializer,
+    DeduplicatedString, Error, Options, SerializationContext,
+};
+use desert_macro::BinaryCodec;
+use test_r::test;
+
+test_r::enable!();
+
+mod desert_rust {
+    pub use desert_core::*;
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+enum LogEntry {
+    Create {
+        timestamp: u64,
+        name: String,
+    },
+    Update {
+        timestamp: u64,
+        name: String,
+        payload: Vec<u8>,
+    },
+    Interrupted,
+    Delete {
+        timestamp: u64,
+        name: String,
+    },
+}
+
+fn entries() -> Vec<LogEntry> {
+    vec![
+        LogEntry::Create {
+            timestamp: 1,
+            name: "a".to_string(),
+        },
+        LogEntry::Update {
+            timestamp: 2,
+            name: "a".to_string(),
+            payload: vec![1, 2, 3],
+        },
+        LogEntry::Interrupted,
+        LogEntry::Update {
+            timestamp: 4,
+            name: "a".to_string(),
+            payload: vec![],
+        },
+        LogEntry::Delete {
+            timestamp: 5,
+            name: "a".to_string(),
+        },
+    ]
+}
+
+fn log() -> Vec<u8> {
+    let mut data = Vec::new();
+    for entry in entries() {
+        data.extend(serialize_to_byte_vec(&entry).unwrap());
+    }
+    data
+}
+
+#[test]
+fn stream_yields_concatenated_values_in_order() {
+    let result = deserialize_stream::<LogEntry>(&log())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(result, entries());
+}
+
+#[test]
+fn empty_stream_yields_nothing() {
+    assert_eq!(deserialize_stream::<LogEntry>(&[]).count(), 0);
+}
+
+#[test]
+fn stream_stops_after_truncated_value() {
+    let data = log();
+    let mut stream = deserialize_stream::<LogEntry>(&data[..data.len() - 1]);
+    for expected in entries().into_iter().take(4) {
+        assert_eq!(stream.next().unwrap().unwrap(), expected);
+    }
+    assert!(matches!(
+        stream.next(),
+        Some(Err(Error::InputEndedUnexpectedly { offset })) if offset == data.len() - 1
+    ));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn stream_of_zero_sized_values_fails_instead_of_looping() {
+    let mut stream = deserialize_stream::<()>(&[1]);
+    assert!(matches!(
+        stream.next(),
+        Some(Err(Error::DeserializationFailure(_)))
+    ));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn deduplicated_strings_are_resolved_per_value_by_default() {
+    let mut data = serialize_to_byte_vec(&DeduplicatedString("hello".to_string())).unwrap();
+    data.extend(serialize_to_byte_vec(&DeduplicatedString("hello".to_string())).unwrap());
+
+    let result = deserialize_stream::<DeduplicatedString>(&data)
+        .map(|s| s.unwrap().0)
+        .collect::<Vec<_>>();
+    assert_eq!(result, vec!["hello".to_string(), "hello".to_string()]);
+}
+
+#[test]
+fn deduplicated_strings_can_be_shared_between_values() {
+    let mut context = SerializationContext::new(Vec::new());
+    DeduplicatedString("hello".to_string())
+        .serialize(&mut context)
+        .unwrap();
+    DeduplicatedString("hello".to_string())
+        .serialize(&mut context)
+        .unwrap();
+    let data = context.into_output();
+
+    let options = Options {
+        shared_stream_state: true,
+        ..Default::default()
+    };
+    let result = deserialize_stream_with_options::<DeduplicatedString>(&data, options)
+        .map(|s| s.unwrap().0)
+        .collect::<Vec<_>>();
+    assert_eq!(result, vec!["hello".to_string(), "hello".to_string()]);
+
+    let mut stream = deserialize_stream::<DeduplicatedString>(&data);
+    assert!(stream.next().unwrap().is_ok());
+    assert!(matches!(
+        stream.next(),
+        Some(Err(Error::InvalidStringId(_)))
+    ));
+}
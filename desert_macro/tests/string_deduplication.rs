@@ -123,3 +123,72 @@ fn default_string_serialization_does_not_break_data_evolution() {
         },
     );
 }
+
+#[derive(BinaryCodec)]
+struct StatusLog {
+    statuses: Vec<DeduplicatedString>,
+}
+
+impl StatusLog {
+    fn strings(&self) -> Vec<&str> {
+        self.statuses.iter().map(|s| s.0.as_str()).collect()
+    }
+}
+
+const STATUS_DICTIONARY: [&str; 3] = ["Pending", "Running", "Completed"];
+
+fn status_log() -> StatusLog {
+    StatusLog {
+        statuses: [
+            "Pending",
+            "Running",
+            "Suspended",
+            "Running",
+            "Completed",
+            "Suspended",
+        ]
+        .into_iter()
+        .map(|s| DeduplicatedString(s.to_string()))
+        .collect(),
+    }
+}
+
+#[test]
+fn string_dictionary_reduces_serialized_size() {
+    let mut context = SerializationContext::new(Vec::new());
+    status_log().serialize(&mut context).unwrap();
+    let without_dictionary = context.into_output();
+
+    let mut context = SerializationContext::new(Vec::new());
+    context
+        .register_string_dictionary(&STATUS_DICTIONARY)
+        .unwrap();
+    status_log().serialize(&mut context).unwrap();
+    let with_dictionary = context.into_output();
+
+    // Pending, Running and Completed are written as a single byte id instead of their length and content
+    assert_eq!(
+        without_dictionary.len() - with_dictionary.len(),
+        "Pending".len() + "Running".len() + "Completed".len()
+    );
+
+    let mut context = DeserializationContext::new(&with_dictionary);
+    context
+        .register_string_dictionary(&STATUS_DICTIONARY)
+        .unwrap();
+    let result = StatusLog::deserialize(&mut context).unwrap();
+    assert_eq!(result.strings(), status_log().strings());
+}
+
+#[test]
+fn string_dictionary_is_required_for_reading() {
+    let mut context = SerializationContext::new(Vec::new());
+    context
+        .register_string_dictionary(&STATUS_DICTIONARY)
+        .unwrap();
+    status_log().serialize(&mut context).unwrap();
+    let bytes = context.into_output();
+
+    let mut context = DeserializationContext::new(&bytes);
+    assert!(StatusLog::deserialize(&mut context).is_err());
+}
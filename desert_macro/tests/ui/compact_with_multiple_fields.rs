@@ -0,0 +1,10 @@
+use desert_macro::BinaryCodec;
+
+#[derive(BinaryCodec)]
+#[desert(compact)]
+enum Outcome {
+    Success(u32),
+    Failure(String, u32),
+}
+
+fn main() {}
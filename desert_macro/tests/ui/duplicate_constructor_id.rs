@@ -0,0 +1,10 @@
+use desert_macro::BinaryCodec;
+
+#[derive(BinaryCodec)]
+enum Duplicated {
+    #[desert(id = 1)]
+    First,
+    Second,
+}
+
+fn main() {}
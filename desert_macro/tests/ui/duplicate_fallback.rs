@@ -0,0 +1,12 @@
+use desert_macro::BinaryCodec;
+
+#[derive(BinaryCodec)]
+#[evolution(FieldRemoved("unused"))]
+enum Duplicated {
+    #[desert(fallback)]
+    First,
+    #[desert(fallback)]
+    Second,
+}
+
+fn main() {}
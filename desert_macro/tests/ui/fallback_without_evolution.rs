@@ -0,0 +1,10 @@
+use desert_macro::BinaryCodec;
+
+#[derive(BinaryCodec)]
+enum Unevolved {
+    Known(String),
+    #[desert(fallback)]
+    Unknown,
+}
+
+fn main() {}
@@ -0,0 +1,48 @@
+use desert_macro::BinaryCodec;
+use std::borrow::Cow;
+
+mod desert_rust {
+    pub use desert_core::*;
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+#[evolution(FieldAdded("tags", Vec::new()))]
+struct Labelled<'a, T>
+where
+    T: Clone,
+{
+    label: Cow<'a, str>,
+    value: T,
+    tags: Vec<Cow<'a, str>>,
+}
+
+#[derive(Debug, PartialEq, BinaryCodec)]
+enum Node<'a, K: Ord, V> {
+    Leaf(V),
+    Branch {
+        key: K,
+        name: Cow<'a, str>,
+        children: Vec<Node<'a, K, V>>,
+    },
+}
+
+fn main() {
+    let label = String::from("borrowed");
+    let value = Labelled {
+        label: Cow::Borrowed(label.as_str()),
+        value: 42u64,
+        tags: vec![Cow::Borrowed("a")],
+    };
+    let bytes = desert_core::serialize_to_byte_vec(&value).unwrap();
+    let result: Labelled<'static, u64> = desert_core::deserialize(&bytes).unwrap();
+    assert_eq!(result, value);
+
+    let node: Node<'_, u8, String> = Node::Branch {
+        key: 1,
+        name: Cow::Borrowed(label.as_str()),
+        children: vec![Node::Leaf("leaf".to_string())],
+    };
+    let bytes = desert_core::serialize_to_byte_vec(&node).unwrap();
+    let result: Node<'static, u8, String> = desert_core::deserialize(&bytes).unwrap();
+    assert_eq!(result, node);
+}
@@ -0,0 +1,11 @@
+use desert_macro::BinaryCodec;
+
+#[derive(BinaryCodec)]
+#[desert(no_evolution)]
+#[evolution(FieldAdded("second", 0))]
+struct Frozen {
+    first: String,
+    second: i32,
+}
+
+fn main() {}
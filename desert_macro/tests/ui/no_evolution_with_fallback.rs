@@ -0,0 +1,11 @@
+use desert_macro::BinaryCodec;
+
+#[derive(BinaryCodec)]
+#[desert(no_evolution)]
+enum Frozen {
+    First,
+    #[desert(fallback)]
+    Unknown,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use desert_macro::BinaryCodec;
+
+mod desert_rust {
+    pub use desert_core::*;
+}
+
+struct NotACodec;
+
+#[derive(BinaryCodec)]
+struct Record {
+    name: String,
+    inner: NotACodec,
+}
+
+#[derive(BinaryCodec)]
+enum Choice {
+    First(String),
+    Second { value: NotACodec },
+}
+
+fn main() {}
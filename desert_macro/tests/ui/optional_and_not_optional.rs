@@ -0,0 +1,12 @@
+use desert_macro::BinaryCodec;
+
+type MaybeString = Option<String>;
+
+#[derive(BinaryCodec)]
+struct Conflicting {
+    #[desert(optional)]
+    #[desert(not_optional)]
+    value: MaybeString,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use desert_macro::BinaryCodec;
+
+#[derive(BinaryCodec)]
+#[desert(repr_discriminant)]
+enum WithFields {
+    First,
+    Second(u32),
+}
+
+fn main() {}
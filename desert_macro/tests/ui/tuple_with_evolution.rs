@@ -0,0 +1,11 @@
+use desert_macro::BinaryCodec;
+
+#[derive(BinaryCodec)]
+#[desert(tuple)]
+#[evolution(FieldAdded("second", 0))]
+struct Flat {
+    first: String,
+    second: i32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use desert_macro::BinaryCodec;
+
+#[derive(BinaryCodec)]
+struct Misspelled {
+    #[desert(optinal)]
+    value: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,73 @@
+use desert_core::{deserialize, serialize_to_byte_vec, Deduplicated};
+use desert_macro::BinaryCodec;
+use test_r::test;
+
+test_r::enable!();
+
+mod desert_rust {
+    pub use desert_core::*;
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+struct WorkerId {
+    component_id: String,
+    worker_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, BinaryCodec)]
+#[evolution(FieldAdded("timestamp", 0))]
+struct OplogEntry {
+    worker_id: Deduplicated<WorkerId>,
+    index: u64,
+    timestamp: i64,
+}
+
+fn worker_id(name: &str) -> WorkerId {
+    WorkerId {
+        component_id: "a7a2a9c4-6e3f-4b0a-9d4e-0f6d2d7c1b11".to_string(),
+        worker_name: name.to_string(),
+    }
+}
+
+fn entries(worker_ids: &[WorkerId]) -> Vec<OplogEntry> {
+    worker_ids
+        .iter()
+        .enumerate()
+        .map(|(index, worker_id)| OplogEntry {
+            worker_id: Deduplicated(worker_id.clone()),
+            index: index as u64,
+            timestamp: 1_700_000_000 + index as i64,
+        })
+        .collect()
+}
+
+#[test]
+fn repeated_structs_roundtrip() {
+    let value = entries(&[
+        worker_id("worker-1"),
+        worker_id("worker-2"),
+        worker_id("worker-1"),
+        worker_id("worker-1"),
+        worker_id("worker-2"),
+    ]);
+    let data = serialize_to_byte_vec(&value).unwrap();
+    let result: Vec<OplogEntry> = deserialize(&data).unwrap();
+    assert_eq!(result, value);
+}
+
+#[test]
+fn repeated_structs_cost_only_a_back_reference() {
+    let single = serialize_to_byte_vec(&entries(&[worker_id("worker-1")])).unwrap();
+    let repeated = serialize_to_byte_vec(&entries(&vec![worker_id("worker-1"); 10])).unwrap();
+    let distinct = serialize_to_byte_vec(&entries(
+        &(0..10)
+            .map(|i| worker_id(&format!("worker-{i}")))
+            .collect::<Vec<_>>(),
+    ))
+    .unwrap();
+
+    let entry_without_worker_id = serialize_to_byte_vec(&(0u64, 0i64)).unwrap().len();
+    let per_repeated_entry = (repeated.len() - single.len()) / 9;
+    assert!(per_repeated_entry <= entry_without_worker_id + 8);
+    assert!(distinct.len() > repeated.len() + 9 * 40);
+}
@@ -0,0 +1,69 @@
+//! Compile test making sure `desert_core` builds without the `std` feature.
+//!
+//! Build it in isolation, otherwise feature unification with the rest of the workspace
+//! re-enables `std`:
+//!
+//! ```sh
+//! cargo build -p desert_no_std
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::time::Duration;
+use desert_core::{
+    deserialize, serialize, serialize_to_byte_vec, BinaryDeserializer, BinaryInput, BinaryOutput,
+    BinarySerializer, DeserializationContext, Result, SerializationContext,
+};
+
+pub struct Sample {
+    pub id: u64,
+    pub name: String,
+    pub values: Vec<i32>,
+    pub tags: BTreeSet<String>,
+    pub attributes: BTreeMap<String, f64>,
+    pub timeout: Option<Duration>,
+}
+
+impl BinarySerializer for Sample {
+    fn serialize<Output: BinaryOutput>(
+        &self,
+        context: &mut SerializationContext<Output>,
+    ) -> Result<()> {
+        context.write_var_u32(1);
+        self.id.serialize(context)?;
+        self.name.serialize(context)?;
+        self.values.serialize(context)?;
+        self.tags.serialize(context)?;
+        self.attributes.serialize(context)?;
+        self.timeout.serialize(context)
+    }
+}
+
+impl BinaryDeserializer for Sample {
+    fn deserialize(context: &mut DeserializationContext<'_>) -> Result<Self> {
+        let _version = context.read_var_u32()?;
+        Ok(Sample {
+            id: u64::deserialize(context)?,
+            name: String::deserialize(context)?,
+            values: Vec::deserialize(context)?,
+            tags: BTreeSet::deserialize(context)?,
+            attributes: BTreeMap::deserialize(context)?,
+            timeout: Option::deserialize(context)?,
+        })
+    }
+}
+
+pub fn roundtrip(sample: &Sample) -> Result<Sample> {
+    let bytes = serialize_to_byte_vec(sample)?;
+    deserialize(&bytes)
+}
+
+pub fn roundtrip_into_vec<T: BinarySerializer + BinaryDeserializer>(value: &T) -> Result<T> {
+    let bytes: Vec<u8> = serialize(value, Vec::new())?;
+    deserialize(&bytes)
+}
@@ -0,0 +1,42 @@
+#![no_main]
+
+// Deserializing arbitrary bytes must fail with an error instead of panicking. Run with
+// `cargo +nightly fuzz run deserialize` from the repository root.
+
+use desert_rust::{deserialize_with_options, BinaryCodec, DeduplicatedString, Options};
+use libfuzzer_sys::fuzz_target;
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+
+#[derive(BinaryCodec)]
+#[evolution(FieldMadeOptional("option"), FieldAdded("string", "default".to_string()), FieldAdded("set", HashSet::new()))]
+struct Model {
+    byte: i8,
+    long: i64,
+    double: f64,
+    boolean: bool,
+    string: String,
+    elements: Vec<Element>,
+    array: [u32; 4],
+    set: HashSet<String>,
+    either: Result<Duration, char>,
+    option: Option<BTreeMap<String, Element>>,
+}
+
+#[derive(BinaryCodec)]
+enum Element {
+    Named { name: DeduplicatedString },
+    Nested { inner: Box<Element>, tags: Vec<DeduplicatedString> },
+    Empty,
+}
+
+fuzz_target!(|data: &[u8]| {
+    // Without a depth limit deeply nested Elements overflow the stack
+    let options = Options {
+        max_depth: Some(128),
+        ..Default::default()
+    };
+    let _ = deserialize_with_options::<Model>(data, options.clone());
+    let _ = deserialize_with_options::<Vec<Element>>(data, options);
+    let _ = desert_rust::debug_dump(data);
+});